@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
+
 use anyhow::Context;
 use serde::Serialize;
-use tauri::State;
+use tauri::{Manager, State};
 
 use crate::core::cache_cleanup::{
     cleanup_git_cache_dirs, get_git_cache_cleanup_days as get_git_cache_cleanup_days_core,
@@ -9,18 +11,56 @@ use crate::core::cache_cleanup::{
     set_git_cache_ttl_secs as set_git_cache_ttl_secs_core,
 };
 use crate::core::central_repo::{ensure_central_repo, resolve_central_repo_path};
+use crate::core::credentials::{credential_store, CredentialInfo};
+use crate::core::forge_url::{
+    get_forge_hosts as get_forge_hosts_core, set_forge_hosts as set_forge_hosts_core, ForgeHost,
+};
+use crate::core::git_credentials::{
+    get_ssh_keys as get_ssh_keys_core, set_ssh_key as set_ssh_key_core, SshKeyBinding,
+};
 use crate::core::github_search::{search_github_repos, RepoSummary};
 use crate::core::installer::{
-    install_git_skill, install_git_skill_from_selection, install_local_skill,
-    install_local_skill_from_selection, list_git_skills, list_local_skills,
-    update_managed_skill_from_source, GitSkillCandidate, InstallResult, LocalSkillCandidate,
+    abort_skill_update as abort_skill_update_core, check_skill_update, check_updates,
+    confirm_skill_update as confirm_skill_update_core, install_archive_skill, install_git_skill,
+    install_git_skill_from_selection, install_local_skill, install_local_skill_from_selection,
+    list_archive_skills, list_git_skills, list_local_skills,
+    prepare_skill_update as prepare_skill_update_core, rollback_managed_skill,
+    set_skill_pin as set_skill_pin_core, update_managed_skill_from_source, GitSkillCandidate,
+    InstallResult, LocalChangesPolicy, LocalSkillCandidate, PendingUpdates, SkillUpdateCheck,
+    UpdateResult,
+};
+use crate::core::onboarding::{
+    apply_onboarding_plan as apply_onboarding_plan_core, build_onboarding_plan,
+    compare_onboarding_variants as compare_onboarding_variants_core, OnboardingApplyReport,
+    OnboardingDecision, OnboardingPlan, VariantComparison,
+};
+use crate::core::project_roots::{
+    add_project_root as add_project_root_core, get_project_roots as get_project_roots_core,
+    remove_project_root as remove_project_root_core, resolve_target_scope,
+};
+use crate::core::reconcile::{
+    apply_team_manifest as apply_team_manifest_core, plan_team_manifest as plan_team_manifest_core,
+    update_team_lock as update_team_lock_core, ReconcilePlan, ReconcileReport,
 };
-use crate::core::onboarding::{build_onboarding_plan, OnboardingPlan};
-use crate::core::skill_store::{SkillStore, SkillTargetRecord};
-use crate::core::sync_engine::{
-    copy_dir_recursive, sync_dir_for_tool_with_overwrite, sync_dir_hybrid, SyncMode,
+use crate::core::skill_diff::SkillDiff;
+use crate::core::skill_history::{remove_skill_history_dir, resolve_history_root};
+use crate::core::skill_manifest::SkillManifest;
+use crate::core::skill_merge::MergeReport;
+use crate::core::skill_store::{SkillStore, SkillTargetRecord, SkillWithTargets};
+use crate::core::sync_engine::{copy_dir_recursive, sync_dir_for_tool_with_mode, sync_dir_hybrid};
+use crate::core::target_health::{
+    repair_targets as repair_targets_core, verify_targets as verify_targets_core, RepairReport,
+    TargetCheck,
 };
-use crate::core::tool_adapters::{adapter_by_key, is_tool_installed, resolve_default_path};
+use crate::core::tool_adapters::{
+    adapter_by_key, adapters_sharing_skills_dir_in, custom_tools_path, is_tool_installed,
+    reload_custom_tools, resolve_default_path, resolve_scope_path, skill_format, TargetScope,
+};
+use crate::core::tool_sync_modes::{
+    get_tool_sync_modes as get_tool_sync_modes_core, preferred_sync_mode,
+    set_tool_sync_mode as set_tool_sync_mode_core,
+};
+use crate::core::watcher::restart_watcher;
 use uuid::Uuid;
 
 fn format_anyhow_error(err: anyhow::Error) -> String {
@@ -29,6 +69,7 @@ fn format_anyhow_error(err: anyhow::Error) -> String {
     if first.starts_with("MULTI_SKILLS|")
         || first.starts_with("TARGET_EXISTS|")
         || first.starts_with("TOOL_NOT_INSTALLED|")
+        || first.starts_with("LOCAL_CHANGES|")
     {
         return first;
     }
@@ -98,6 +139,8 @@ pub struct ToolInfoDto {
     pub label: String,
     pub installed: bool,
     pub skills_dir: String,
+    /// `skill` for SKILL.md folders, `rule` or `mdc` when skills are rendered on sync.
+    pub format: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -105,12 +148,18 @@ pub struct ToolStatusDto {
     pub tools: Vec<ToolInfoDto>,
     pub installed: Vec<String>,
     pub newly_installed: Vec<String>,
+    /// Config file for user-defined tools (`tools.toml`), whether or not it exists.
+    pub custom_tools_path: Option<String>,
+    /// Why `tools.toml` was rejected; its tools are left out until it is fixed.
+    pub custom_tools_error: Option<String>,
 }
 
 #[tauri::command]
 pub async fn get_tool_status(store: State<'_, SkillStore>) -> Result<ToolStatusDto, String> {
     let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
+        // Pick up edits to `tools.toml` without a restart.
+        let custom_tools_error = reload_custom_tools();
         let adapters = crate::core::tool_adapters::default_tool_adapters();
         let mut tools: Vec<ToolInfoDto> = Vec::new();
         let mut installed: Vec<String> = Vec::new();
@@ -124,6 +173,7 @@ pub async fn get_tool_status(store: State<'_, SkillStore>) -> Result<ToolStatusD
                 label: adapter.display_name.to_string(),
                 installed: ok,
                 skills_dir,
+                format: skill_format(adapter).as_key().to_string(),
             });
             if ok {
                 installed.push(key);
@@ -154,6 +204,8 @@ pub async fn get_tool_status(store: State<'_, SkillStore>) -> Result<ToolStatusD
             tools,
             installed,
             newly_installed,
+            custom_tools_path: custom_tools_path().map(|p| p.to_string_lossy().to_string()),
+            custom_tools_error,
         })
     })
     .await
@@ -173,6 +225,43 @@ pub async fn get_onboarding_plan(
         .map_err(format_anyhow_error)
 }
 
+#[tauri::command]
+pub async fn apply_onboarding_plan(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    decisions: Vec<OnboardingDecision>,
+) -> Result<OnboardingApplyReport, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        apply_onboarding_plan_core(&app, &store, &decisions)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn compare_onboarding_variants(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    group: String,
+    basePath: Option<String>,
+) -> Result<VariantComparison, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        compare_onboarding_variants_core(
+            &app,
+            &store,
+            &group,
+            basePath.as_deref().map(std::path::Path::new),
+        )
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
 #[tauri::command]
 pub async fn get_git_cache_cleanup_days(store: State<'_, SkillStore>) -> Result<i64, String> {
     let store = store.inner().clone();
@@ -229,6 +318,177 @@ pub async fn set_git_cache_ttl_secs(
         .map_err(format_anyhow_error)
 }
 
+#[tauri::command]
+pub async fn get_forge_hosts(store: State<'_, SkillStore>) -> Result<Vec<ForgeHost>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        Ok::<_, anyhow::Error>(get_forge_hosts_core(&store))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn set_forge_hosts(
+    store: State<'_, SkillStore>,
+    hosts: Vec<ForgeHost>,
+) -> Result<Vec<ForgeHost>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || set_forge_hosts_core(&store, hosts))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn get_project_roots(store: State<'_, SkillStore>) -> Result<Vec<String>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        Ok::<_, anyhow::Error>(get_project_roots_core(&store))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn add_project_root(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    path: String,
+) -> Result<Vec<String>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let roots = add_project_root_core(&store, &path)?;
+        // Project skills dirs are watched too.
+        if let Err(err) = restart_watcher(&app, &store) {
+            log::warn!("file watcher not restarted: {:#}", err);
+        }
+        Ok::<_, anyhow::Error>(roots)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn remove_project_root(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    path: String,
+) -> Result<Vec<String>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let roots = remove_project_root_core(&store, &path)?;
+        // Project skills dirs are watched too.
+        if let Err(err) = restart_watcher(&app, &store) {
+            log::warn!("file watcher not restarted: {:#}", err);
+        }
+        Ok::<_, anyhow::Error>(roots)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn get_tool_sync_modes(
+    store: State<'_, SkillStore>,
+) -> Result<BTreeMap<String, String>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        Ok::<_, anyhow::Error>(get_tool_sync_modes_core(&store))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn set_tool_sync_mode(
+    store: State<'_, SkillStore>,
+    tool: String,
+    mode: Option<String>,
+) -> Result<BTreeMap<String, String>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        set_tool_sync_mode_core(&store, &tool, mode.as_deref())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn get_ssh_keys(store: State<'_, SkillStore>) -> Result<Vec<SshKeyBinding>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || Ok::<_, anyhow::Error>(get_ssh_keys_core(&store)))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn set_ssh_key(
+    store: State<'_, SkillStore>,
+    source: String,
+    keyPath: Option<String>,
+) -> Result<Vec<SshKeyBinding>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        set_ssh_key_core(&store, &source, keyPath.as_deref())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn list_credentials(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+) -> Result<Vec<CredentialInfo>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        Ok::<_, anyhow::Error>(credential_store(&app)?.list(&store))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn set_credential(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    host: String,
+    token: String,
+    username: Option<String>,
+) -> Result<CredentialInfo, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        credential_store(&app)?.set(&store, &host, username.as_deref(), &token)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn delete_credential(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    host: String,
+) -> Result<(), String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || credential_store(&app)?.delete(&store, &host))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
 #[derive(Debug, Serialize)]
 pub struct InstallResultDto {
     pub skill_id: String,
@@ -321,6 +581,9 @@ pub async fn set_central_repo_path(
         }
 
         store.set_setting("central_repo_path", new_base.to_string_lossy().as_ref())?;
+        if let Err(err) = restart_watcher(&app, &store) {
+            log::warn!("file watcher not restarted: {:#}", err);
+        }
         Ok::<_, anyhow::Error>(new_base.to_string_lossy().to_string())
     })
     .await
@@ -348,10 +611,13 @@ pub async fn install_local(
 
 #[tauri::command]
 #[allow(non_snake_case)]
-pub async fn list_local_skills_cmd(basePath: String) -> Result<Vec<LocalSkillCandidate>, String> {
+pub async fn list_local_skills_cmd(
+    basePath: String,
+    maxDepth: Option<usize>,
+) -> Result<Vec<LocalSkillCandidate>, String> {
     tauri::async_runtime::spawn_blocking(move || {
         let path = std::path::PathBuf::from(basePath);
-        list_local_skills(&path)
+        list_local_skills(&path, maxDepth)
     })
     .await
     .map_err(|err| err.to_string())?
@@ -403,9 +669,10 @@ pub async fn list_git_skills_cmd(
     app: tauri::AppHandle,
     store: State<'_, SkillStore>,
     repoUrl: String,
+    maxDepth: Option<usize>,
 ) -> Result<Vec<GitSkillCandidate>, String> {
     let store = store.inner().clone();
-    tauri::async_runtime::spawn_blocking(move || list_git_skills(&app, &store, &repoUrl))
+    tauri::async_runtime::spawn_blocking(move || list_git_skills(&app, &store, &repoUrl, maxDepth))
         .await
         .map_err(|err| err.to_string())?
         .map_err(format_anyhow_error)
@@ -430,6 +697,96 @@ pub async fn install_git_selection(
     .map_err(format_anyhow_error)
 }
 
+#[tauri::command]
+pub async fn list_archive_skills_cmd(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    source: String,
+) -> Result<Vec<LocalSkillCandidate>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || list_archive_skills(&app, &store, &source))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn install_archive(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    source: String,
+    subpath: Option<String>,
+    name: Option<String>,
+) -> Result<InstallResultDto, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = install_archive_skill(&app, &store, &source, subpath.as_deref(), name)?;
+        Ok::<_, anyhow::Error>(to_install_dto(result))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn plan_team_manifest(
+    store: State<'_, SkillStore>,
+    manifestPath: String,
+) -> Result<ReconcilePlan, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        plan_team_manifest_core(&store, manifestPath.as_ref())
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn apply_team_manifest(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    manifestPath: String,
+    onLocalChanges: Option<LocalChangesPolicy>,
+) -> Result<ReconcileReport, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        apply_team_manifest_core(
+            &app,
+            &store,
+            manifestPath.as_ref(),
+            onLocalChanges.unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn update_team_lockfile(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    manifestPath: String,
+    onLocalChanges: Option<LocalChangesPolicy>,
+) -> Result<ReconcileReport, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        update_team_lock_core(
+            &app,
+            &store,
+            manifestPath.as_ref(),
+            onLocalChanges.unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
 #[derive(Debug, Serialize)]
 pub struct SyncResultDto {
     pub mode_used: String,
@@ -444,13 +801,7 @@ pub async fn sync_skill_dir(
     tauri::async_runtime::spawn_blocking(move || {
         let result = sync_dir_hybrid(source_path.as_ref(), target_path.as_ref())?;
         Ok::<_, anyhow::Error>(SyncResultDto {
-            mode_used: match result.mode_used {
-                SyncMode::Auto => "auto",
-                SyncMode::Symlink => "symlink",
-                SyncMode::Junction => "junction",
-                SyncMode::Copy => "copy",
-            }
-            .to_string(),
+            mode_used: result.mode_used.as_key().to_string(),
             target_path: result.target_path.to_string_lossy().to_string(),
         })
     })
@@ -468,6 +819,7 @@ pub async fn sync_skill_to_tool(
     tool: String,
     name: String,
     overwrite: Option<bool>,
+    scope: Option<String>,
 ) -> Result<SyncResultDto, String> {
     let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
@@ -475,22 +827,30 @@ pub async fn sync_skill_to_tool(
         if !is_tool_installed(&adapter)? {
             anyhow::bail!("TOOL_NOT_INSTALLED|{}", adapter.id.as_key());
         }
-        let tool_root = resolve_default_path(&adapter)?;
+        let scope = resolve_target_scope(&store, scope.as_deref())?;
+        let tool_root = resolve_scope_path(&adapter, &scope)?;
         let target = tool_root.join(&name);
         let overwrite = overwrite.unwrap_or(false);
-        let result =
-            sync_dir_for_tool_with_overwrite(&tool, sourcePath.as_ref(), &target, overwrite)
-                .map_err(|err| {
-                    let msg = err.to_string();
-                    if msg.contains("target already exists") {
-                        anyhow::anyhow!("TARGET_EXISTS|{}", target.to_string_lossy())
-                    } else {
-                        anyhow::anyhow!(msg)
-                    }
-                })?;
-
-        // Some tools share the same global skills directory; keep DB records consistent across them.
-        let group = crate::core::tool_adapters::adapters_sharing_skills_dir(&adapter);
+        let preferred = preferred_sync_mode(&store, &tool);
+        let result = sync_dir_for_tool_with_mode(
+            &store,
+            &tool,
+            sourcePath.as_ref(),
+            &target,
+            overwrite,
+            preferred,
+        )
+        .map_err(|err| {
+            let msg = err.to_string();
+            if msg.contains("target already exists") {
+                anyhow::anyhow!("TARGET_EXISTS|{}", target.to_string_lossy())
+            } else {
+                anyhow::anyhow!(msg)
+            }
+        })?;
+
+        // Some tools share the same skills directory; keep DB records consistent across them.
+        let group = adapters_sharing_skills_dir_in(&adapter, &scope);
         for a in group {
             if !is_tool_installed(&a)? {
                 continue;
@@ -499,29 +859,19 @@ pub async fn sync_skill_to_tool(
                 id: Uuid::new_v4().to_string(),
                 skill_id: skillId.clone(),
                 tool: a.id.as_key().to_string(),
+                scope: scope.as_key(),
                 target_path: result.target_path.to_string_lossy().to_string(),
-                mode: match result.mode_used {
-                    SyncMode::Auto => "auto",
-                    SyncMode::Symlink => "symlink",
-                    SyncMode::Junction => "junction",
-                    SyncMode::Copy => "copy",
-                }
-                .to_string(),
+                mode: result.mode_used.as_key().to_string(),
                 status: "ok".to_string(),
                 last_error: None,
                 synced_at: Some(now_ms()),
+                rendered_hash: result.rendered_hash.clone(),
             };
             store.upsert_skill_target(&record)?;
         }
 
         Ok::<_, anyhow::Error>(SyncResultDto {
-            mode_used: match result.mode_used {
-                SyncMode::Auto => "auto",
-                SyncMode::Symlink => "symlink",
-                SyncMode::Junction => "junction",
-                SyncMode::Copy => "copy",
-            }
-            .to_string(),
+            mode_used: result.mode_used.as_key().to_string(),
             target_path: result.target_path.to_string_lossy().to_string(),
         })
     })
@@ -536,12 +886,16 @@ pub async fn unsync_skill_from_tool(
     store: State<'_, SkillStore>,
     skillId: String,
     tool: String,
+    scope: Option<String>,
 ) -> Result<(), String> {
     let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
-        // Some tools share the same global skills directory; unsync should update all of them.
+        // Unregistered project roots are fine here: targets outlive their root's registration.
+        let scope = TargetScope::from_key(scope.as_deref().unwrap_or_default());
+        let scope_key = scope.as_key();
+        // Some tools share the same skills directory; unsync should update all of them.
         let group_tool_keys: Vec<String> = if let Some(adapter) = adapter_by_key(&tool) {
-            let group = crate::core::tool_adapters::adapters_sharing_skills_dir(&adapter);
+            let group = adapters_sharing_skills_dir_in(&adapter, &scope);
             // If none of the group tools are installed, do nothing (treat as already not effective).
             let mut any_installed = false;
             for a in &group {
@@ -564,12 +918,12 @@ pub async fn unsync_skill_from_tool(
         // Remove filesystem target once (shared dir => shared target path).
         let mut removed = false;
         for k in &group_tool_keys {
-            if let Some(target) = store.get_skill_target(&skillId, k)? {
+            if let Some(target) = store.get_skill_target(&skillId, k, &scope_key)? {
                 if !removed {
                     remove_path_any(&target.target_path).map_err(anyhow::Error::msg)?;
                     removed = true;
                 }
-                store.delete_skill_target(&skillId, k)?;
+                store.delete_skill_target(&skillId, k, &scope_key)?;
             }
         }
 
@@ -580,6 +934,28 @@ pub async fn unsync_skill_from_tool(
     .map_err(format_anyhow_error)
 }
 
+#[tauri::command]
+pub async fn verify_targets(store: State<'_, SkillStore>) -> Result<Vec<TargetCheck>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || verify_targets_core(&store))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn repair_targets(
+    store: State<'_, SkillStore>,
+    targetIds: Option<Vec<String>>,
+) -> Result<RepairReport, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || repair_targets_core(&store, targetIds.as_deref()))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(format_anyhow_error)
+}
+
 #[derive(Debug, Serialize)]
 pub struct UpdateResultDto {
     pub skill_id: String,
@@ -587,6 +963,20 @@ pub struct UpdateResultDto {
     pub content_hash: Option<String>,
     pub source_revision: Option<String>,
     pub updated_targets: Vec<String>,
+    pub merge: Option<MergeReport>,
+}
+
+impl From<UpdateResult> for UpdateResultDto {
+    fn from(res: UpdateResult) -> Self {
+        Self {
+            skill_id: res.skill_id,
+            name: res.name,
+            content_hash: res.content_hash,
+            source_revision: res.source_revision,
+            updated_targets: res.updated_targets,
+            merge: res.merge,
+        }
+    }
 }
 
 #[tauri::command]
@@ -595,30 +985,209 @@ pub async fn update_managed_skill(
     app: tauri::AppHandle,
     store: State<'_, SkillStore>,
     skillId: String,
+    onLocalChanges: Option<LocalChangesPolicy>,
 ) -> Result<UpdateResultDto, String> {
     let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
-        let res = update_managed_skill_from_source(&app, &store, &skillId)?;
-        Ok::<_, anyhow::Error>(UpdateResultDto {
-            skill_id: res.skill_id,
-            name: res.name,
-            content_hash: res.content_hash,
-            source_revision: res.source_revision,
-            updated_targets: res.updated_targets,
-        })
+        update_managed_skill_from_source(&app, &store, &skillId, onLocalChanges.unwrap_or_default())
+            .map(UpdateResultDto::from)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreparedUpdateDto {
+    pub update_id: String,
+    pub skill_id: String,
+    pub name: String,
+    pub source_revision: Option<String>,
+    pub diff: SkillDiff,
+    pub local_changes: bool,
+}
+
+/// Pin a git skill to a tag/commit (`pin: null` unpins) and move it to that revision.
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn set_skill_pin(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    skillId: String,
+    pin: Option<String>,
+    onLocalChanges: Option<LocalChangesPolicy>,
+) -> Result<UpdateResultDto, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        set_skill_pin_core(
+            &app,
+            &store,
+            &skillId,
+            pin.as_deref(),
+            onLocalChanges.unwrap_or_default(),
+        )
+        .map(UpdateResultDto::from)
     })
     .await
     .map_err(|err| err.to_string())?
     .map_err(format_anyhow_error)
 }
 
+/// Stage an update and return its diff; the UI then calls `confirm_skill_update`
+/// or `abort_skill_update` with the returned `update_id`.
 #[tauri::command]
-pub async fn search_github(query: String, limit: Option<u32>) -> Result<Vec<RepoSummary>, String> {
+#[allow(non_snake_case)]
+pub async fn prepare_skill_update(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    skillId: String,
+) -> Result<PreparedUpdateDto, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let prepared = prepare_skill_update_core(&app, &store, &skillId)?;
+        let dto = PreparedUpdateDto {
+            update_id: prepared.id.clone(),
+            skill_id: prepared.skill_id.clone(),
+            name: prepared.name.clone(),
+            source_revision: prepared.source_revision.clone(),
+            diff: prepared.diff.clone(),
+            local_changes: prepared.local_changes,
+        };
+        app.state::<PendingUpdates>().insert(prepared);
+        Ok::<_, anyhow::Error>(dto)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn confirm_skill_update(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    updateId: String,
+    onLocalChanges: Option<LocalChangesPolicy>,
+) -> Result<UpdateResultDto, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let prepared = app
+            .state::<PendingUpdates>()
+            .take(&updateId)
+            .ok_or_else(|| anyhow::anyhow!("no pending update: {}", updateId))?;
+        confirm_skill_update_core(&app, &store, prepared, onLocalChanges.unwrap_or_default())
+            .map(UpdateResultDto::from)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn abort_skill_update(app: tauri::AppHandle, updateId: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        match app.state::<PendingUpdates>().take(&updateId) {
+            Some(prepared) => abort_skill_update_core(prepared),
+            None => Ok(()),
+        }
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[derive(Debug, Serialize)]
+pub struct SkillSnapshotDto {
+    pub id: String,
+    pub content_hash: Option<String>,
+    pub source_revision: Option<String>,
+    pub created_at: i64,
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn list_skill_history(
+    store: State<'_, SkillStore>,
+    skillId: String,
+) -> Result<Vec<SkillSnapshotDto>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let snapshots = store.list_skill_snapshots(&skillId)?;
+        Ok::<_, anyhow::Error>(
+            snapshots
+                .into_iter()
+                .map(|s| SkillSnapshotDto {
+                    id: s.id,
+                    content_hash: s.content_hash,
+                    source_revision: s.source_revision,
+                    created_at: s.created_at,
+                })
+                .collect(),
+        )
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn rollback_skill(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    skillId: String,
+    snapshotId: String,
+) -> Result<UpdateResultDto, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        rollback_managed_skill(&app, &store, &skillId, &snapshotId).map(UpdateResultDto::from)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+/// Dry-run of `update_managed_skill`: fetches upstream and reports per-skill status
+/// for every git skill (or just `skillId`) without changing anything.
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn check_skill_updates(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    skillId: Option<String>,
+) -> Result<Vec<SkillUpdateCheck>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || match skillId {
+        Some(skill_id) => {
+            let record = store
+                .get_skill_by_id(&skill_id)?
+                .ok_or_else(|| anyhow::anyhow!("skill not found"))?;
+            Ok::<_, anyhow::Error>(vec![check_skill_update(&app, &store, &record)])
+        }
+        None => check_updates(&app, &store),
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
+}
+
+#[tauri::command]
+pub async fn search_github(
+    app: tauri::AppHandle,
+    store: State<'_, SkillStore>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<RepoSummary>, String> {
+    let store = store.inner().clone();
     let limit = limit.unwrap_or(10) as usize;
-    tauri::async_runtime::spawn_blocking(move || search_github_repos(&query, limit))
-        .await
-        .map_err(|err| err.to_string())?
-        .map_err(format_anyhow_error)
+    tauri::async_runtime::spawn_blocking(move || {
+        let token = credential_store(&app)?.token_for_host(&store, "github.com");
+        search_github_repos(&query, limit, token.as_ref().map(|t| t.token.as_str()))
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(format_anyhow_error)
 }
 
 #[tauri::command]
@@ -632,6 +1201,7 @@ pub async fn import_existing_skill(
     let store = store.inner().clone();
     tauri::async_runtime::spawn_blocking(move || {
         let result = install_local_skill(&app, &store, sourcePath.as_ref(), name)?;
+        store.mark_discovered_imported(&sourcePath, &result.skill_id)?;
         Ok::<_, anyhow::Error>(to_install_dto(result))
     })
     .await
@@ -645,19 +1215,25 @@ pub struct ManagedSkillDto {
     pub name: String,
     pub source_type: String,
     pub source_ref: Option<String>,
+    pub source_revision: Option<String>,
+    pub source_pin: Option<String>,
     pub central_path: String,
     pub created_at: i64,
     pub updated_at: i64,
     pub last_sync_at: Option<i64>,
     pub status: String,
     pub targets: Vec<SkillTargetDto>,
+    /// SKILL.md frontmatter; `None` when it could not be parsed.
+    pub manifest: Option<SkillManifest>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct SkillTargetDto {
     pub tool: String,
+    pub scope: String,
     pub mode: String,
     pub status: String,
+    pub last_error: Option<String>,
     pub target_path: String,
     pub synced_at: Option<i64>,
 }
@@ -670,6 +1246,7 @@ pub fn get_managed_skills(store: State<'_, SkillStore>) -> Result<Vec<ManagedSki
 #[tauri::command]
 #[allow(non_snake_case)]
 pub async fn delete_managed_skill(
+    app: tauri::AppHandle,
     store: State<'_, SkillStore>,
     skillId: String,
 ) -> Result<(), String> {
@@ -698,6 +1275,13 @@ pub async fn delete_managed_skill(
             store.delete_skill(&skillId)?;
         }
 
+        // Snapshot rows cascade with the skill; drop their files as well (best effort).
+        if let Err(err) = resolve_history_root(&app)
+            .and_then(|history_root| remove_skill_history_dir(&history_root, &skillId))
+        {
+            log::warn!("[delete_managed_skill] history cleanup failed: {:#}", err);
+        }
+
         if !remove_failures.is_empty() {
             anyhow::bail!(
                 "已删除托管记录，但清理部分工具目录失败：\n- {}",
@@ -753,34 +1337,43 @@ fn now_ms() -> i64 {
 }
 
 fn get_managed_skills_impl(store: &SkillStore) -> Result<Vec<ManagedSkillDto>, String> {
-    let skills = store.list_skills().map_err(|err| err.to_string())?;
+    let skills = store
+        .list_skills_with_targets()
+        .map_err(|err| err.to_string())?;
+    let mut manifests = store
+        .list_skill_manifests()
+        .map_err(|err| err.to_string())?;
     Ok(skills
         .into_iter()
-        .map(|skill| {
-            let targets = store
-                .list_skill_targets(&skill.id)
-                .unwrap_or_default()
+        .map(|SkillWithTargets { skill, targets }| {
+            let targets = targets
                 .into_iter()
                 .map(|target| SkillTargetDto {
                     tool: target.tool,
+                    scope: target.scope,
                     mode: target.mode,
                     status: target.status,
+                    last_error: target.last_error,
                     target_path: target.target_path,
                     synced_at: target.synced_at,
                 })
                 .collect();
 
+            let manifest = manifests.remove(&skill.id);
             ManagedSkillDto {
                 id: skill.id,
                 name: skill.name,
                 source_type: skill.source_type,
                 source_ref: skill.source_ref,
+                source_revision: skill.source_revision,
+                source_pin: skill.source_pin,
                 central_path: skill.central_path,
                 created_at: skill.created_at,
                 updated_at: skill.updated_at,
                 last_sync_at: skill.last_sync_at,
                 status: skill.status,
                 targets,
+                manifest,
             }
         })
         .collect())
@@ -1,12 +1,5 @@
 use super::*;
-use crate::core::skill_store::SkillRecord;
-
-fn make_store() -> (tempfile::TempDir, SkillStore) {
-    let dir = tempfile::tempdir().expect("tempdir");
-    let store = SkillStore::new(dir.path().join("test.db"));
-    store.ensure_schema().expect("ensure_schema");
-    (dir, store)
-}
+use crate::core::test_fixtures::{make_skill, make_store};
 
 #[test]
 fn format_anyhow_error_passthrough_prefixes() {
@@ -77,31 +70,20 @@ fn remove_path_any_removes_symlink_only() {
 #[test]
 fn get_managed_skills_impl_maps_targets() {
     let (_dir, store) = make_store();
-    let skill = SkillRecord {
-        id: "s1".to_string(),
-        name: "S1".to_string(),
-        source_type: "local".to_string(),
-        source_ref: Some("/tmp/src".to_string()),
-        source_revision: None,
-        central_path: "/tmp/central".to_string(),
-        content_hash: None,
-        created_at: 1,
-        updated_at: 2,
-        last_sync_at: None,
-        last_seen_at: 1,
-        status: "ok".to_string(),
-    };
+    let skill = make_skill("s1", "S1", "/tmp/central", 2);
     store.upsert_skill(&skill).unwrap();
 
     let target = SkillTargetRecord {
         id: "t1".to_string(),
         skill_id: "s1".to_string(),
         tool: "cursor".to_string(),
+        scope: "global".to_string(),
         target_path: "/tmp/target".to_string(),
         mode: "copy".to_string(),
         status: "ok".to_string(),
         last_error: None,
         synced_at: None,
+        rendered_hash: None,
     };
     store.upsert_skill_target(&target).unwrap();
 
@@ -0,0 +1,344 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use reqwest::blocking::Client;
+use sha2::Digest;
+
+/// Largest archive we download or read.
+pub const MAX_ARCHIVE_BYTES: u64 = 100 * 1024 * 1024;
+/// Largest total size an archive may expand to.
+pub const MAX_EXTRACTED_BYTES: u64 = 256 * 1024 * 1024;
+/// Most entries (files and directories) an archive may contain.
+pub const MAX_ARCHIVE_ENTRIES: usize = 10_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    TarGz,
+    Tar,
+    /// A bare `SKILL.md` rather than an archive.
+    SkillMd,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Limits {
+    archive_bytes: u64,
+    extracted_bytes: u64,
+    entries: usize,
+}
+
+const DEFAULT_LIMITS: Limits = Limits {
+    archive_bytes: MAX_ARCHIVE_BYTES,
+    extracted_bytes: MAX_EXTRACTED_BYTES,
+    entries: MAX_ARCHIVE_ENTRIES,
+};
+
+pub struct FetchedArchive {
+    /// Directory holding the skill content; a lone top-level folder is unwrapped.
+    pub root: PathBuf,
+    /// `sha256:<hex>` of the downloaded bytes.
+    pub checksum: String,
+}
+
+/// Download (`http(s)://`) or read (local path or `file://`) `source` and extract it
+/// into `work_dir`, which must be empty or missing. `token` is sent as a bearer token.
+pub fn fetch_archive(source: &str, work_dir: &Path, token: Option<&str>) -> Result<FetchedArchive> {
+    fetch_archive_with_limits(source, work_dir, token, &DEFAULT_LIMITS)
+}
+
+fn fetch_archive_with_limits(
+    source: &str,
+    work_dir: &Path,
+    token: Option<&str>,
+    limits: &Limits,
+) -> Result<FetchedArchive> {
+    std::fs::create_dir_all(work_dir)
+        .with_context(|| format!("failed to create dir {:?}", work_dir))?;
+    let download_path = work_dir.join("download");
+    let checksum = if is_http_url(source) {
+        download(source, &download_path, token, limits)?
+    } else {
+        copy_local(&local_path(source), &download_path, limits)?
+    };
+
+    let mut file =
+        File::open(&download_path).with_context(|| format!("open {:?}", download_path))?;
+    let kind = match kind_from_name(source) {
+        Some(kind) => kind,
+        None => sniff_kind(&mut file)?,
+    };
+
+    let content_dir = work_dir.join("content");
+    std::fs::create_dir_all(&content_dir)
+        .with_context(|| format!("failed to create dir {:?}", content_dir))?;
+    extract(file, kind, &content_dir, limits)?;
+    let _ = std::fs::remove_file(&download_path);
+
+    Ok(FetchedArchive {
+        root: unwrap_single_dir(&content_dir),
+        checksum,
+    })
+}
+
+pub fn is_http_url(source: &str) -> bool {
+    let lower = source.trim().to_ascii_lowercase();
+    lower.starts_with("https://") || lower.starts_with("http://")
+}
+
+fn local_path(source: &str) -> PathBuf {
+    let source = source.trim();
+    PathBuf::from(source.strip_prefix("file://").unwrap_or(source))
+}
+
+fn download(url: &str, dest: &Path, token: Option<&str>, limits: &Limits) -> Result<String> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(300))
+        .build()
+        .context("build http client")?;
+    let mut request = client.get(url.trim()).header("User-Agent", "skills-hub");
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .with_context(|| format!("download {} failed", url))?
+        .error_for_status()
+        .with_context(|| format!("download {} returned error", url))?;
+    if response
+        .content_length()
+        .is_some_and(|len| len > limits.archive_bytes)
+    {
+        anyhow::bail!(
+            "archive is larger than {}",
+            format_mib(limits.archive_bytes)
+        );
+    }
+    write_hashed(response, dest, limits)
+}
+
+fn copy_local(source: &Path, dest: &Path, limits: &Limits) -> Result<String> {
+    if !source.is_file() {
+        anyhow::bail!("archive not found: {:?}", source);
+    }
+    let file = File::open(source).with_context(|| format!("open {:?}", source))?;
+    write_hashed(file, dest, limits)
+}
+
+/// Copy at most `limits.archive_bytes` from `reader` to `dest`, returning its checksum.
+fn write_hashed(reader: impl Read, dest: &Path, limits: &Limits) -> Result<String> {
+    let mut out = File::create(dest).with_context(|| format!("create {:?}", dest))?;
+    let mut reader = reader.take(limits.archive_bytes + 1);
+    let mut hasher = sha2::Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    let mut total = 0u64;
+    loop {
+        let n = reader.read(&mut buf).context("read archive")?;
+        if n == 0 {
+            break;
+        }
+        total += n as u64;
+        if total > limits.archive_bytes {
+            anyhow::bail!(
+                "archive is larger than {}",
+                format_mib(limits.archive_bytes)
+            );
+        }
+        hasher.update(&buf[..n]);
+        out.write_all(&buf[..n])
+            .with_context(|| format!("write {:?}", dest))?;
+    }
+    Ok(format!("sha256:{}", hex::encode(hasher.finalize())))
+}
+
+fn kind_from_name(source: &str) -> Option<ArchiveKind> {
+    let path = source.split(['?', '#']).next().unwrap_or_default();
+    let name = path.rsplit(['/', '\\']).next()?.to_ascii_lowercase();
+    if name.ends_with(".zip") {
+        Some(ArchiveKind::Zip)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(ArchiveKind::TarGz)
+    } else if name.ends_with(".tar") {
+        Some(ArchiveKind::Tar)
+    } else if name.ends_with(".md") {
+        Some(ArchiveKind::SkillMd)
+    } else {
+        None
+    }
+}
+
+/// Detect the format from magic bytes, for URLs without a telling file name.
+fn sniff_kind(file: &mut File) -> Result<ArchiveKind> {
+    let mut head = [0u8; 512];
+    let mut len = 0;
+    while len < head.len() {
+        let n = file.read(&mut head[len..]).context("read archive")?;
+        if n == 0 {
+            break;
+        }
+        len += n;
+    }
+    file.seek(SeekFrom::Start(0)).context("rewind archive")?;
+    let head = &head[..len];
+
+    if head.starts_with(b"PK\x03\x04") || head.starts_with(b"PK\x05\x06") {
+        Ok(ArchiveKind::Zip)
+    } else if head.starts_with(&[0x1f, 0x8b]) {
+        Ok(ArchiveKind::TarGz)
+    } else if head.len() >= 262 && &head[257..262] == b"ustar" {
+        Ok(ArchiveKind::Tar)
+    } else if head.starts_with(b"---") {
+        Ok(ArchiveKind::SkillMd)
+    } else {
+        anyhow::bail!("unsupported archive format (expected .zip, .tar.gz, .tar or SKILL.md)")
+    }
+}
+
+fn extract(file: File, kind: ArchiveKind, dest: &Path, limits: &Limits) -> Result<()> {
+    let mut budget = limits.extracted_bytes;
+    match kind {
+        ArchiveKind::Zip => extract_zip(file, dest, limits, &mut budget),
+        ArchiveKind::TarGz => extract_tar(
+            flate2::read::GzDecoder::new(file),
+            dest,
+            limits,
+            &mut budget,
+        ),
+        ArchiveKind::Tar => extract_tar(file, dest, limits, &mut budget),
+        ArchiveKind::SkillMd => write_limited(file, &dest.join("SKILL.md"), limits, &mut budget),
+    }
+}
+
+fn extract_zip(file: File, dest: &Path, limits: &Limits, budget: &mut u64) -> Result<()> {
+    let mut zip = zip::ZipArchive::new(file).context("open zip archive")?;
+    if zip.len() > limits.entries {
+        anyhow::bail!("archive has more than {} entries", limits.entries);
+    }
+    for i in 0..zip.len() {
+        let entry = zip.by_index(i).context("read zip entry")?;
+        let rel = safe_relative_path(Path::new(entry.name()))?;
+        if is_junk(&rel) {
+            continue;
+        }
+        if entry.is_symlink() {
+            anyhow::bail!("archive contains a symlink: {}", entry.name());
+        }
+        let target = dest.join(&rel);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target)
+                .with_context(|| format!("failed to create dir {:?}", target))?;
+            continue;
+        }
+        write_limited(entry, &target, limits, budget)?;
+    }
+    Ok(())
+}
+
+fn extract_tar(reader: impl Read, dest: &Path, limits: &Limits, budget: &mut u64) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    let mut count = 0usize;
+    for entry in archive.entries().context("read tar archive")? {
+        let entry = entry.context("read tar entry")?;
+        count += 1;
+        if count > limits.entries {
+            anyhow::bail!("archive has more than {} entries", limits.entries);
+        }
+        let raw = entry.path().context("read tar entry path")?.into_owned();
+        let rel = safe_relative_path(&raw)?;
+        if is_junk(&rel) {
+            continue;
+        }
+        let target = dest.join(&rel);
+        let kind = entry.header().entry_type();
+        if kind.is_dir() {
+            std::fs::create_dir_all(&target)
+                .with_context(|| format!("failed to create dir {:?}", target))?;
+        } else if kind.is_file() || kind.is_contiguous() {
+            write_limited(entry, &target, limits, budget)?;
+        } else if kind.is_symlink() || kind.is_hard_link() {
+            anyhow::bail!("archive contains a link: {:?}", raw);
+        } else if kind.is_pax_global_extensions() {
+            continue;
+        } else {
+            anyhow::bail!("archive contains an unsupported entry: {:?}", raw);
+        }
+    }
+    Ok(())
+}
+
+/// Reject absolute paths and `..` so no entry lands outside the extraction dir.
+fn safe_relative_path(path: &Path) -> Result<PathBuf> {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => out.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                anyhow::bail!("archive entry escapes the extraction dir: {:?}", path);
+            }
+        }
+    }
+    if out.as_os_str().is_empty() {
+        anyhow::bail!("archive entry has an empty path");
+    }
+    Ok(out)
+}
+
+/// Metadata that macOS Finder adds to zips it creates.
+fn is_junk(rel: &Path) -> bool {
+    rel.components().any(|c| {
+        let part = c.as_os_str();
+        part == "__MACOSX" || part == ".DS_Store"
+    })
+}
+
+/// Write `reader` to `target`, charging the bytes actually written against `budget`
+/// so a lying size header cannot get past the cap.
+fn write_limited(
+    reader: impl Read,
+    target: &Path,
+    limits: &Limits,
+    budget: &mut u64,
+) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create dir {:?}", parent))?;
+    }
+    let mut out = File::create(target).with_context(|| format!("create {:?}", target))?;
+    let written = std::io::copy(&mut reader.take(*budget + 1), &mut out)
+        .with_context(|| format!("extract {:?}", target))?;
+    if written > *budget {
+        anyhow::bail!(
+            "archive expands to more than {}",
+            format_mib(limits.extracted_bytes)
+        );
+    }
+    *budget -= written;
+    Ok(())
+}
+
+/// Skill root inside the extracted content: archives usually wrap everything in one
+/// folder (`repo-main/`), so descend into it when it is the only entry and holds a
+/// `SKILL.md` or a `skills/` dir.
+fn unwrap_single_dir(content_dir: &Path) -> PathBuf {
+    let entries: Vec<_> = match std::fs::read_dir(content_dir) {
+        Ok(rd) => rd.flatten().collect(),
+        Err(_) => return content_dir.to_path_buf(),
+    };
+    match entries.as_slice() {
+        [only] if only.path().join("SKILL.md").is_file() || only.path().join("skills").is_dir() => {
+            only.path()
+        }
+        _ => content_dir.to_path_buf(),
+    }
+}
+
+fn format_mib(bytes: u64) -> String {
+    format!("{} MiB", bytes / (1024 * 1024))
+}
+
+#[cfg(test)]
+#[path = "tests/archive.rs"]
+mod tests;
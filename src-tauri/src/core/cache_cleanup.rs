@@ -8,7 +8,9 @@ use tauri::Manager;
 use super::skill_store::SkillStore;
 
 const CACHE_DIR_NAME: &str = "skills-hub-git-cache";
-const CACHE_META_FILE: &str = ".skills-hub-cache.json";
+/// Fetch bookkeeping written at the root of each cached checkout. It is not skill
+/// content: copies skip it and content hashes ignore it.
+pub const CACHE_META_FILE: &str = ".skills-hub-cache.json";
 pub const GIT_CACHE_CLEANUP_DAYS_KEY: &str = "git_cache_cleanup_days";
 pub const DEFAULT_GIT_CACHE_CLEANUP_DAYS: i64 = 30;
 const MAX_GIT_CACHE_CLEANUP_DAYS: i64 = 3650;
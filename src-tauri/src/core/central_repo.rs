@@ -32,6 +32,34 @@ pub fn ensure_central_repo(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Reject skill names that would not land directly inside the central repo: empty,
+/// padded with whitespace, `.`/`..`, holding a path separator or control characters,
+/// or absolute/prefixed on this platform.
+pub fn validate_skill_name(name: &str) -> Result<()> {
+    let mut components = Path::new(name).components();
+    let single_normal = matches!(
+        (components.next(), components.next()),
+        (Some(std::path::Component::Normal(_)), None)
+    );
+    if name.is_empty()
+        || name.trim() != name
+        || name == "."
+        || name == ".."
+        || name.contains(['/', '\\'])
+        || name.chars().any(char::is_control)
+        || !single_normal
+    {
+        anyhow::bail!("invalid skill name {:?}", name);
+    }
+    Ok(())
+}
+
+/// The folder for skill `name` inside `central_dir`, after `validate_skill_name`.
+pub fn skill_dir_in(central_dir: &Path, name: &str) -> Result<PathBuf> {
+    validate_skill_name(name)?;
+    Ok(central_dir.join(name))
+}
+
 #[cfg(test)]
 #[path = "tests/central_repo.rs"]
 mod tests;
@@ -4,9 +4,17 @@ use anyhow::{Context, Result};
 use sha2::{Digest, Sha256};
 use walkdir::{DirEntry, WalkDir};
 
-const IGNORE_NAMES: [&str; 4] = [".git", ".DS_Store", "Thumbs.db", ".gitignore"];
+use super::cache_cleanup::CACHE_META_FILE;
 
-fn is_ignored(entry: &DirEntry) -> bool {
+const IGNORE_NAMES: [&str; 5] = [
+    ".git",
+    ".DS_Store",
+    "Thumbs.db",
+    ".gitignore",
+    CACHE_META_FILE,
+];
+
+pub fn is_ignored(entry: &DirEntry) -> bool {
     let file_name = entry.file_name().to_string_lossy();
     IGNORE_NAMES.iter().any(|name| name == &file_name.as_ref())
 }
@@ -41,6 +49,41 @@ pub fn hash_dir(path: &Path) -> Result<String> {
     Ok(hex::encode(digest))
 }
 
+/// Cheap summary of a directory: where it resolves to plus every entry's relative path,
+/// size and mtime. When it is unchanged the folder's `hash_dir` can be reused without
+/// reading any file contents.
+pub fn dir_stamp(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    let resolved = std::fs::canonicalize(path).with_context(|| format!("resolve {:?}", path))?;
+    hasher.update(resolved.to_string_lossy().as_bytes());
+
+    for entry in WalkDir::new(path)
+        .follow_links(false)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| !is_ignored(entry))
+    {
+        let entry = entry?;
+        let relative = entry
+            .path()
+            .strip_prefix(path)
+            .with_context(|| format!("strip prefix {:?}", entry.path()))?;
+        let metadata = entry
+            .metadata()
+            .with_context(|| format!("stat {:?}", entry.path()))?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .unwrap_or_default();
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update(metadata.len().to_le_bytes());
+        hasher.update(modified.as_nanos().to_le_bytes());
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
 #[cfg(test)]
 #[path = "tests/content_hash.rs"]
 mod tests;
@@ -0,0 +1,248 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use super::skill_store::SkillStore;
+
+pub const CREDENTIALS_KEY: &str = "credentials";
+const KEYRING_SERVICE: &str = "skills-hub";
+const CREDENTIALS_FILE: &str = "credentials.json";
+/// Username git sends with a token when none is configured; accepted by GitHub,
+/// GitLab and Gitea for personal access tokens.
+pub const DEFAULT_TOKEN_USERNAME: &str = "x-access-token";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialBackend {
+    Keyring,
+    /// Plain JSON in app data, readable by anyone who can read the user's files.
+    PlainFile,
+}
+
+/// What is stored for a host. The token itself lives only in the keyring or the
+/// credentials file; this index (in `settings`) lets us list hosts without reading secrets.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialInfo {
+    pub host: String,
+    pub username: Option<String>,
+    pub backend: CredentialBackend,
+}
+
+pub struct HostToken {
+    pub username: String,
+    pub token: String,
+}
+
+/// Access tokens keyed by host. Prefers the OS keyring and falls back to a plain
+/// `credentials.json` in app data when no keyring is available; that file is not
+/// encrypted and is protected only by its 0600 permissions.
+pub struct CredentialStore {
+    use_keyring: bool,
+    file_path: PathBuf,
+}
+
+impl CredentialStore {
+    pub fn new(data_dir: &Path, use_keyring: bool) -> Self {
+        Self {
+            use_keyring,
+            file_path: data_dir.join(CREDENTIALS_FILE),
+        }
+    }
+
+    pub fn list(&self, store: &SkillStore) -> Vec<CredentialInfo> {
+        store
+            .get_setting(CREDENTIALS_KEY)
+            .ok()
+            .flatten()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn set(
+        &self,
+        store: &SkillStore,
+        host: &str,
+        username: Option<&str>,
+        token: &str,
+    ) -> Result<CredentialInfo> {
+        let host = normalize_host(host);
+        if host.is_empty() {
+            anyhow::bail!("credential host is empty");
+        }
+        let token = token.trim();
+        if token.is_empty() {
+            anyhow::bail!("token is empty");
+        }
+
+        // Drop any copy in the other backend so a host never has two tokens.
+        self.remove_secret(&host);
+        let backend = match self.keyring_set(&host, token) {
+            Ok(()) => CredentialBackend::Keyring,
+            Err(err) => {
+                if self.use_keyring {
+                    log::warn!(
+                        "[credentials] keyring unavailable, storing token in plain file {:?}: {:#}",
+                        self.file_path,
+                        err
+                    );
+                }
+                let mut tokens = self.read_file()?;
+                tokens.insert(host.clone(), token.to_string());
+                self.write_file(&tokens)?;
+                CredentialBackend::PlainFile
+            }
+        };
+
+        let info = CredentialInfo {
+            host: host.clone(),
+            username: username
+                .map(str::trim)
+                .filter(|u| !u.is_empty())
+                .map(str::to_string),
+            backend,
+        };
+        let mut index = self.list(store);
+        index.retain(|c| c.host != host);
+        index.push(info.clone());
+        store.set_setting(CREDENTIALS_KEY, &serde_json::to_string(&index)?)?;
+        Ok(info)
+    }
+
+    pub fn delete(&self, store: &SkillStore, host: &str) -> Result<()> {
+        let host = normalize_host(host);
+        self.remove_secret(&host);
+        let mut index = self.list(store);
+        index.retain(|c| c.host != host);
+        store.set_setting(CREDENTIALS_KEY, &serde_json::to_string(&index)?)?;
+        Ok(())
+    }
+
+    /// Token for `host`, if one is stored. Lookup failures are logged and treated as
+    /// "no token" so a broken keyring degrades to anonymous access.
+    pub fn token_for_host(&self, store: &SkillStore, host: &str) -> Option<HostToken> {
+        let host = normalize_host(host);
+        let info = self.list(store).into_iter().find(|c| c.host == host)?;
+        let token = match info.backend {
+            CredentialBackend::Keyring => keyring::Entry::new(KEYRING_SERVICE, &host)
+                .and_then(|entry| entry.get_password())
+                .map_err(anyhow::Error::from),
+            CredentialBackend::PlainFile => self.read_file().and_then(|mut tokens| {
+                tokens
+                    .remove(&host)
+                    .ok_or_else(|| anyhow::anyhow!("missing from credentials file"))
+            }),
+        };
+        match token {
+            Ok(token) => Some(HostToken {
+                username: info
+                    .username
+                    .unwrap_or_else(|| DEFAULT_TOKEN_USERNAME.to_string()),
+                token,
+            }),
+            Err(err) => {
+                log::warn!("[credentials] failed to read token for {}: {:#}", host, err);
+                None
+            }
+        }
+    }
+
+    fn keyring_set(&self, host: &str, token: &str) -> Result<()> {
+        if !self.use_keyring {
+            anyhow::bail!("keyring disabled");
+        }
+        keyring::Entry::new(KEYRING_SERVICE, host)?.set_password(token)?;
+        Ok(())
+    }
+
+    fn remove_secret(&self, host: &str) {
+        if self.use_keyring {
+            if let Ok(entry) = keyring::Entry::new(KEYRING_SERVICE, host) {
+                let _ = entry.delete_credential();
+            }
+        }
+        if let Ok(mut tokens) = self.read_file() {
+            if tokens.remove(host).is_some() {
+                if let Err(err) = self.write_file(&tokens) {
+                    log::warn!("[credentials] failed to update credentials file: {:#}", err);
+                }
+            }
+        }
+    }
+
+    fn read_file(&self) -> Result<BTreeMap<String, String>> {
+        if !self.file_path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let data =
+            std::fs::read(&self.file_path).with_context(|| format!("read {:?}", self.file_path))?;
+        serde_json::from_slice(&data).context("parse credentials file")
+    }
+
+    fn write_file(&self, tokens: &BTreeMap<String, String>) -> Result<()> {
+        write_private(&self.file_path, &serde_json::to_vec_pretty(tokens)?)
+    }
+}
+
+/// Credential store for the running app. Set `SKILLS_HUB_DISABLE_KEYRING=1` to keep
+/// tokens in the plain credentials file only (e.g. on headless Linux without a secret service).
+pub fn credential_store<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<CredentialStore> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .context("failed to resolve app data dir")?;
+    let use_keyring = !std::env::var("SKILLS_HUB_DISABLE_KEYRING")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+    Ok(CredentialStore::new(&data_dir, use_keyring))
+}
+
+/// Host (with port) of an `https://` URL, the only remotes a stored token is sent to;
+/// `None` for plain `http://`, SSH and local remotes.
+pub fn url_host(url: &str) -> Option<String> {
+    match split_url(url)? {
+        (scheme, host) if scheme.eq_ignore_ascii_case("https") => Some(host),
+        _ => None,
+    }
+}
+
+/// Scheme and lowercased host of an `http(s)://` URL.
+fn split_url(url: &str) -> Option<(&str, String)> {
+    let (scheme, rest) = url.split_once("://")?;
+    if !scheme.eq_ignore_ascii_case("https") && !scheme.eq_ignore_ascii_case("http") {
+        return None;
+    }
+    let authority = rest.split('/').next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    (!host.is_empty()).then(|| (scheme, host.to_ascii_lowercase()))
+}
+
+fn normalize_host(input: &str) -> String {
+    let trimmed = input.trim();
+    split_url(trimmed)
+        .map(|(_, host)| host)
+        .unwrap_or_else(|| trimmed.trim_matches('/').to_ascii_lowercase())
+}
+
+fn write_private(path: &Path, bytes: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("create dir {:?}", parent))?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("open {:?}", path))?;
+    std::io::Write::write_all(&mut file, bytes).with_context(|| format!("write {:?}", path))
+}
+
+#[cfg(test)]
+#[path = "tests/credentials.rs"]
+mod tests;
@@ -0,0 +1,255 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::skill_store::SkillStore;
+
+pub const FORGE_HOSTS_KEY: &str = "forge_hosts";
+
+/// URL layout family of a git forge. Self-hosted instances reuse the layout of
+/// the software they run (GitLab CE/EE, Gitea/Forgejo, ...).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForgeKind {
+    Github,
+    Gitlab,
+    Bitbucket,
+    Gitea,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForgeHost {
+    /// Host (optionally with port and a path prefix), e.g. `git.example.com:8443`
+    /// or `example.com/gitlab`.
+    pub host: String,
+    pub kind: ForgeKind,
+}
+
+/// Repository, ref and folder extracted from a forge web URL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForgeLocation {
+    pub clone_url: String,
+    pub branch: Option<String>,
+    /// Tag or commit named by the URL itself (e.g. `/src/tag/<t>/` or a SHA permalink).
+    pub pin: Option<String>,
+    pub subpath: Option<String>,
+}
+
+pub fn default_forge_hosts() -> Vec<ForgeHost> {
+    [
+        ("github.com", ForgeKind::Github),
+        ("gitlab.com", ForgeKind::Gitlab),
+        ("bitbucket.org", ForgeKind::Bitbucket),
+        ("codeberg.org", ForgeKind::Gitea),
+        ("gitea.com", ForgeKind::Gitea),
+    ]
+    .into_iter()
+    .map(|(host, kind)| ForgeHost {
+        host: host.to_string(),
+        kind,
+    })
+    .collect()
+}
+
+/// User-configured self-hosted forges.
+pub fn get_forge_hosts(store: &SkillStore) -> Vec<ForgeHost> {
+    store
+        .get_setting(FORGE_HOSTS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn set_forge_hosts(store: &SkillStore, hosts: Vec<ForgeHost>) -> Result<Vec<ForgeHost>> {
+    let mut normalized: Vec<ForgeHost> = Vec::new();
+    for entry in hosts {
+        let host = normalize_host(&entry.host);
+        if host.is_empty() || host.contains(char::is_whitespace) {
+            anyhow::bail!("invalid forge host: {:?}", entry.host);
+        }
+        if normalized.iter().any(|h| h.host == host) {
+            continue;
+        }
+        normalized.push(ForgeHost {
+            host,
+            kind: entry.kind,
+        });
+    }
+    store.set_setting(FORGE_HOSTS_KEY, &serde_json::to_string(&normalized)?)?;
+    Ok(normalized)
+}
+
+/// Configured hosts first (so a self-hosted entry can override a built-in one),
+/// then the built-in public forges.
+pub fn forge_registry(store: &SkillStore) -> Vec<ForgeHost> {
+    let mut hosts = get_forge_hosts(store);
+    hosts.extend(default_forge_hosts());
+    hosts
+}
+
+/// Parse a forge web/clone URL (`https://host/...` or scheme-less `host/...`) into a
+/// clone URL plus ref and folder. Returns `None` when the host is not a known forge
+/// or the path does not name a repository.
+pub fn parse_forge_url(input: &str, forges: &[ForgeHost]) -> Option<ForgeLocation> {
+    let (scheme, rest) = match input.split_once("://") {
+        Some((scheme, rest)) if scheme.eq_ignore_ascii_case("https") => ("https", rest),
+        Some((scheme, rest)) if scheme.eq_ignore_ascii_case("http") => ("http", rest),
+        Some(_) => return None,
+        None => ("https", input),
+    };
+    // Query strings (e.g. Bitbucket's `?at=`) and fragments are UI state only.
+    let rest = rest
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('/');
+
+    let lower = rest.to_ascii_lowercase();
+    let forge = forges.iter().find(|f| {
+        lower
+            .strip_prefix(&f.host)
+            .is_some_and(|tail| tail.is_empty() || tail.starts_with('/'))
+    })?;
+    let base = &rest[..forge.host.len()];
+    let segments: Vec<&str> = rest[forge.host.len()..]
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    let (repo_path, git_ref, subpath) = match forge.kind {
+        ForgeKind::Github => split_github(&segments)?,
+        ForgeKind::Gitlab => split_gitlab(&segments)?,
+        ForgeKind::Bitbucket => split_bitbucket(&segments)?,
+        ForgeKind::Gitea => split_gitea(&segments)?,
+    };
+
+    // Public forges are always reachable over https; self-hosted ones keep the scheme given.
+    let builtin = default_forge_hosts().iter().any(|h| h.host == forge.host);
+    let scheme = if builtin { "https" } else { scheme };
+    let (branch, pin) = match git_ref {
+        Some(GitRef::Branch(b)) => (Some(b), None),
+        Some(GitRef::Pin(p)) => (None, Some(p)),
+        Some(GitRef::Unknown(r)) if looks_like_commit_sha(&r) => (None, Some(r)),
+        Some(GitRef::Unknown(r)) => (Some(r), None),
+        None => (None, None),
+    };
+    Some(ForgeLocation {
+        clone_url: format!("{}://{}/{}.git", scheme, base, repo_path),
+        branch,
+        pin,
+        subpath,
+    })
+}
+
+pub fn looks_like_commit_sha(s: &str) -> bool {
+    (7..=40).contains(&s.len()) && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+enum GitRef {
+    Branch(String),
+    Pin(String),
+    /// A branch, tag or commit; the URL layout does not say which.
+    Unknown(String),
+}
+
+type RepoParts = (String, Option<GitRef>, Option<String>);
+
+/// `owner/repo[/tree|blob/<ref>[/<path>]]`
+fn split_github(segments: &[&str]) -> Option<RepoParts> {
+    let repo = repo_path(segments.get(..2)?)?;
+    match segments.get(2..) {
+        Some([kind, git_ref, path @ ..]) if matches!(*kind, "tree" | "blob") => Some((
+            repo,
+            Some(GitRef::Unknown(git_ref.to_string())),
+            join_subpath(path),
+        )),
+        _ => Some((repo, None, None)),
+    }
+}
+
+/// `group[/subgroup...]/repo[/-/tree|blob/<ref>[/<path>]]`
+fn split_gitlab(segments: &[&str]) -> Option<RepoParts> {
+    let (repo_segments, ui) = match segments.iter().position(|s| *s == "-") {
+        Some(dash) => (&segments[..dash], &segments[dash + 1..]),
+        None => (segments, &[][..]),
+    };
+    if repo_segments.len() < 2 {
+        return None;
+    }
+    let repo = repo_path(repo_segments)?;
+    match ui {
+        [kind, git_ref, path @ ..] if matches!(*kind, "tree" | "blob") => Some((
+            repo,
+            Some(GitRef::Unknown(git_ref.to_string())),
+            join_subpath(path),
+        )),
+        _ => Some((repo, None, None)),
+    }
+}
+
+/// `workspace/repo[/src/<ref>[/<path>]]`
+fn split_bitbucket(segments: &[&str]) -> Option<RepoParts> {
+    let repo = repo_path(segments.get(..2)?)?;
+    match segments.get(2..) {
+        Some(["src", git_ref, path @ ..]) => Some((
+            repo,
+            Some(GitRef::Unknown(git_ref.to_string())),
+            join_subpath(path),
+        )),
+        _ => Some((repo, None, None)),
+    }
+}
+
+/// `owner/repo[/src/branch|tag|commit/<ref>[/<path>]]` (Gitea, Forgejo, Codeberg)
+fn split_gitea(segments: &[&str]) -> Option<RepoParts> {
+    let repo = repo_path(segments.get(..2)?)?;
+    match segments.get(2..) {
+        Some(["src", "branch", git_ref, path @ ..]) => Some((
+            repo,
+            Some(GitRef::Branch(git_ref.to_string())),
+            join_subpath(path),
+        )),
+        Some(["src", "tag" | "commit", git_ref, path @ ..]) => Some((
+            repo,
+            Some(GitRef::Pin(git_ref.to_string())),
+            join_subpath(path),
+        )),
+        // Older Gitea links: `/src/<ref>/<path>`.
+        Some(["src", git_ref, path @ ..]) => Some((
+            repo,
+            Some(GitRef::Unknown(git_ref.to_string())),
+            join_subpath(path),
+        )),
+        _ => Some((repo, None, None)),
+    }
+}
+
+fn repo_path(segments: &[&str]) -> Option<String> {
+    let (repo, owners) = segments.split_last()?;
+    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+    if repo.is_empty() || owners.is_empty() {
+        return None;
+    }
+    Some(format!("{}/{}", owners.join("/"), repo))
+}
+
+fn join_subpath(path: &[&str]) -> Option<String> {
+    if path.is_empty() {
+        None
+    } else {
+        Some(path.join("/"))
+    }
+}
+
+fn normalize_host(input: &str) -> String {
+    let trimmed = input.trim();
+    let without_scheme = trimmed
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(trimmed);
+    without_scheme.trim_matches('/').to_ascii_lowercase()
+}
+
+#[cfg(test)]
+#[path = "tests/forge_url.rs"]
+mod tests;
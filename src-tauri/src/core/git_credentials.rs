@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::credentials::{url_host, CredentialStore};
+use super::git_fetcher::{GitAuth, HttpsCredential};
+use super::skill_store::SkillStore;
+
+pub const SSH_KEYS_KEY: &str = "git_ssh_keys";
+
+/// SSH private key to use for remotes starting with `source`, e.g.
+/// `git@git.example.com:team/` for a whole group or a full repo URL for one repo.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SshKeyBinding {
+    pub source: String,
+    pub key_path: String,
+}
+
+pub fn get_ssh_keys(store: &SkillStore) -> Vec<SshKeyBinding> {
+    store
+        .get_setting(SSH_KEYS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Bind `key_path` to `source`, or remove the binding when `key_path` is `None`.
+pub fn set_ssh_key(
+    store: &SkillStore,
+    source: &str,
+    key_path: Option<&str>,
+) -> Result<Vec<SshKeyBinding>> {
+    let source = source.trim();
+    if source.is_empty() {
+        anyhow::bail!("ssh key source is empty");
+    }
+    let mut bindings = get_ssh_keys(store);
+    bindings.retain(|b| b.source != source);
+    if let Some(key_path) = key_path.map(str::trim).filter(|p| !p.is_empty()) {
+        if !PathBuf::from(key_path).is_file() {
+            anyhow::bail!("ssh key not found: {}", key_path);
+        }
+        bindings.push(SshKeyBinding {
+            source: source.to_string(),
+            key_path: key_path.to_string(),
+        });
+    }
+    store.set_setting(SSH_KEYS_KEY, &serde_json::to_string(&bindings)?)?;
+    Ok(bindings)
+}
+
+/// Credentials for fetching `clone_url`: the most specific matching SSH key binding,
+/// plus the stored token for its host when it is an HTTPS remote.
+pub fn git_auth_for(store: &SkillStore, credentials: &CredentialStore, clone_url: &str) -> GitAuth {
+    let ssh_key = get_ssh_keys(store)
+        .into_iter()
+        .filter(|b| clone_url.starts_with(&b.source))
+        .max_by_key(|b| b.source.len())
+        .map(|b| PathBuf::from(b.key_path));
+    let https = url_host(clone_url).and_then(|host| {
+        credentials
+            .token_for_host(store, &host)
+            .map(|t| HttpsCredential {
+                host,
+                username: t.username,
+                token: t.token,
+            })
+    });
+    GitAuth { ssh_key, https }
+}
+
+#[cfg(test)]
+#[path = "tests/git_credentials.rs"]
+mod tests;
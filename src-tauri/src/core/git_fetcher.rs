@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::process::Stdio;
 use std::sync::OnceLock;
@@ -7,12 +7,75 @@ use std::time::{Duration, Instant};
 use anyhow::{Context, Result};
 use git2::{FetchOptions, Repository};
 
-pub fn clone_or_pull(repo_url: &str, dest: &Path, branch: Option<&str>) -> Result<String> {
+use super::credentials::url_host;
+
+/// `ssh` program git runs for SSH remotes with a configured key.
+const SSH_BIN: &str = "ssh";
+
+/// Per-remote credentials for a fetch.
+#[derive(Clone, Debug, Default)]
+pub struct GitAuth {
+    /// Private key for SSH remotes; `None` leaves SSH to the user's ssh config/agent.
+    pub ssh_key: Option<PathBuf>,
+    /// Access token for HTTPS remotes, handed to git through a credential helper.
+    pub https: Option<HttpsCredential>,
+}
+
+#[derive(Clone)]
+pub struct HttpsCredential {
+    /// Host the token was stored for; it is never offered to any other host.
+    pub host: String,
+    pub username: String,
+    pub token: String,
+}
+
+impl std::fmt::Debug for HttpsCredential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpsCredential")
+            .field("host", &self.host)
+            .field("username", &self.username)
+            .field("token", &"<redacted>")
+            .finish()
+    }
+}
+
+/// The remote rejected our credentials (or had none to offer). Never retried with libgit2,
+/// since that would fail the same way.
+#[derive(Debug)]
+pub struct GitAuthError(String);
+
+impl std::fmt::Display for GitAuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for GitAuthError {}
+
+/// git reported that the remote repository, or the branch/ref being fetched, does not
+/// exist. Like `GitAuthError`, never retried with libgit2.
+#[derive(Debug)]
+pub struct GitRemoteMissingError(String);
+
+impl std::fmt::Display for GitRemoteMissingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for GitRemoteMissingError {}
+
+pub fn clone_or_pull(
+    repo_url: &str,
+    dest: &Path,
+    branch: Option<&str>,
+    auth: &GitAuth,
+) -> Result<String> {
     // Prefer the system `git` binary if available. It tends to work better on macOS
     // networks because it respects user git config (proxy/certs) and OS trust store.
     if let Some(git_bin) = resolve_git_bin() {
         let started = Instant::now();
-        match clone_or_pull_via_git_cli(repo_url, dest, branch) {
+        match clone_or_pull_via_git_cli(repo_url, dest, branch, auth, SSH_BIN) {
             Ok(head) => {
                 log::info!(
                     "[git_fetcher] git-cli ok (bin={}) {}s url={}",
@@ -23,10 +86,6 @@ pub fn clone_or_pull(repo_url: &str, dest: &Path, branch: Option<&str>) -> Resul
                 return Ok(head);
             }
             Err(err) => {
-                let allow_fallback = std::env::var("SKILLS_HUB_ALLOW_LIBGIT2_FALLBACK")
-                    .ok()
-                    .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
-                    .unwrap_or(false);
                 log::warn!(
                     "[git_fetcher] git-cli failed (bin={}) {}s url={} err={:#}",
                     git_bin,
@@ -34,15 +93,7 @@ pub fn clone_or_pull(repo_url: &str, dest: &Path, branch: Option<&str>) -> Resul
                     repo_url,
                     err
                 );
-                if !allow_fallback {
-                    anyhow::bail!(
-                        "git 命令执行失败（为避免卡死，已停止并不再回退到内置 git）。请检查系统 git/网络/代理；或设置环境变量 SKILLS_HUB_ALLOW_LIBGIT2_FALLBACK=1 允许回退。\n{:#}",
-                        err
-                    );
-                }
-                log::warn!(
-                    "[git_fetcher] falling back to libgit2 (SKILLS_HUB_ALLOW_LIBGIT2_FALLBACK=1)"
-                );
+                ensure_libgit2_fallback_allowed(err)?;
             }
         }
     } else {
@@ -51,11 +102,10 @@ pub fn clone_or_pull(repo_url: &str, dest: &Path, branch: Option<&str>) -> Resul
 
     let repo = if dest.exists() {
         let repo = Repository::open(dest).with_context(|| format!("open repo at {:?}", dest))?;
-        fetch_origin(&repo)?;
+        fetch_origin(&repo, auth)?;
         repo
     } else {
-        Repository::clone(repo_url, dest)
-            .with_context(|| format!("clone {} into {:?}", repo_url, dest))?
+        clone_via_libgit2(repo_url, dest, auth)?
     };
 
     // Best-effort: move working tree HEAD to the fetched remote head (so "pull" actually updates).
@@ -83,6 +133,73 @@ pub fn clone_or_pull(repo_url: &str, dest: &Path, branch: Option<&str>) -> Resul
     Ok(head.to_string())
 }
 
+/// Check out exactly `rev` (a tag or commit SHA) in `dest`, detached, and return
+/// the commit id. Used for pinned skills instead of `clone_or_pull`.
+pub fn checkout_revision(repo_url: &str, dest: &Path, rev: &str, auth: &GitAuth) -> Result<String> {
+    if let Some(git_bin) = resolve_git_bin() {
+        let started = Instant::now();
+        match checkout_revision_via_git_cli(repo_url, dest, rev, auth) {
+            Ok(head) => {
+                log::info!(
+                    "[git_fetcher] git-cli checkout ok (bin={}) {}s url={} rev={}",
+                    git_bin,
+                    started.elapsed().as_secs_f32(),
+                    repo_url,
+                    rev
+                );
+                return Ok(head);
+            }
+            Err(err) => {
+                log::warn!(
+                    "[git_fetcher] git-cli checkout failed (bin={}) {}s url={} rev={} err={:#}",
+                    git_bin,
+                    started.elapsed().as_secs_f32(),
+                    repo_url,
+                    rev,
+                    err
+                );
+                ensure_libgit2_fallback_allowed(err)?;
+            }
+        }
+    } else {
+        log::info!("[git_fetcher] system git not available; using libgit2");
+    }
+
+    let repo = if dest.join(".git").exists() {
+        let repo = Repository::open(dest).with_context(|| format!("open repo at {:?}", dest))?;
+        fetch_origin(&repo, auth)?;
+        repo
+    } else {
+        clone_via_libgit2(repo_url, dest, auth)?
+    };
+    let obj = repo
+        .revparse_single(&format!("{}^{{commit}}", rev))
+        .with_context(|| format!("revision not found: {}", rev))?;
+    repo.checkout_tree(&obj, Some(git2::build::CheckoutBuilder::new().force()))?;
+    repo.set_head_detached(obj.id())?;
+    Ok(obj.id().to_string())
+}
+
+/// Only fall back from the git CLI to libgit2 when explicitly allowed; otherwise
+/// surface the CLI error.
+fn ensure_libgit2_fallback_allowed(err: anyhow::Error) -> Result<()> {
+    if err.is::<GitAuthError>() || err.is::<GitRemoteMissingError>() {
+        return Err(err);
+    }
+    let allow_fallback = std::env::var("SKILLS_HUB_ALLOW_LIBGIT2_FALLBACK")
+        .ok()
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+    if !allow_fallback {
+        anyhow::bail!(
+            "git 命令执行失败（为避免卡死，已停止并不再回退到内置 git）。请检查系统 git/网络/代理；或设置环境变量 SKILLS_HUB_ALLOW_LIBGIT2_FALLBACK=1 允许回退。\n{:#}",
+            err
+        );
+    }
+    log::warn!("[git_fetcher] falling back to libgit2 (SKILLS_HUB_ALLOW_LIBGIT2_FALLBACK=1)");
+    Ok(())
+}
+
 fn git_timeout() -> Duration {
     let secs = std::env::var("SKILLS_HUB_GIT_TIMEOUT_SECS")
         .ok()
@@ -150,18 +267,122 @@ fn git_bin_works(bin: &str) -> bool {
         .unwrap_or(false)
 }
 
-fn git_cmd() -> Command {
+fn git_cmd(auth: &GitAuth, ssh_bin: &str) -> Command {
     let bin = resolve_git_bin().unwrap_or_else(|| "git".to_string());
     let mut cmd = Command::new(bin);
     // Never block on interactive auth prompts inside a GUI app.
     cmd.env("GIT_TERMINAL_PROMPT", "0")
         .env("GIT_ASKPASS", "echo");
+    if let Some(key) = &auth.ssh_key {
+        cmd.env("GIT_SSH_COMMAND", ssh_command(ssh_bin, key))
+            .env("GIT_SSH_VARIANT", "ssh");
+    }
+    if let Some(cred) = &auth.https {
+        // The token reaches git through the environment, never the URL or argv, so it
+        // is not written to `.git/config` or visible in process listings. The empty
+        // helper first clears any user-configured helpers for this command; ours only
+        // answers for the token's host, not redirects, submodules or other remotes.
+        cmd.args([
+            "-c".to_string(),
+            "credential.helper=".to_string(),
+            "-c".to_string(),
+            format!(
+                "credential.https://{}.helper=!f() {{ test \"$1\" = get && printf 'username=%s\\npassword=%s\\n' \"$SKILLS_HUB_GIT_USERNAME\" \"$SKILLS_HUB_GIT_TOKEN\"; }}; f",
+                cred.host
+            ),
+        ])
+        .env("SKILLS_HUB_GIT_USERNAME", &cred.username)
+        .env("SKILLS_HUB_GIT_TOKEN", &cred.token);
+    }
     // Abort stalled HTTPS transfers (helps avoid "spinner forever" on bad networks).
     cmd.env("GIT_HTTP_LOW_SPEED_LIMIT", "1024")
         .env("GIT_HTTP_LOW_SPEED_TIME", "120");
     cmd
 }
 
+/// `ssh` invocation that only offers `key` and fails instead of prompting. Host keys
+/// are checked against the user's `known_hosts` as is: an unknown host fails (see
+/// `git_failure`) rather than being trusted silently.
+fn ssh_command(ssh_bin: &str, key: &Path) -> String {
+    format!(
+        "{} -i {} -o IdentitiesOnly=yes -o BatchMode=yes",
+        shell_quote(ssh_bin),
+        shell_quote(&key.to_string_lossy())
+    )
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Turn a failed git command into an error, recognizing auth failures so the user
+/// gets told what to fix instead of a raw transport error, and a missing remote so
+/// callers can tell it apart from a transient failure.
+fn git_failure(what: &str, stderr: &[u8], auth: &GitAuth) -> anyhow::Error {
+    let stderr = String::from_utf8_lossy(stderr);
+    let lower = stderr.to_lowercase();
+    let key = auth
+        .ssh_key
+        .as_ref()
+        .map(|k| k.to_string_lossy().to_string());
+
+    let hint = if lower.contains("host key verification failed") {
+        "SSH 主机密钥校验失败：该主机不在 known_hosts 中，或指纹与记录不符。请先在终端手动连接一次（如 `ssh -T git@<host>`）核对并确认主机指纹，再重试。".to_string()
+    } else if lower.contains("load key")
+        || lower.contains("bad permissions")
+        || lower.contains("no such identity")
+        || lower.contains("identity file")
+    {
+        format!(
+            "无法使用 SSH 私钥 {}：请确认文件存在、权限为 600，且私钥没有口令（或已加入 ssh-agent）。",
+            key.as_deref().unwrap_or("")
+        )
+    } else if lower.contains("permission denied")
+        || lower.contains("authentication failed")
+        || lower.contains("could not read username")
+        || lower.contains("could not read password")
+        || lower.contains("terminal prompts disabled")
+    {
+        match &key {
+            _ if auth.https.is_some() && !lower.contains("publickey") => {
+                "HTTPS 鉴权失败：已保存的访问令牌无效、已过期或权限不足，请更新该主机的令牌。"
+                    .to_string()
+            }
+            Some(key) => format!(
+                "SSH 鉴权失败：服务器拒绝了私钥 {}。请确认对应公钥已添加到该仓库或账号。",
+                key
+            ),
+            None => {
+                "Git 鉴权失败：该仓库需要凭据。可为该主机保存访问令牌、为此来源指定 SSH 私钥，或在系统 git 中配置凭据。"
+                    .to_string()
+            }
+        }
+    } else if remote_is_missing(&lower) {
+        return anyhow::Error::new(GitRemoteMissingError(format!(
+            "{} failed: {}",
+            what,
+            stderr.trim()
+        )));
+    } else {
+        return anyhow::anyhow!("{} failed: {}", what, stderr);
+    };
+    anyhow::Error::new(GitAuthError(format!("{}\n{}", hint, stderr.trim())))
+}
+
+/// git's own wording (lowercased) for a repository or ref that is not there.
+fn remote_is_missing(lower_stderr: &str) -> bool {
+    let missing_repo = lower_stderr.lines().any(|line| {
+        line.starts_with("fatal: repository '")
+            && (line.ends_with("' not found") || line.ends_with("' does not exist"))
+    });
+    missing_repo
+        || lower_stderr.contains("remote: repository not found")
+        || lower_stderr.contains("does not appear to be a git repository")
+        || lower_stderr.contains("fatal: couldn't find remote ref")
+        || (lower_stderr.contains("fatal: remote branch")
+            && lower_stderr.contains("not found in upstream"))
+}
+
 fn run_cmd_with_timeout(
     mut cmd: Command,
     timeout: Duration,
@@ -195,7 +416,13 @@ fn run_cmd_with_timeout(
     }
 }
 
-fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>) -> Result<String> {
+fn clone_or_pull_via_git_cli(
+    repo_url: &str,
+    dest: &Path,
+    branch: Option<&str>,
+    auth: &GitAuth,
+    ssh_bin: &str,
+) -> Result<String> {
     // Ensure parent exists so `git clone` can create dest.
     if let Some(parent) = dest.parent() {
         std::fs::create_dir_all(parent)
@@ -206,7 +433,7 @@ fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>)
         // Fetch updates.
         let out = run_cmd_with_timeout(
             {
-                let mut cmd = git_cmd();
+                let mut cmd = git_cmd(auth, ssh_bin);
                 cmd.arg("-C").arg(dest).args(["fetch", "--prune", "origin"]);
                 cmd
             },
@@ -214,14 +441,14 @@ fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>)
             format!("git fetch in {:?}", dest),
         )?;
         if !out.status.success() {
-            anyhow::bail!("git fetch failed: {}", String::from_utf8_lossy(&out.stderr));
+            return Err(git_failure("git fetch", &out.stderr, auth));
         }
 
         // Move local HEAD to fetched commit.
         if let Some(branch) = branch {
             let out = run_cmd_with_timeout(
                 {
-                    let mut cmd = git_cmd();
+                    let mut cmd = git_cmd(auth, ssh_bin);
                     cmd.arg("-C").arg(dest).args([
                         "checkout",
                         "-B",
@@ -242,7 +469,7 @@ fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>)
         } else {
             let out = run_cmd_with_timeout(
                 {
-                    let mut cmd = git_cmd();
+                    let mut cmd = git_cmd(auth, ssh_bin);
                     cmd.arg("-C")
                         .arg(dest)
                         .args(["reset", "--hard", "FETCH_HEAD"]);
@@ -260,7 +487,7 @@ fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>)
         }
     } else {
         // Clone.
-        let mut cmd = git_cmd();
+        let mut cmd = git_cmd(auth, ssh_bin);
         cmd.arg("clone")
             .args(["--depth", "1", "--filter=blob:none", "--no-tags"]);
         if let Some(branch) = branch {
@@ -273,7 +500,7 @@ fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>)
             format!("git clone {} into {:?}", repo_url, dest),
         )?;
         if !out.status.success() {
-            anyhow::bail!("git clone failed: {}", String::from_utf8_lossy(&out.stderr));
+            return Err(git_failure("git clone", &out.stderr, auth));
         }
     }
 
@@ -281,7 +508,7 @@ fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>)
     if let Some(branch) = branch {
         let out = run_cmd_with_timeout(
             {
-                let mut cmd = git_cmd();
+                let mut cmd = git_cmd(auth, ssh_bin);
                 cmd.arg("-C").arg(dest).args(["checkout", branch]);
                 cmd
             },
@@ -301,7 +528,7 @@ fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>)
     // Read HEAD revision.
     let out = run_cmd_with_timeout(
         {
-            let mut cmd = git_cmd();
+            let mut cmd = git_cmd(auth, ssh_bin);
             cmd.arg("-C").arg(dest).args(["rev-parse", "HEAD"]);
             cmd
         },
@@ -317,11 +544,133 @@ fn clone_or_pull_via_git_cli(repo_url: &str, dest: &Path, branch: Option<&str>)
     Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
 }
 
-fn fetch_origin(repo: &Repository) -> Result<()> {
+fn checkout_revision_via_git_cli(
+    repo_url: &str,
+    dest: &Path,
+    rev: &str,
+    auth: &GitAuth,
+) -> Result<String> {
+    let run = |args: &[&str], timeout: Duration| -> Result<std::process::Output> {
+        let mut cmd = git_cmd(auth, SSH_BIN);
+        cmd.arg("-C").arg(dest).args(args);
+        run_cmd_with_timeout(
+            cmd,
+            timeout,
+            format!("git {} in {:?}", args.join(" "), dest),
+        )
+    };
+    let ensure_ok = |out: std::process::Output, what: &str| -> Result<std::process::Output> {
+        if !out.status.success() {
+            return Err(git_failure(what, &out.stderr, auth));
+        }
+        Ok(out)
+    };
+
+    if !dest.join(".git").exists() {
+        std::fs::create_dir_all(dest)
+            .with_context(|| format!("failed to create dir {:?}", dest))?;
+        ensure_ok(run(&["init", "--quiet"], git_fetch_timeout())?, "git init")?;
+        ensure_ok(
+            run(&["remote", "add", "origin", repo_url], git_fetch_timeout())?,
+            "git remote add",
+        )?;
+    }
+
+    // Fetch just the pinned ref. Short SHAs (and servers that refuse SHA wants) need
+    // the full history instead.
+    let shallow = run(
+        &["fetch", "--depth", "1", "--filter=blob:none", "origin", rev],
+        git_timeout(),
+    )?;
+    let target = if shallow.status.success() {
+        "FETCH_HEAD".to_string()
+    } else {
+        // Don't retry with a full fetch when the remote refused us outright.
+        let err = git_failure("git fetch", &shallow.stderr, auth);
+        if err.is::<GitAuthError>() {
+            return Err(err);
+        }
+        let mut args = vec!["fetch", "--filter=blob:none", "--tags", "origin"];
+        if dest.join(".git").join("shallow").exists() {
+            args.push("--unshallow");
+        }
+        ensure_ok(run(&args, git_timeout())?, "git fetch")?;
+        format!("{}^{{commit}}", rev)
+    };
+
+    let out = run(
+        &["checkout", "--quiet", "--force", "--detach", &target],
+        git_fetch_timeout(),
+    )?;
+    if !out.status.success() {
+        anyhow::bail!(
+            "revision not found: {} ({})",
+            rev,
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+
+    let out = ensure_ok(
+        run(&["rev-parse", "HEAD"], git_fetch_timeout())?,
+        "git rev-parse",
+    )?;
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+fn clone_via_libgit2(repo_url: &str, dest: &Path, auth: &GitAuth) -> Result<Repository> {
+    let mut opts = FetchOptions::new();
+    opts.remote_callbacks(remote_callbacks(auth));
+    git2::build::RepoBuilder::new()
+        .fetch_options(opts)
+        .clone(repo_url, dest)
+        .with_context(|| format!("clone {} into {:?}", repo_url, dest))
+}
+
+fn remote_callbacks(auth: &GitAuth) -> git2::RemoteCallbacks<'_> {
+    let mut callbacks = git2::RemoteCallbacks::new();
+    // libgit2 keeps asking while we keep answering; give up after a few rounds.
+    let mut attempts = 0;
+    callbacks.credentials(move |url, username, allowed| {
+        attempts += 1;
+        if attempts > 3 {
+            return Err(git2::Error::from_str("authentication failed"));
+        }
+        if allowed.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
+            if let Some(cred) = https_credential_for(auth, url) {
+                return git2::Cred::userpass_plaintext(&cred.username, &cred.token);
+            }
+        }
+        let user = username.unwrap_or("git");
+        if allowed.contains(git2::CredentialType::USERNAME) {
+            return git2::Cred::username(user);
+        }
+        if allowed.contains(git2::CredentialType::SSH_KEY) {
+            return match &auth.ssh_key {
+                Some(key) => git2::Cred::ssh_key(user, None, key, None),
+                None => git2::Cred::ssh_key_from_agent(user),
+            };
+        }
+        git2::Cred::default()
+    });
+    callbacks
+}
+
+/// The token in `auth`, if `url` is an HTTPS URL on the host it was stored for.
+fn https_credential_for<'a>(auth: &'a GitAuth, url: &str) -> Option<&'a HttpsCredential> {
+    auth.https
+        .as_ref()
+        .filter(|cred| url_host(url).is_some_and(|host| host == cred.host))
+}
+
+fn fetch_origin(repo: &Repository, auth: &GitAuth) -> Result<()> {
     let mut remote = repo.find_remote("origin")?;
     let mut opts = FetchOptions::new();
+    opts.remote_callbacks(remote_callbacks(auth));
     remote.fetch(
-        &["refs/heads/*:refs/remotes/origin/*"],
+        &[
+            "refs/heads/*:refs/remotes/origin/*",
+            "+refs/tags/*:refs/tags/*",
+        ],
         Some(&mut opts),
         None,
     )?;
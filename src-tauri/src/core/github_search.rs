@@ -27,14 +27,21 @@ pub struct RepoSummary {
     pub clone_url: String,
 }
 
-pub fn search_github_repos(query: &str, limit: usize) -> Result<Vec<RepoSummary>> {
-    search_github_repos_inner("https://api.github.com", query, limit)
+/// Search public repositories. A `token` (GitHub personal access token) raises the
+/// API rate limit and lets results include private repos the token can see.
+pub fn search_github_repos(
+    query: &str,
+    limit: usize,
+    token: Option<&str>,
+) -> Result<Vec<RepoSummary>> {
+    search_github_repos_inner("https://api.github.com", query, limit, token)
 }
 
 fn search_github_repos_inner(
     base_url: &str,
     query: &str,
     limit: usize,
+    token: Option<&str>,
 ) -> Result<Vec<RepoSummary>> {
     let client = Client::new();
     let base_url = base_url.trim_end_matches('/');
@@ -45,9 +52,11 @@ fn search_github_repos_inner(
         limit.clamp(1, 50)
     );
 
-    let response = client
-        .get(url)
-        .header("User-Agent", "skills-hub")
+    let mut request = client.get(url).header("User-Agent", "skills-hub");
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = request
         .send()
         .context("GitHub search request failed")?
         .error_for_status()
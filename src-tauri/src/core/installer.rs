@@ -1,20 +1,36 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::{Mutex, OnceLock};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use tauri::Manager;
 use uuid::Uuid;
 
-use super::cache_cleanup::get_git_cache_ttl_secs;
-use super::central_repo::{ensure_central_repo, resolve_central_repo_path};
+use super::archive::fetch_archive;
+use super::cache_cleanup::{get_git_cache_ttl_secs, CACHE_META_FILE};
+use super::central_repo::{ensure_central_repo, resolve_central_repo_path, skill_dir_in};
 use super::content_hash::hash_dir;
-use super::git_fetcher::clone_or_pull;
+use super::credentials::{credential_store, url_host};
+use super::forge_url::{forge_registry, looks_like_commit_sha, parse_forge_url, ForgeHost};
+use super::git_credentials::git_auth_for;
+use super::git_fetcher::{checkout_revision, clone_or_pull, GitAuthError, GitRemoteMissingError};
+use super::op_journal::Swap;
+use super::skill_diff::{diff_dirs, SkillDiff};
+use super::skill_discovery::discover_skill_dirs;
+use super::skill_history::{
+    base_copy_path, resolve_history_root, save_base_copy, snapshot_skill_dir,
+};
+use super::skill_manifest::{refresh_skill_manifest, ManifestError, SkillManifest};
+use super::skill_merge::{has_local_changes, merge_into_upstream, MergeReport};
 use super::skill_store::{SkillRecord, SkillStore};
 use super::sync_engine::copy_dir_recursive;
-use super::sync_engine::sync_dir_copy_with_overwrite;
+use super::sync_engine::{remove_path_any, sync_rendered_with_overwrite};
+use super::sync_engine::{sync_dir_tree_with_overwrite, SyncMode};
+use super::temp_cleanup::mark_temp_dir;
 use super::tool_adapters::adapter_by_key;
 use super::tool_adapters::is_tool_installed;
+use super::tool_adapters::transformer_for;
 
 pub struct InstallResult {
     pub skill_id: String,
@@ -42,7 +58,7 @@ pub fn install_local_skill<R: tauri::Runtime>(
 
     let central_dir = resolve_central_repo_path(app, store)?;
     ensure_central_repo(&central_dir)?;
-    let central_path = central_dir.join(&name);
+    let central_path = skill_dir_in(&central_dir, &name)?;
 
     if central_path.exists() {
         anyhow::bail!("skill already exists in central repo: {:?}", central_path);
@@ -60,6 +76,8 @@ pub fn install_local_skill<R: tauri::Runtime>(
         source_type: "local".to_string(),
         source_ref: Some(source_path.to_string_lossy().to_string()),
         source_revision: None,
+        source_subpath: None,
+        source_pin: None,
         central_path: central_path.to_string_lossy().to_string(),
         content_hash: content_hash.clone(),
         created_at: now,
@@ -70,6 +88,8 @@ pub fn install_local_skill<R: tauri::Runtime>(
     };
 
     store.upsert_skill(&record)?;
+    refresh_skill_manifest(store, &record.id, &central_path)?;
+    keep_base_copy(app, &record.id, &central_path);
 
     Ok(InstallResult {
         skill_id: record.id,
@@ -85,7 +105,22 @@ pub fn install_git_skill<R: tauri::Runtime>(
     repo_url: &str,
     name: Option<String>,
 ) -> Result<InstallResult> {
-    let parsed = parse_github_url(repo_url);
+    install_git_skill_at(app, store, repo_url, name, None)
+}
+
+/// [`install_git_skill`] checked out at `pin` (a tag or commit, e.g. from a team
+/// lockfile) instead of any ref in `repo_url`. `repo_url` is still recorded as the source.
+pub fn install_git_skill_at<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    repo_url: &str,
+    name: Option<String>,
+    pin: Option<&str>,
+) -> Result<InstallResult> {
+    let mut parsed = parse_github_url(repo_url, &forge_registry(store));
+    if let Some(pin) = pin {
+        parsed.pin = Some(pin.to_string());
+    }
     let name = name.unwrap_or_else(|| {
         if let Some(subpath) = &parsed.subpath {
             subpath
@@ -100,7 +135,7 @@ pub fn install_git_skill<R: tauri::Runtime>(
 
     let central_dir = resolve_central_repo_path(app, store)?;
     ensure_central_repo(&central_dir)?;
-    let central_path = central_dir.join(&name);
+    let central_path = skill_dir_in(&central_dir, &name)?;
 
     if central_path.exists() {
         anyhow::bail!("skill already exists in central repo: {:?}", central_path);
@@ -109,7 +144,13 @@ pub fn install_git_skill<R: tauri::Runtime>(
     // Always clone into a temp dir first, then copy the skill directory into central repo.
     // This avoids storing a full git repo (with .git) inside central repo and allows
     // handling GitHub folder URLs (/tree/<branch>/<path>).
-    let (repo_dir, rev) = clone_to_cache(app, store, &parsed.clone_url, parsed.branch.as_deref())?;
+    let (repo_dir, rev) = clone_to_cache(
+        app,
+        store,
+        &parsed.clone_url,
+        parsed.branch.as_deref(),
+        parsed.pin.as_deref(),
+    )?;
 
     let copy_src = if let Some(subpath) = &parsed.subpath {
         let sub_src = repo_dir.join(subpath);
@@ -152,6 +193,8 @@ pub fn install_git_skill<R: tauri::Runtime>(
         source_type: "git".to_string(),
         source_ref: Some(repo_url.to_string()),
         source_revision: Some(revision),
+        source_subpath: parsed.subpath.clone(),
+        source_pin: parsed.pin.clone(),
         central_path: central_path.to_string_lossy().to_string(),
         content_hash: content_hash.clone(),
         created_at: now,
@@ -162,6 +205,8 @@ pub fn install_git_skill<R: tauri::Runtime>(
     };
 
     store.upsert_skill(&record)?;
+    refresh_skill_manifest(store, &record.id, &central_path)?;
+    keep_base_copy(app, &record.id, &central_path);
 
     Ok(InstallResult {
         skill_id: record.id,
@@ -176,76 +221,114 @@ struct ParsedGitSource {
     clone_url: String,
     branch: Option<String>,
     subpath: Option<String>,
+    /// Exact tag/commit to check out instead of tracking `branch`.
+    pin: Option<String>,
 }
 
-fn parse_github_url(input: &str) -> ParsedGitSource {
+fn parse_github_url(input: &str, forges: &[ForgeHost]) -> ParsedGitSource {
     // Supports:
     // - https://github.com/owner/repo
     // - https://github.com/owner/repo.git
     // - https://github.com/owner/repo/tree/<branch>/<path>
     // - https://github.com/owner/repo/blob/<branch>/<path>
-    let trimmed = input.trim().trim_end_matches('/');
+    // - GitLab/Bitbucket/Gitea folder links on any host in `forges` (see forge_url)
+    // - SSH remotes (`git@host:owner/repo.git`, `ssh://host/owner/repo.git`) and other
+    //   non-web remotes, with an optional `#<branch-or-sha>:<path>` suffix
+    // - any of the above with an `@<tag-or-sha>` suffix, or `/tree/<sha>/...`, to pin
+    let input = input.trim();
+    if !is_web_url(input) {
+        if let Some((remote, fragment)) = input.split_once('#') {
+            let (remote, pin) = split_pin_suffix(remote.trim_end_matches('/'));
+            let (git_ref, subpath) = fragment.split_once(':').unwrap_or((fragment, ""));
+            let git_ref = git_ref.trim().to_string();
+            let (branch, pin) = match pin {
+                Some(pin) => (None, Some(pin)),
+                None if git_ref.is_empty() => (None, None),
+                None if looks_like_commit_sha(&git_ref) => (None, Some(git_ref)),
+                None => (Some(git_ref), None),
+            };
+            let subpath = subpath.trim().trim_matches('/');
+            return ParsedGitSource {
+                clone_url: remote.to_string(),
+                branch,
+                subpath: (!subpath.is_empty()).then(|| subpath.to_string()),
+                pin,
+            };
+        }
+    }
+    let (trimmed, pin) = split_pin_suffix(input.trim_end_matches('/'));
 
     // Convenience: allow GitHub shorthand inputs like `owner/repo` (and `owner/repo/tree/<branch>/...`).
     // This keeps the UI friendly while still allowing local paths or other git remotes.
-    let normalized = if trimmed.starts_with("https://github.com/") {
-        trimmed.to_string()
-    } else if trimmed.starts_with("http://github.com/") {
-        trimmed.replacen("http://github.com/", "https://github.com/", 1)
-    } else if trimmed.starts_with("github.com/") {
-        format!("https://{}", trimmed)
-    } else if looks_like_github_shorthand(trimmed) {
-        format!("https://github.com/{}", trimmed)
-    } else {
-        trimmed.to_string()
-    };
-
-    let trimmed = normalized.trim_end_matches('/');
-    let gh_prefix = "https://github.com/";
-    if !trimmed.starts_with(gh_prefix) {
+    let location = parse_forge_url(trimmed, forges).or_else(|| {
+        looks_like_github_shorthand(trimmed)
+            .then(|| parse_forge_url(&format!("https://github.com/{}", trimmed), forges))
+            .flatten()
+    });
+    let Some(location) = location else {
         return ParsedGitSource {
             clone_url: trimmed.to_string(),
             branch: None,
             subpath: None,
+            pin,
         };
-    }
+    };
 
-    let rest = &trimmed[gh_prefix.len()..];
-    let parts: Vec<&str> = rest.split('/').collect();
-    if parts.len() < 2 {
-        return ParsedGitSource {
-            clone_url: trimmed.to_string(),
-            branch: None,
-            subpath: None,
-        };
+    // An explicit `@<ref>` suffix wins over whatever ref the URL names.
+    let (branch, pin) = match pin {
+        Some(pin) => (None, Some(pin)),
+        None => (location.branch, location.pin),
+    };
+    ParsedGitSource {
+        clone_url: location.clone_url,
+        branch,
+        subpath: location.subpath,
+        pin,
     }
+}
 
-    let owner = parts[0];
-    let mut repo = parts[1].to_string();
-    if let Some(stripped) = repo.strip_suffix(".git") {
-        repo = stripped.to_string();
-    }
-    let clone_url = format!("https://github.com/{}/{}.git", owner, repo);
+/// `http(s)://` links may carry `#` anchors of their own; only other remotes
+/// (SSH, `file://`, local paths) take a `#<ref>:<path>` suffix.
+fn is_web_url(input: &str) -> bool {
+    let lower = input.to_ascii_lowercase();
+    lower.starts_with("https://") || lower.starts_with("http://")
+}
 
-    if parts.len() >= 4 && (parts[2] == "tree" || parts[2] == "blob") {
-        let branch = Some(parts[3].to_string());
-        let subpath = if parts.len() > 4 {
-            Some(parts[4..].join("/"))
-        } else {
-            None
-        };
-        return ParsedGitSource {
-            clone_url,
-            branch,
-            subpath,
-        };
+/// SSH remote in URL form (`ssh://`, `git+ssh://`) or scp form (`[user@]host:path`).
+fn is_ssh_remote(input: &str) -> bool {
+    let lower = input.to_ascii_lowercase();
+    if lower.starts_with("ssh://") || lower.starts_with("git+ssh://") {
+        return true;
+    }
+    // Like git: scp form has a `:` before any `/`; one-letter hosts are drive letters.
+    match input.find(':') {
+        Some(colon) => !input[..colon].contains('/') && !input.contains("://") && colon > 1,
+        None => false,
     }
+}
 
-    ParsedGitSource {
-        clone_url,
-        branch: None,
-        subpath: None,
+/// Split a trailing `@<ref>` off the last path segment. scp-style remotes
+/// (`git@host:owner/repo`) keep their `@` because it is not in the last segment
+/// or is followed by a `:`.
+fn split_pin_suffix(input: &str) -> (&str, Option<String>) {
+    let last_segment = input.rfind('/').map(|i| i + 1).unwrap_or(0);
+    if let Some(at) = input[last_segment..].rfind('@') {
+        let at = last_segment + at;
+        let pin = &input[at + 1..];
+        if at > last_segment && !pin.is_empty() && !pin.contains(':') {
+            return (&input[..at], Some(pin.to_string()));
+        }
     }
+    (input, None)
+}
+
+/// Folder of a git skill inside its repo: the stored subpath (selection installs),
+/// else whatever the source URL itself points at.
+fn git_skill_subpath(record: &SkillRecord, parsed: &ParsedGitSource) -> Option<String> {
+    record
+        .source_subpath
+        .clone()
+        .or_else(|| parsed.subpath.clone())
 }
 
 fn looks_like_github_shorthand(input: &str) -> bool {
@@ -301,8 +384,14 @@ fn now_ms() -> i64 {
 }
 
 fn derive_name_from_repo_url(repo_url: &str) -> String {
+    // Split on `:` too so scp-style remotes without a path (`git@host:repo.git`) work.
+    let split_on: &[char] = if is_ssh_remote(repo_url) {
+        &['/', ':']
+    } else {
+        &['/']
+    };
     let mut name = repo_url
-        .split('/')
+        .split(split_on)
         .next_back()
         .unwrap_or("skill")
         .to_string();
@@ -316,22 +405,22 @@ fn derive_name_from_repo_url(repo_url: &str) -> String {
     }
 }
 
+/// Always recorded: updates compare it against central to detect local edits.
 fn compute_content_hash(path: &Path) -> Option<String> {
-    if should_compute_content_hash() {
-        hash_dir(path).ok()
-    } else {
-        None
-    }
+    hash_dir(path).ok()
 }
 
-fn should_compute_content_hash() -> bool {
-    if cfg!(debug_assertions) {
-        return true;
+/// Best-effort: without a base copy, local edits can still be detected but not merged.
+fn keep_base_copy<R: tauri::Runtime>(app: &tauri::AppHandle<R>, skill_id: &str, source: &Path) {
+    let res = resolve_history_root(app)
+        .and_then(|history_root| save_base_copy(&history_root, skill_id, source));
+    if let Err(err) = res {
+        log::warn!(
+            "[installer] failed to keep base copy of skill {}: {:#}",
+            skill_id,
+            err
+        );
     }
-    std::env::var("SKILLS_HUB_COMPUTE_HASH")
-        .ok()
-        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
-        .unwrap_or(false)
 }
 
 pub struct UpdateResult {
@@ -342,17 +431,253 @@ pub struct UpdateResult {
     pub content_hash: Option<String>,
     pub source_revision: Option<String>,
     pub updated_targets: Vec<String>,
+    /// Set when local edits were merged into the update.
+    pub merge: Option<MergeReport>,
+}
+
+/// What an update does when central was edited since it was last installed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocalChangesPolicy {
+    /// Fail with a `LOCAL_CHANGES|<central_path>` error and leave central untouched.
+    #[default]
+    Refuse,
+    /// Replace central with upstream; the edited version is still kept in history.
+    Overwrite,
+    /// Three-way merge of base (last install), local (central) and upstream.
+    Merge,
 }
 
 pub fn update_managed_skill_from_source<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     store: &SkillStore,
     skill_id: &str,
+    policy: LocalChangesPolicy,
+) -> Result<UpdateResult> {
+    let record = store
+        .get_skill_by_id(skill_id)?
+        .ok_or_else(|| anyhow::anyhow!("skill not found"))?;
+    let (staging_dir, new_revision) = stage_skill_update(app, store, &record)?;
+    promote_staged_update(app, store, record, &staging_dir, new_revision, policy)
+}
+
+/// Pin a git skill to a tag/commit (or unpin with `None` to track its branch again)
+/// and move central to that revision. The pin is only saved if the update succeeds.
+pub fn set_skill_pin<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    skill_id: &str,
+    pin: Option<&str>,
+    policy: LocalChangesPolicy,
+) -> Result<UpdateResult> {
+    let record = store
+        .get_skill_by_id(skill_id)?
+        .ok_or_else(|| anyhow::anyhow!("skill not found"))?;
+    if record.source_type != "git" {
+        anyhow::bail!("only git skills can be pinned");
+    }
+    let record = SkillRecord {
+        source_pin: pin
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(str::to_string),
+        ..record
+    };
+    let (staging_dir, new_revision) = stage_skill_update(app, store, &record)?;
+    promote_staged_update(app, store, record, &staging_dir, new_revision, policy)
+}
+
+/// Where a skill is installed from, as recorded on its `SkillRecord`.
+pub struct SkillSource {
+    pub source_type: String,
+    pub source_ref: String,
+    pub subpath: Option<String>,
+    pub pin: Option<String>,
+}
+
+/// Move a skill to a different source and pull central from it. Like a pin change,
+/// the new source is only saved if the update succeeds.
+pub fn set_skill_source<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    skill_id: &str,
+    source: SkillSource,
+    policy: LocalChangesPolicy,
 ) -> Result<UpdateResult> {
     let record = store
         .get_skill_by_id(skill_id)?
         .ok_or_else(|| anyhow::anyhow!("skill not found"))?;
+    let record = SkillRecord {
+        source_type: source.source_type,
+        source_ref: Some(source.source_ref),
+        source_subpath: source.subpath,
+        source_pin: source.pin,
+        // The old revision means nothing for the new source (local sources have none).
+        source_revision: None,
+        ..record
+    };
+    let (staging_dir, new_revision) = stage_skill_update(app, store, &record)?;
+    promote_staged_update(app, store, record, &staging_dir, new_revision, policy)
+}
+
+/// An update built into a staging dir next to central, waiting for confirm/abort.
+pub struct PreparedUpdate {
+    pub id: String,
+    pub skill_id: String,
+    pub name: String,
+    pub source_revision: Option<String>,
+    pub diff: SkillDiff,
+    /// Central was edited since the last install; confirming needs a policy other
+    /// than `Refuse`.
+    pub local_changes: bool,
+    staging_dir: PathBuf,
+    /// `updated_at` of the record the diff was computed against.
+    base_updated_at: i64,
+}
+
+/// First phase of an update: fetch the source into a staging dir and diff it against
+/// central. Nothing in central, the DB, or tool targets changes until confirmed.
+pub fn prepare_skill_update<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    skill_id: &str,
+) -> Result<PreparedUpdate> {
+    let record = store
+        .get_skill_by_id(skill_id)?
+        .ok_or_else(|| anyhow::anyhow!("skill not found"))?;
+    let (staging_dir, new_revision) = stage_skill_update(app, store, &record)?;
+
+    let inspected = diff_dirs(Path::new(&record.central_path), &staging_dir)
+        .context("failed to diff staged update")
+        .and_then(|diff| Ok((diff, has_local_changes(&record)?)));
+    let (diff, local_changes) = match inspected {
+        Ok(v) => v,
+        Err(err) => {
+            let _ = std::fs::remove_dir_all(&staging_dir);
+            return Err(err);
+        }
+    };
+
+    Ok(PreparedUpdate {
+        id: Uuid::new_v4().to_string(),
+        skill_id: record.id,
+        name: record.name,
+        source_revision: new_revision,
+        diff,
+        local_changes,
+        staging_dir,
+        base_updated_at: record.updated_at,
+    })
+}
 
+/// Second phase: promote a prepared update into central. Refuses if the skill was
+/// changed after the update was prepared, since the reviewed diff is then stale.
+pub fn confirm_skill_update<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    prepared: PreparedUpdate,
+    policy: LocalChangesPolicy,
+) -> Result<UpdateResult> {
+    let record = match store.get_skill_by_id(&prepared.skill_id)? {
+        Some(record) if record.updated_at == prepared.base_updated_at => record,
+        Some(_) => {
+            abort_skill_update(prepared)?;
+            anyhow::bail!("skill changed since the update was prepared; prepare it again");
+        }
+        None => {
+            abort_skill_update(prepared)?;
+            anyhow::bail!("skill not found");
+        }
+    };
+    if !prepared.staging_dir.exists() {
+        anyhow::bail!("staged update not found: {:?}", prepared.staging_dir);
+    }
+    promote_staged_update(
+        app,
+        store,
+        record,
+        &prepared.staging_dir,
+        prepared.source_revision,
+        policy,
+    )
+}
+
+pub fn abort_skill_update(prepared: PreparedUpdate) -> Result<()> {
+    if prepared.staging_dir.exists() {
+        std::fs::remove_dir_all(&prepared.staging_dir)
+            .with_context(|| format!("remove {:?}", prepared.staging_dir))?;
+    }
+    Ok(())
+}
+
+/// Prepared updates awaiting a decision from the UI, keyed by `PreparedUpdate::id`.
+#[derive(Default)]
+pub struct PendingUpdates(Mutex<HashMap<String, PreparedUpdate>>);
+
+impl PendingUpdates {
+    /// Track `prepared`, aborting any earlier pending update of the same skill.
+    pub fn insert(&self, prepared: PreparedUpdate) {
+        let mut pending = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        let stale: Vec<String> = pending
+            .values()
+            .filter(|p| p.skill_id == prepared.skill_id)
+            .map(|p| p.id.clone())
+            .collect();
+        for id in stale {
+            if let Some(old) = pending.remove(&id) {
+                let _ = abort_skill_update(old);
+            }
+        }
+        pending.insert(prepared.id.clone(), prepared);
+    }
+
+    pub fn take(&self, update_id: &str) -> Option<PreparedUpdate> {
+        let mut pending = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        pending.remove(update_id)
+    }
+}
+
+/// Name prefix of the sibling dirs updates and rollbacks are built in.
+const UPDATE_STAGING_PREFIX: &str = ".skills-hub-update-";
+/// Name prefix of the sibling dirs local edits are merged into upstream in.
+const MERGE_STAGING_PREFIX: &str = ".skills-hub-merge-";
+
+/// Remove staging dirs left in the central repo by updates that were neither confirmed
+/// nor aborted (pending updates only live in memory) or by a crash. Runs at startup
+/// after `recover_operations`; dirs a journaled swap still refers to are kept.
+pub fn cleanup_update_staging_dirs(store: &SkillStore, central_dir: &Path) -> Result<usize> {
+    let Ok(entries) = std::fs::read_dir(central_dir) else {
+        return Ok(0);
+    };
+    let journaled: Vec<PathBuf> = store
+        .list_operations()?
+        .into_iter()
+        .map(|op| PathBuf::from(op.staged_path))
+        .collect();
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = entry.path();
+        if !(name.starts_with(UPDATE_STAGING_PREFIX) || name.starts_with(MERGE_STAGING_PREFIX))
+            || journaled.contains(&path)
+        {
+            continue;
+        }
+        match remove_path_any(&path) {
+            Ok(()) => removed += 1,
+            Err(err) => log::warn!("failed to remove {:?}: {:#}", path, err),
+        }
+    }
+    Ok(removed)
+}
+
+/// Build the new content of `record` from its source in a sibling staging dir.
+/// Returns the staging dir and, for git sources, the fetched revision.
+fn stage_skill_update<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    record: &SkillRecord,
+) -> Result<(PathBuf, Option<String>)> {
     let central_path = PathBuf::from(record.central_path.clone());
     if !central_path.exists() {
         anyhow::bail!("central path not found: {:?}", central_path);
@@ -362,38 +687,53 @@ pub fn update_managed_skill_from_source<R: tauri::Runtime>(
         .ok_or_else(|| anyhow::anyhow!("invalid central path"))?
         .to_path_buf();
 
-    let now = now_ms();
-
     // Build new content in a sibling temp dir for safe swap.
-    let staging_dir = central_parent.join(format!(".skills-hub-update-{}", Uuid::new_v4()));
+    let staging_dir = central_parent.join(format!("{}{}", UPDATE_STAGING_PREFIX, Uuid::new_v4()));
     if staging_dir.exists() {
         let _ = std::fs::remove_dir_all(&staging_dir);
     }
 
-    let mut new_revision: Option<String> = None;
+    match copy_update_source(app, store, record, &staging_dir) {
+        Ok(new_revision) => Ok((staging_dir, new_revision)),
+        Err(err) => {
+            let _ = std::fs::remove_dir_all(&staging_dir);
+            Err(err)
+        }
+    }
+}
 
+fn copy_update_source<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    record: &SkillRecord,
+    staging_dir: &Path,
+) -> Result<Option<String>> {
     if record.source_type == "git" {
         let repo_url = record
             .source_ref
             .as_deref()
             .ok_or_else(|| anyhow::anyhow!("missing source_ref for git skill"))?;
-        let parsed = parse_github_url(repo_url);
-
-        let (repo_dir, rev) =
-            clone_to_cache(app, store, &parsed.clone_url, parsed.branch.as_deref())?;
-        new_revision = Some(rev);
-
-        let copy_src = if let Some(subpath) = &parsed.subpath {
-            repo_dir.join(subpath)
-        } else {
-            repo_dir.clone()
+        let parsed = parse_github_url(repo_url, &forge_registry(store));
+
+        let (repo_dir, rev) = clone_to_cache(
+            app,
+            store,
+            &parsed.clone_url,
+            parsed.branch.as_deref(),
+            record.source_pin.as_deref(),
+        )?;
+
+        let copy_src = match git_skill_subpath(record, &parsed) {
+            Some(subpath) => repo_dir.join(subpath),
+            None => repo_dir.clone(),
         };
         if !copy_src.exists() {
             anyhow::bail!("path not found in repo: {:?}", copy_src);
         }
 
-        copy_dir_recursive(&copy_src, &staging_dir)
+        copy_dir_recursive(&copy_src, staging_dir)
             .with_context(|| format!("copy {:?} -> {:?}", copy_src, staging_dir))?;
+        Ok(Some(rev))
     } else if record.source_type == "local" {
         let source = record
             .source_ref
@@ -403,25 +743,87 @@ pub fn update_managed_skill_from_source<R: tauri::Runtime>(
         if !source_path.exists() {
             anyhow::bail!("source path not found: {:?}", source_path);
         }
-        copy_dir_recursive(&source_path, &staging_dir)
+        copy_dir_recursive(&source_path, staging_dir)
             .with_context(|| format!("copy {:?} -> {:?}", source_path, staging_dir))?;
+        Ok(None)
+    } else if record.source_type == "archive" {
+        let source = record
+            .source_ref
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("missing source_ref for archive skill"))?;
+        with_fetched_archive(app, store, source, |root, checksum| {
+            let copy_src = match record.source_subpath.as_deref() {
+                Some(subpath) => root.join(subpath),
+                None => root.to_path_buf(),
+            };
+            if !copy_src.join("SKILL.md").exists() {
+                anyhow::bail!("path not found in archive: {:?}", copy_src);
+            }
+            copy_dir_recursive(&copy_src, staging_dir)
+                .with_context(|| format!("copy {:?} -> {:?}", copy_src, staging_dir))?;
+            Ok(Some(checksum.to_string()))
+        })
     } else {
         anyhow::bail!("unsupported source_type for update: {}", record.source_type);
     }
+}
 
-    // Swap: remove old dir and rename staging into place (best effort).
-    std::fs::remove_dir_all(&central_path)
-        .with_context(|| format!("failed to remove old central dir {:?}", central_path))?;
-    if let Err(err) = std::fs::rename(&staging_dir, &central_path) {
-        // Fallback for cross-device rename: copy then delete staging.
-        copy_dir_recursive(&staging_dir, &central_path)
-            .with_context(|| format!("fallback copy {:?} -> {:?}", staging_dir, central_path))?;
-        let _ = std::fs::remove_dir_all(&staging_dir);
-        // Still surface original rename error in logs for troubleshooting.
-        eprintln!("[update] rename warning: {}", err);
+/// Snapshot the current version, swap `staging_dir` into central, and record the result.
+/// Local edits to central are handled according to `policy`.
+fn promote_staged_update<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    record: SkillRecord,
+    staging_dir: &Path,
+    new_revision: Option<String>,
+    policy: LocalChangesPolicy,
+) -> Result<UpdateResult> {
+    let central_path = PathBuf::from(record.central_path.clone());
+    let now = now_ms();
+    let skill_lock = SkillLock::for_skill(&record.id);
+    let _guard = skill_lock.lock();
+
+    let merged = match resolve_local_changes(app, &record, staging_dir, policy) {
+        Ok(v) => v,
+        Err(err) => {
+            let _ = std::fs::remove_dir_all(staging_dir);
+            return Err(err);
+        }
+    };
+
+    // Keep the current version so a bad upstream change can be rolled back.
+    let snapshot = resolve_history_root(app)
+        .and_then(|history_root| snapshot_skill_dir(store, &history_root, &record));
+    if let Err(err) = snapshot {
+        let _ = std::fs::remove_dir_all(staging_dir);
+        if let Some((merged_dir, _)) = &merged {
+            let _ = std::fs::remove_dir_all(merged_dir);
+        }
+        return Err(err.context("failed to snapshot current version before update"));
     }
 
-    let content_hash = compute_content_hash(&central_path);
+    // The recorded hash and base copy always describe pristine upstream content, so
+    // merged-in local edits still count as local changes on the next update.
+    let content_hash = compute_content_hash(staging_dir);
+    let merge = match merged {
+        Some((merged_dir, report)) => {
+            keep_base_copy(app, &record.id, staging_dir);
+            let _ = std::fs::remove_dir_all(staging_dir);
+            if let Err(err) = swap_staging_into_place(store, &merged_dir, &central_path) {
+                let _ = remove_path_any(&merged_dir);
+                return Err(err);
+            }
+            Some(report)
+        }
+        None => {
+            if let Err(err) = swap_staging_into_place(store, staging_dir, &central_path) {
+                let _ = remove_path_any(staging_dir);
+                return Err(err);
+            }
+            keep_base_copy(app, &record.id, &central_path);
+            None
+        }
+    };
 
     // Update DB skill row.
     let updated = SkillRecord {
@@ -430,6 +832,8 @@ pub fn update_managed_skill_from_source<R: tauri::Runtime>(
         source_type: record.source_type.clone(),
         source_ref: record.source_ref.clone(),
         source_revision: new_revision.clone().or(record.source_revision.clone()),
+        source_subpath: record.source_subpath.clone(),
+        source_pin: record.source_pin.clone(),
         central_path: record.central_path.clone(),
         content_hash: content_hash.clone(),
         created_at: record.created_at,
@@ -439,45 +843,345 @@ pub fn update_managed_skill_from_source<R: tauri::Runtime>(
         status: "ok".to_string(),
     };
     store.upsert_skill(&updated)?;
+    refresh_skill_manifest(store, &record.id, &central_path)?;
+
+    let updated_targets = resync_copy_targets(store, &record.id, &central_path, now)?;
+
+    Ok(UpdateResult {
+        skill_id: record.id,
+        name: record.name,
+        central_path,
+        content_hash,
+        source_revision: new_revision,
+        updated_targets,
+        merge,
+    })
+}
+
+/// Apply `policy` when central has local edits. For `Merge`, returns a sibling dir
+/// holding upstream with the edits merged in; `staging_dir` itself stays pristine.
+fn resolve_local_changes<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    record: &SkillRecord,
+    staging_dir: &Path,
+    policy: LocalChangesPolicy,
+) -> Result<Option<(PathBuf, MergeReport)>> {
+    if !has_local_changes(record)? {
+        return Ok(None);
+    }
+    match policy {
+        LocalChangesPolicy::Refuse => anyhow::bail!("LOCAL_CHANGES|{}", record.central_path),
+        LocalChangesPolicy::Overwrite => Ok(None),
+        LocalChangesPolicy::Merge => {
+            let base = base_copy_path(&resolve_history_root(app)?, &record.id);
+            if !base.exists() {
+                anyhow::bail!(
+                    "no base copy of skill {} to merge local changes against",
+                    record.name
+                );
+            }
+            let merged_dir =
+                staging_dir.with_file_name(format!("{}{}", MERGE_STAGING_PREFIX, Uuid::new_v4()));
+            copy_dir_recursive(staging_dir, &merged_dir)
+                .with_context(|| format!("copy {:?} -> {:?}", staging_dir, merged_dir))?;
+            match merge_into_upstream(&base, Path::new(&record.central_path), &merged_dir) {
+                Ok(report) => Ok(Some((merged_dir, report))),
+                Err(err) => {
+                    let _ = std::fs::remove_dir_all(&merged_dir);
+                    Err(err.context("failed to merge local changes"))
+                }
+            }
+        }
+    }
+}
+
+/// Restore a skill's central dir from one of its history snapshots. The version being
+/// replaced is snapshotted first, so a rollback can itself be undone.
+pub fn rollback_managed_skill<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    skill_id: &str,
+    snapshot_id: &str,
+) -> Result<UpdateResult> {
+    let record = store
+        .get_skill_by_id(skill_id)?
+        .ok_or_else(|| anyhow::anyhow!("skill not found"))?;
+    let snapshot = store
+        .get_skill_snapshot(snapshot_id)?
+        .filter(|s| s.skill_id == record.id)
+        .ok_or_else(|| anyhow::anyhow!("snapshot not found"))?;
+    let snapshot_path = PathBuf::from(&snapshot.snapshot_path);
+    if !snapshot_path.exists() {
+        anyhow::bail!("snapshot path not found: {:?}", snapshot_path);
+    }
+    let skill_lock = SkillLock::for_skill(&record.id);
+    let _guard = skill_lock.lock();
+
+    let central_path = PathBuf::from(record.central_path.clone());
+    let central_parent = central_path
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("invalid central path"))?
+        .to_path_buf();
+    let now = now_ms();
+
+    // Stage the snapshot before taking a new one: pruning may remove the snapshot we restore.
+    let staging_dir = central_parent.join(format!("{}{}", UPDATE_STAGING_PREFIX, Uuid::new_v4()));
+    copy_dir_recursive(&snapshot_path, &staging_dir)
+        .with_context(|| format!("copy {:?} -> {:?}", snapshot_path, staging_dir))?;
+
+    if central_path.exists() {
+        let snapshot = resolve_history_root(app)
+            .and_then(|history_root| snapshot_skill_dir(store, &history_root, &record));
+        if let Err(err) = snapshot {
+            let _ = std::fs::remove_dir_all(&staging_dir);
+            return Err(err.context("failed to snapshot current version before rollback"));
+        }
+    }
+
+    if let Err(err) = swap_staging_into_place(store, &staging_dir, &central_path) {
+        let _ = remove_path_any(&staging_dir);
+        return Err(err);
+    }
+    keep_base_copy(app, &record.id, &central_path);
+
+    let content_hash = compute_content_hash(&central_path);
+    let updated = SkillRecord {
+        source_revision: snapshot.source_revision.clone(),
+        content_hash: content_hash.clone(),
+        updated_at: now,
+        last_seen_at: now,
+        status: "ok".to_string(),
+        ..record.clone()
+    };
+    store.upsert_skill(&updated)?;
+    refresh_skill_manifest(store, skill_id, &central_path)?;
+
+    let updated_targets = resync_copy_targets(store, skill_id, &central_path, now)?;
+
+    Ok(UpdateResult {
+        skill_id: record.id,
+        name: record.name,
+        central_path,
+        content_hash,
+        source_revision: snapshot.source_revision,
+        updated_targets,
+        merge: None,
+    })
+}
 
+/// Replace `central_path` with the fully built `staging_dir`. The old version is kept
+/// aside until the new one is in place, so a crash never leaves the skill without a
+/// folder (`op_journal` finishes or undoes the swap on the next start).
+fn swap_staging_into_place(
+    store: &SkillStore,
+    staging_dir: &Path,
+    central_path: &Path,
+) -> Result<()> {
+    Swap::adopt(store, "central_update", staging_dir, central_path)?
+        .commit()
+        .with_context(|| format!("failed to swap update into {:?}", central_path))
+}
+
+static SKILL_LOCKS: OnceLock<Mutex<HashMap<String, Arc<Mutex<()>>>>> = OnceLock::new();
+
+/// Held while a skill's central folder is swapped or its copy targets are re-synced, so
+/// an update or rollback and the watcher's refresh of the same skill never interleave.
+pub struct SkillLock(Arc<Mutex<()>>);
+
+impl SkillLock {
+    pub fn for_skill(skill_id: &str) -> Self {
+        let locks = SKILL_LOCKS.get_or_init(|| Mutex::new(HashMap::new()));
+        let mut locks = locks.lock().unwrap_or_else(|err| err.into_inner());
+        Self(locks.entry(skill_id.to_string()).or_default().clone())
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, ()> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// Re-sync every copy, hardlink and reflink target of a skill so central changes propagate.
+/// Returns the tools that were refreshed.
+pub fn resync_copy_targets(
+    store: &SkillStore,
+    skill_id: &str,
+    central_path: &Path,
+    now: i64,
+) -> Result<Vec<String>> {
     // If any targets are "copy", re-sync them so changes propagate. Symlinks update automatically.
     // Cursor 目前不支持软链/junction，因此无论历史 mode 如何，都需要强制 copy 回灌。
+    // Tools with a format transformer are re-rendered from the updated SKILL.md.
     let targets = store.list_skill_targets(skill_id)?;
     let mut updated_targets: Vec<String> = Vec::new();
     for t in targets {
+        let adapter = adapter_by_key(&t.tool);
         // Skip if tool not installed anymore.
-        if let Some(adapter) = adapter_by_key(&t.tool) {
-            if !is_tool_installed(&adapter).unwrap_or(false) {
+        if let Some(adapter) = &adapter {
+            if !is_tool_installed(adapter).unwrap_or(false) {
                 continue;
             }
         }
-        let force_copy = t.mode == "copy" || t.tool == "cursor";
-        if force_copy {
+        if let Some(transformer) = adapter.as_ref().and_then(transformer_for) {
+            let stored = PathBuf::from(&t.target_path);
+            // Targets synced before the tool had a transformer are skill folders.
+            let output = if t.mode == "render" {
+                stored.clone()
+            } else {
+                transformer.output_path(&stored)
+            };
+            let sync_res = sync_rendered_with_overwrite(transformer, central_path, &output, true)?;
+            if output != stored {
+                remove_path_any(&stored)?;
+            }
+            store.upsert_skill_target(&super::skill_store::SkillTargetRecord {
+                target_path: sync_res.target_path.to_string_lossy().to_string(),
+                mode: "render".to_string(),
+                status: "ok".to_string(),
+                last_error: None,
+                synced_at: Some(now),
+                rendered_hash: sync_res.rendered_hash,
+                ..t.clone()
+            })?;
+            updated_targets.push(t.tool.clone());
+            continue;
+        }
+        // Hardlinked files still point at the replaced central files, so link trees are
+        // rebuilt too; each keeps its mode (falling back to copy if it is unavailable).
+        let mode = match SyncMode::from_key(&t.mode) {
+            Some(mode @ (SyncMode::Copy | SyncMode::Hardlink | SyncMode::Reflink)) => Some(mode),
+            _ if t.tool == "cursor" => Some(SyncMode::Copy),
+            _ => None,
+        };
+        if let Some(mode) = mode {
             let target_path = PathBuf::from(&t.target_path);
-            let sync_res = sync_dir_copy_with_overwrite(&central_path, &target_path, true)?;
+            let sync_res =
+                sync_dir_tree_with_overwrite(store, central_path, &target_path, true, mode)?;
             let record = super::skill_store::SkillTargetRecord {
                 id: t.id.clone(),
                 skill_id: t.skill_id.clone(),
                 tool: t.tool.clone(),
+                scope: t.scope.clone(),
                 target_path: sync_res.target_path.to_string_lossy().to_string(),
-                mode: "copy".to_string(),
+                mode: sync_res.mode_used.as_key().to_string(),
                 status: "ok".to_string(),
                 last_error: None,
                 synced_at: Some(now),
+                rendered_hash: None,
             };
             store.upsert_skill_target(&record)?;
             updated_targets.push(t.tool.clone());
         }
     }
+    Ok(updated_targets)
+}
 
-    Ok(UpdateResult {
-        skill_id: record.id,
-        name: record.name,
-        central_path,
-        content_hash,
-        source_revision: new_revision,
-        updated_targets,
-    })
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateStatus {
+    UpToDate,
+    UpdateAvailable,
+    SourceMissing,
+    Error,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct SkillUpdateCheck {
+    pub skill_id: String,
+    pub name: String,
+    pub status: UpdateStatus,
+    pub current_revision: Option<String>,
+    pub latest_revision: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Report which git skills have upstream changes, without touching central or targets.
+/// Non-git skills are skipped; per-skill failures are reported, not propagated.
+pub fn check_updates<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+) -> Result<Vec<SkillUpdateCheck>> {
+    let skills = store.list_skills()?;
+    Ok(skills
+        .iter()
+        .filter(|skill| skill.source_type == "git")
+        .map(|skill| check_skill_update(app, store, skill))
+        .collect())
+}
+
+pub fn check_skill_update<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    record: &SkillRecord,
+) -> SkillUpdateCheck {
+    let mut check = SkillUpdateCheck {
+        skill_id: record.id.clone(),
+        name: record.name.clone(),
+        status: UpdateStatus::Error,
+        current_revision: record.source_revision.clone(),
+        latest_revision: None,
+        error: None,
+    };
+
+    let Some(repo_url) = record.source_ref.as_deref() else {
+        check.error = Some("missing source_ref for git skill".to_string());
+        return check;
+    };
+    let parsed = parse_github_url(repo_url, &forge_registry(store));
+
+    let (repo_dir, head) = match clone_to_cache(
+        app,
+        store,
+        &parsed.clone_url,
+        parsed.branch.as_deref(),
+        record.source_pin.as_deref(),
+    ) {
+        Ok(v) => v,
+        Err(err) => {
+            if is_missing_remote_error(&err) {
+                check.status = UpdateStatus::SourceMissing;
+            }
+            check.error = Some(format!("{:#}", err));
+            return check;
+        }
+    };
+    check.latest_revision = Some(head.clone());
+
+    let skill_dir = match git_skill_subpath(record, &parsed) {
+        Some(subpath) => repo_dir.join(subpath),
+        None => repo_dir,
+    };
+    if !skill_dir.exists() {
+        check.status = UpdateStatus::SourceMissing;
+        check.error = Some(format!("path not found in repo: {:?}", skill_dir));
+        return check;
+    }
+
+    if record.source_revision.as_deref() == Some(head.as_str()) {
+        check.status = UpdateStatus::UpToDate;
+        return check;
+    }
+
+    // New commits upstream may not touch this skill's folder (monorepos); the content
+    // hash tells those apart when we have one.
+    let unchanged = match (&record.content_hash, hash_dir(&skill_dir)) {
+        (Some(current), Ok(latest)) => *current == latest,
+        _ => false,
+    };
+    check.status = if unchanged {
+        UpdateStatus::UpToDate
+    } else {
+        UpdateStatus::UpdateAvailable
+    };
+    check
+}
+
+/// The fetch failed because git said the remote (or its tracked ref) is gone, as
+/// opposed to auth, network or any other failure.
+fn is_missing_remote_error(err: &anyhow::Error) -> bool {
+    if err.is::<GitAuthError>() {
+        return false;
+    }
+    err.is::<GitRemoteMissingError>()
 }
 
 #[derive(Clone, Debug, serde::Serialize)]
@@ -485,6 +1189,10 @@ pub struct GitSkillCandidate {
     pub name: String,
     pub description: Option<String>,
     pub subpath: String,
+    /// Search root the skill was found under (`.` for the repo root, or the index file).
+    pub root: String,
+    /// Directory levels below `root`.
+    pub depth: usize,
 }
 
 #[derive(Clone, Debug, serde::Serialize)]
@@ -494,200 +1202,106 @@ pub struct LocalSkillCandidate {
     pub subpath: String,
     pub valid: bool,
     pub reason: Option<String>,
+    /// Parser details (message and position in SKILL.md) when `reason` came from it.
+    pub error: Option<ManifestError>,
+    pub root: String,
+    pub depth: usize,
 }
 
+/// Skills in a git repo, found by `discover_skill_dirs` (at most `max_depth` levels deep).
 pub fn list_git_skills<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     store: &SkillStore,
     repo_url: &str,
+    max_depth: Option<usize>,
 ) -> Result<Vec<GitSkillCandidate>> {
-    let parsed = parse_github_url(repo_url);
-    let (repo_dir, _rev) = clone_to_cache(app, store, &parsed.clone_url, parsed.branch.as_deref())?;
+    let parsed = parse_github_url(repo_url, &forge_registry(store));
+    let (repo_dir, _rev) = clone_to_cache(
+        app,
+        store,
+        &parsed.clone_url,
+        parsed.branch.as_deref(),
+        parsed.pin.as_deref(),
+    )?;
+
+    // A folder URL is either one skill or a subtree to search.
+    let (base, prefix) = match &parsed.subpath {
+        Some(subpath) => (repo_dir.join(subpath), Some(subpath.trim_matches('/'))),
+        None => (repo_dir.clone(), None),
+    };
+    if !base.is_dir() {
+        return Ok(Vec::new());
+    }
 
     let mut out: Vec<GitSkillCandidate> = Vec::new();
-
-    // If user provided a folder URL, treat it as a single candidate.
-    if let Some(subpath) = &parsed.subpath {
-        let dir = repo_dir.join(subpath);
-        if dir.is_dir() && dir.join("SKILL.md").exists() {
-            let (name, desc) = parse_skill_md(&dir.join("SKILL.md")).unwrap_or((
-                dir.file_name()
-                    .unwrap_or_default()
-                    .to_string_lossy()
-                    .to_string(),
-                None,
-            ));
-            out.push(GitSkillCandidate {
-                name,
-                description: desc,
-                subpath: subpath.to_string(),
-            });
+    for found in discover_skill_dirs(&base, max_depth)? {
+        if !found.has_skill_md {
+            continue;
         }
-        return Ok(out);
-    }
-
-    // Root-level skill
-    let root_skill = repo_dir.join("SKILL.md");
-    if root_skill.exists() {
-        let (name, desc) = parse_skill_md(&root_skill).unwrap_or(("root-skill".to_string(), None));
+        let dir = base.join(&found.subpath);
+        let fallback = if found.subpath == "." && prefix.is_none() {
+            "root-skill".to_string()
+        } else {
+            dir_name(&dir)
+        };
+        let (name, desc) = parse_skill_md(&dir.join("SKILL.md")).unwrap_or((fallback, None));
         out.push(GitSkillCandidate {
             name,
             description: desc,
-            subpath: ".".to_string(),
+            subpath: join_subpath(prefix, &found.subpath),
+            root: join_subpath(prefix, &found.root),
+            depth: found.depth,
         });
     }
 
-    // Standard discovery locations (subset aligned with add-skill):
-    // skills/*, skills/.curated/*, skills/.experimental/*, skills/.system/*
-    for base in [
-        "skills",
-        "skills/.curated",
-        "skills/.experimental",
-        "skills/.system",
-    ] {
-        let base_dir = repo_dir.join(base);
-        if !base_dir.exists() {
-            continue;
-        }
-        if let Ok(rd) = std::fs::read_dir(&base_dir) {
-            for entry in rd.flatten() {
-                let p = entry.path();
-                if !p.is_dir() {
-                    continue;
-                }
-                let skill_md = p.join("SKILL.md");
-                if !skill_md.exists() {
-                    continue;
-                }
-                let (name, desc) = parse_skill_md(&skill_md).unwrap_or((
-                    p.file_name()
-                        .unwrap_or_default()
-                        .to_string_lossy()
-                        .to_string(),
-                    None,
-                ));
-                let rel = p
-                    .strip_prefix(&repo_dir)
-                    .unwrap_or(&p)
-                    .to_string_lossy()
-                    .to_string();
-                out.push(GitSkillCandidate {
-                    name,
-                    description: desc,
-                    subpath: rel,
-                });
-            }
-        }
-    }
-
     out.sort_by(|a, b| a.name.cmp(&b.name));
     out.dedup_by(|a, b| a.subpath == b.subpath);
 
     Ok(out)
 }
 
-pub fn list_local_skills(base_path: &Path) -> Result<Vec<LocalSkillCandidate>> {
+/// Skills under a local folder, found by `discover_skill_dirs`. Folders that cannot be
+/// installed are listed too, with `valid: false` and the reason.
+pub fn list_local_skills(
+    base_path: &Path,
+    max_depth: Option<usize>,
+) -> Result<Vec<LocalSkillCandidate>> {
     if !base_path.exists() {
         anyhow::bail!("source path not found: {:?}", base_path);
     }
 
     let mut out: Vec<LocalSkillCandidate> = Vec::new();
-
-    let root_skill = base_path.join("SKILL.md");
-    if root_skill.exists() {
-        match parse_skill_md_with_reason(&root_skill) {
-            Ok((name, desc)) => {
-                out.push(LocalSkillCandidate {
-                    name,
-                    description: desc,
-                    subpath: ".".to_string(),
-                    valid: true,
-                    reason: None,
-                });
-            }
-            Err(reason) => {
-                let fallback_name = base_path
-                    .file_name()
-                    .unwrap_or_default()
-                    .to_string_lossy()
-                    .to_string();
-                out.push(LocalSkillCandidate {
-                    name: if fallback_name.is_empty() {
-                        "root-skill".to_string()
-                    } else {
-                        fallback_name
-                    },
-                    description: None,
-                    subpath: ".".to_string(),
-                    valid: false,
-                    reason: Some(reason.to_string()),
-                });
-            }
-        }
-    }
-
-    for base in [
-        "skills",
-        "skills/.curated",
-        "skills/.experimental",
-        "skills/.system",
-    ] {
-        let base_dir = base_path.join(base);
-        if !base_dir.exists() {
-            continue;
-        }
-        if let Ok(rd) = std::fs::read_dir(&base_dir) {
-            for entry in rd.flatten() {
-                let p = entry.path();
-                if !p.is_dir() {
-                    continue;
-                }
-                let skill_md = p.join("SKILL.md");
-                let rel = p
-                    .strip_prefix(base_path)
-                    .unwrap_or(&p)
-                    .to_string_lossy()
-                    .to_string();
-                if !skill_md.exists() {
-                    out.push(LocalSkillCandidate {
-                        name: p
-                            .file_name()
-                            .unwrap_or_default()
-                            .to_string_lossy()
-                            .to_string(),
-                        description: None,
-                        subpath: rel,
-                        valid: false,
-                        reason: Some("missing_skill_md".to_string()),
-                    });
-                    continue;
-                }
-                match parse_skill_md_with_reason(&skill_md) {
-                    Ok((name, desc)) => {
-                        out.push(LocalSkillCandidate {
-                            name,
-                            description: desc,
-                            subpath: rel,
-                            valid: true,
-                            reason: None,
-                        });
-                    }
-                    Err(reason) => {
-                        out.push(LocalSkillCandidate {
-                            name: p
-                                .file_name()
-                                .unwrap_or_default()
-                                .to_string_lossy()
-                                .to_string(),
-                            description: None,
-                            subpath: rel,
-                            valid: false,
-                            reason: Some(reason.to_string()),
-                        });
-                    }
-                }
-            }
-        }
+    for found in discover_skill_dirs(base_path, max_depth)? {
+        let dir = base_path.join(&found.subpath);
+        let fallback_name = match dir_name(&dir) {
+            name if name.is_empty() => "root-skill".to_string(),
+            name => name,
+        };
+        let parsed = if found.has_skill_md {
+            SkillManifest::read(&dir.join("SKILL.md")).map_err(Some)
+        } else {
+            Err(None)
+        };
+        let (name, description, reason, error) = match parsed {
+            Ok(manifest) => (manifest.name, manifest.description, None, None),
+            Err(Some(err)) => (fallback_name, None, Some(err.reason.to_string()), Some(err)),
+            Err(None) => (
+                fallback_name,
+                None,
+                Some("missing_skill_md".to_string()),
+                None,
+            ),
+        };
+        out.push(LocalSkillCandidate {
+            name,
+            description,
+            subpath: found.subpath,
+            valid: reason.is_none(),
+            reason,
+            error,
+            root: found.root,
+            depth: found.depth,
+        });
     }
 
     out.sort_by(|a, b| a.name.cmp(&b.name));
@@ -696,6 +1310,22 @@ pub fn list_local_skills(base_path: &Path) -> Result<Vec<LocalSkillCandidate>> {
     Ok(out)
 }
 
+fn dir_name(dir: &Path) -> String {
+    dir.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+/// `subpath` relative to the repo root when discovery ran under the folder `prefix`.
+fn join_subpath(prefix: Option<&str>, subpath: &str) -> String {
+    match prefix {
+        Some(prefix) if subpath == "." => prefix.to_string(),
+        Some(prefix) => format!("{}/{}", prefix, subpath),
+        None => subpath.to_string(),
+    }
+}
+
 pub fn install_git_skill_from_selection<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     store: &SkillStore,
@@ -703,7 +1333,22 @@ pub fn install_git_skill_from_selection<R: tauri::Runtime>(
     subpath: &str,
     name: Option<String>,
 ) -> Result<InstallResult> {
-    let parsed = parse_github_url(repo_url);
+    install_git_skill_from_selection_at(app, store, repo_url, subpath, name, None)
+}
+
+/// [`install_git_skill_from_selection`] checked out at `pin`, like [`install_git_skill_at`].
+pub fn install_git_skill_from_selection_at<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    repo_url: &str,
+    subpath: &str,
+    name: Option<String>,
+    pin: Option<&str>,
+) -> Result<InstallResult> {
+    let mut parsed = parse_github_url(repo_url, &forge_registry(store));
+    if let Some(pin) = pin {
+        parsed.pin = Some(pin.to_string());
+    }
     let display_name = name.unwrap_or_else(|| {
         subpath
             .rsplit('/')
@@ -714,13 +1359,18 @@ pub fn install_git_skill_from_selection<R: tauri::Runtime>(
 
     let central_dir = resolve_central_repo_path(app, store)?;
     ensure_central_repo(&central_dir)?;
-    let central_path = central_dir.join(&display_name);
+    let central_path = skill_dir_in(&central_dir, &display_name)?;
     if central_path.exists() {
         anyhow::bail!("skill already exists in central repo: {:?}", central_path);
     }
 
-    let (repo_dir, revision) =
-        clone_to_cache(app, store, &parsed.clone_url, parsed.branch.as_deref())?;
+    let (repo_dir, revision) = clone_to_cache(
+        app,
+        store,
+        &parsed.clone_url,
+        parsed.branch.as_deref(),
+        parsed.pin.as_deref(),
+    )?;
 
     let copy_src = if subpath == "." {
         repo_dir.clone()
@@ -742,6 +1392,8 @@ pub fn install_git_skill_from_selection<R: tauri::Runtime>(
         source_type: "git".to_string(),
         source_ref: Some(repo_url.to_string()),
         source_revision: Some(revision),
+        source_subpath: (subpath != ".").then(|| subpath.to_string()),
+        source_pin: parsed.pin.clone(),
         central_path: central_path.to_string_lossy().to_string(),
         content_hash: content_hash.clone(),
         created_at: now,
@@ -751,6 +1403,8 @@ pub fn install_git_skill_from_selection<R: tauri::Runtime>(
         status: "ok".to_string(),
     };
     store.upsert_skill(&record)?;
+    refresh_skill_manifest(store, &record.id, &central_path)?;
+    keep_base_copy(app, &record.id, &central_path);
 
     Ok(InstallResult {
         skill_id: record.id,
@@ -792,6 +1446,123 @@ pub fn install_local_skill_from_selection<R: tauri::Runtime>(
     install_local_skill(app, store, &selected_dir, Some(display_name))
 }
 
+pub fn list_archive_skills<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    source: &str,
+) -> Result<Vec<LocalSkillCandidate>> {
+    with_fetched_archive(app, store, source, |root, _| list_local_skills(root, None))
+}
+
+/// Install a skill from a zip/tar archive or a bare SKILL.md, given as a URL or a local
+/// file. `subpath` picks one skill when the archive holds several.
+pub fn install_archive_skill<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    source: &str,
+    subpath: Option<&str>,
+    name: Option<String>,
+) -> Result<InstallResult> {
+    let source = source.trim();
+    let central_dir = resolve_central_repo_path(app, store)?;
+    ensure_central_repo(&central_dir)?;
+
+    let (central_path, display_name, subpath, checksum) =
+        with_fetched_archive(app, store, source, |root, checksum| {
+            let subpath = match subpath {
+                Some(subpath) => subpath.to_string(),
+                None => {
+                    let valid: Vec<_> = list_local_skills(root, None)?
+                        .into_iter()
+                        .filter(|c| c.valid)
+                        .collect();
+                    match valid.as_slice() {
+                        [] => anyhow::bail!("SKILL_INVALID|missing_skill_md"),
+                        [only] => only.subpath.clone(),
+                        _ => anyhow::bail!(
+                            "MULTI_SKILLS|该压缩包包含多个 Skills，请先选择要安装的 Skill。"
+                        ),
+                    }
+                }
+            };
+            let selected_dir = if subpath == "." {
+                root.to_path_buf()
+            } else {
+                root.join(&subpath)
+            };
+            let skill_md = selected_dir.join("SKILL.md");
+            if !skill_md.exists() {
+                anyhow::bail!("SKILL_INVALID|missing_skill_md");
+            }
+            let (parsed_name, _desc) = parse_skill_md_with_reason(&skill_md)
+                .map_err(|reason| anyhow::anyhow!("SKILL_INVALID|{}", reason))?;
+
+            let display_name = name.clone().unwrap_or(parsed_name);
+            let central_path = skill_dir_in(&central_dir, &display_name)?;
+            if central_path.exists() {
+                anyhow::bail!("skill already exists in central repo: {:?}", central_path);
+            }
+            copy_dir_recursive(&selected_dir, &central_path)
+                .with_context(|| format!("copy {:?} -> {:?}", selected_dir, central_path))?;
+            Ok((central_path, display_name, subpath, checksum.to_string()))
+        })?;
+
+    let now = now_ms();
+    let content_hash = compute_content_hash(&central_path);
+    let record = SkillRecord {
+        id: Uuid::new_v4().to_string(),
+        name: display_name,
+        source_type: "archive".to_string(),
+        source_ref: Some(source.to_string()),
+        source_revision: Some(checksum),
+        source_subpath: (subpath != ".").then_some(subpath),
+        source_pin: None,
+        central_path: central_path.to_string_lossy().to_string(),
+        content_hash: content_hash.clone(),
+        created_at: now,
+        updated_at: now,
+        last_sync_at: None,
+        last_seen_at: now,
+        status: "ok".to_string(),
+    };
+    store.upsert_skill(&record)?;
+    refresh_skill_manifest(store, &record.id, &central_path)?;
+    keep_base_copy(app, &record.id, &central_path);
+
+    Ok(InstallResult {
+        skill_id: record.id,
+        name: record.name,
+        central_path,
+        content_hash,
+    })
+}
+
+/// Fetch and extract `source` into a throwaway temp dir, run `f` on the skill root and
+/// checksum, then remove the temp dir.
+fn with_fetched_archive<R: tauri::Runtime, T>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    source: &str,
+    f: impl FnOnce(&Path, &str) -> Result<T>,
+) -> Result<T> {
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .context("failed to resolve app cache dir")?;
+    let work_dir = cache_dir.join(format!("skills-hub-git-archive-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&work_dir)
+        .with_context(|| format!("failed to create temp dir {:?}", work_dir))?;
+
+    let result = mark_temp_dir(&work_dir).and_then(|_| {
+        let token = url_host(source)
+            .and_then(|host| credential_store(app).ok()?.token_for_host(store, &host));
+        let fetched = fetch_archive(source, &work_dir, token.as_ref().map(|t| t.token.as_str()))?;
+        f(&fetched.root, &fetched.checksum)
+    });
+    let _ = std::fs::remove_dir_all(&work_dir);
+    result
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct RepoCacheMeta {
     last_fetched_ms: i64,
@@ -805,6 +1576,7 @@ fn clone_to_cache<R: tauri::Runtime>(
     store: &SkillStore,
     clone_url: &str,
     branch: Option<&str>,
+    pin: Option<&str>,
 ) -> Result<(PathBuf, String)> {
     let started = std::time::Instant::now();
     let cache_dir = app
@@ -815,8 +1587,8 @@ fn clone_to_cache<R: tauri::Runtime>(
     std::fs::create_dir_all(&cache_root)
         .with_context(|| format!("failed to create cache dir {:?}", cache_root))?;
 
-    let repo_dir = cache_root.join(repo_cache_key(clone_url, branch));
-    let meta_path = repo_dir.join(".skills-hub-cache.json");
+    let repo_dir = cache_root.join(repo_cache_key(clone_url, branch, pin));
+    let meta_path = repo_dir.join(CACHE_META_FILE);
 
     let lock = GIT_CACHE_LOCK.get_or_init(|| Mutex::new(()));
     let _guard = lock.lock().unwrap_or_else(|err| err.into_inner());
@@ -826,7 +1598,11 @@ fn clone_to_cache<R: tauri::Runtime>(
             if let Ok(meta) = serde_json::from_str::<RepoCacheMeta>(&meta) {
                 if let Some(head) = meta.head {
                     let ttl_ms = get_git_cache_ttl_secs(store).saturating_mul(1000);
-                    if ttl_ms > 0 && now_ms().saturating_sub(meta.last_fetched_ms) < ttl_ms {
+                    // A checkout of a full commit SHA can never go stale.
+                    let immutable = pin.is_some_and(|p| p.len() == 40 && head == p);
+                    if immutable
+                        || (ttl_ms > 0 && now_ms().saturating_sub(meta.last_fetched_ms) < ttl_ms)
+                    {
                         log::info!(
                             "[installer] git cache hit (fresh) {}s url={} branch={:?} repo_dir={:?}",
                             started.elapsed().as_secs_f32(),
@@ -849,14 +1625,22 @@ fn clone_to_cache<R: tauri::Runtime>(
         repo_dir
     );
 
-    let rev = match clone_or_pull(clone_url, &repo_dir, branch) {
+    let auth = git_auth_for(store, &credential_store(app)?, clone_url);
+    let fetch = || match pin {
+        Some(rev) => checkout_revision(clone_url, &repo_dir, rev, &auth),
+        None => clone_or_pull(clone_url, &repo_dir, branch, &auth),
+    };
+    let rev = match fetch() {
         Ok(rev) => rev,
+        Err(err) if err.is::<GitAuthError>() || err.is::<GitRemoteMissingError>() => {
+            return Err(err)
+        }
         Err(err) => {
             // If cache got corrupted, retry once from a clean state.
             if repo_dir.exists() {
                 let _ = std::fs::remove_dir_all(&repo_dir);
             }
-            clone_or_pull(clone_url, &repo_dir, branch).with_context(|| format!("{:#}", err))?
+            fetch().with_context(|| format!("{:#}", err))?
         }
     };
 
@@ -879,7 +1663,7 @@ fn clone_to_cache<R: tauri::Runtime>(
     Ok((repo_dir, rev))
 }
 
-fn repo_cache_key(clone_url: &str, branch: Option<&str>) -> String {
+fn repo_cache_key(clone_url: &str, branch: Option<&str>, pin: Option<&str>) -> String {
     use sha2::Digest;
     let mut hasher = sha2::Sha256::new();
     hasher.update(clone_url.as_bytes());
@@ -887,6 +1671,11 @@ fn repo_cache_key(clone_url: &str, branch: Option<&str>) -> String {
     if let Some(b) = branch {
         hasher.update(b.as_bytes());
     }
+    // Pinned checkouts get their own cache dir so they never move a branch checkout.
+    if let Some(p) = pin {
+        hasher.update(b"\n@");
+        hasher.update(p.as_bytes());
+    }
     hex::encode(hasher.finalize())
 }
 
@@ -895,31 +1684,9 @@ fn parse_skill_md(path: &Path) -> Option<(String, Option<String>)> {
 }
 
 fn parse_skill_md_with_reason(path: &Path) -> Result<(String, Option<String>), &'static str> {
-    let text = std::fs::read_to_string(path).map_err(|_| "read_failed")?;
-    let mut lines = text.lines();
-    if lines.next().map(|v| v.trim()) != Some("---") {
-        return Err("invalid_frontmatter");
-    }
-    let mut name: Option<String> = None;
-    let mut desc: Option<String> = None;
-    let mut found_end = false;
-    for line in lines.by_ref() {
-        let l = line.trim();
-        if l == "---" {
-            found_end = true;
-            break;
-        }
-        if let Some(v) = l.strip_prefix("name:") {
-            name = Some(v.trim().trim_matches('"').to_string());
-        } else if let Some(v) = l.strip_prefix("description:") {
-            desc = Some(v.trim().trim_matches('"').to_string());
-        }
-    }
-    if !found_end {
-        return Err("invalid_frontmatter");
-    }
-    let name = name.ok_or("missing_name")?;
-    Ok((name, desc))
+    SkillManifest::read(path)
+        .map(|manifest| (manifest.name, manifest.description))
+        .map_err(|err| err.reason)
 }
 
 #[cfg(test)]
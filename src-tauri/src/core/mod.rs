@@ -1,11 +1,31 @@
+pub mod archive;
 pub mod cache_cleanup;
 pub mod central_repo;
 pub mod content_hash;
+pub mod credentials;
+pub mod forge_url;
+pub mod git_credentials;
 pub mod git_fetcher;
 pub mod github_search;
 pub mod installer;
 pub mod onboarding;
+pub mod op_journal;
+pub mod project_roots;
+pub mod reconcile;
+pub mod skill_diff;
+pub mod skill_discovery;
+pub mod skill_history;
+pub mod skill_manifest;
+pub mod skill_merge;
 pub mod skill_store;
 pub mod sync_engine;
+pub mod target_health;
+pub mod team_manifest;
 pub mod temp_cleanup;
 pub mod tool_adapters;
+pub mod tool_sync_modes;
+pub mod watcher;
+
+#[cfg(test)]
+#[path = "tests/fixtures.rs"]
+pub(crate) mod test_fixtures;
@@ -1,13 +1,22 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
-use anyhow::Result;
-use serde::Serialize;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use super::central_repo::resolve_central_repo_path;
-use super::content_hash::hash_dir;
-use super::skill_store::SkillStore;
-use super::tool_adapters::{default_tool_adapters, scan_tool_dir, DetectedSkill};
+use super::content_hash::{dir_stamp, hash_dir};
+use super::installer::install_local_skill;
+use super::skill_diff::{compare_dirs, diff_dirs, ComparedFile, SkillDiff};
+use super::skill_history::{resolve_history_root, snapshot_dir};
+use super::skill_store::{DiscoveredSkillRecord, SkillStore, SkillTargetRecord};
+use super::sync_engine::{remove_path_any, sync_dir_for_tool_with_mode};
+use super::tool_adapters::{
+    adapter_by_key, adapters_sharing_skills_dir, default_tool_adapters, scan_tool_dir,
+    DetectedSkill, TargetScope,
+};
+use super::tool_sync_modes::preferred_sync_mode;
 
 #[derive(Clone, Debug, Serialize)]
 pub struct OnboardingVariant {
@@ -17,6 +26,10 @@ pub struct OnboardingVariant {
     pub fingerprint: Option<String>,
     pub is_link: bool,
     pub link_target: Option<PathBuf>,
+    /// Not seen by the previous scan.
+    pub is_new: bool,
+    #[serde(skip)]
+    pub dir_stamp: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -30,40 +43,428 @@ pub struct OnboardingGroup {
 pub struct OnboardingPlan {
     pub total_tools_scanned: usize,
     pub total_skills_found: usize,
+    /// Variants that appeared since the previous scan.
+    pub new_since_last_scan: usize,
     pub groups: Vec<OnboardingGroup>,
 }
 
+/// What the user decided for one group of the onboarding plan.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum OnboardingDecision {
+    /// Import `variant_path` (as `name`, default the group name) and replace every
+    /// variant of the group with a managed link to it.
+    Pick {
+        group: String,
+        variant_path: PathBuf,
+        #[serde(default)]
+        name: Option<String>,
+    },
+    /// Import each listed variant under its own name. Unlisted variants with the same
+    /// content as a listed one are linked to it; the rest are left alone.
+    KeepBoth {
+        group: String,
+        variants: Vec<NamedVariant>,
+    },
+    Skip {
+        group: String,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct NamedVariant {
+    pub path: PathBuf,
+    pub name: String,
+}
+
+impl OnboardingDecision {
+    fn group(&self) -> &str {
+        match self {
+            OnboardingDecision::Pick { group, .. }
+            | OnboardingDecision::KeepBoth { group, .. }
+            | OnboardingDecision::Skip { group } => group,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct OnboardingApplyReport {
+    pub groups: Vec<OnboardingGroupResult>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct OnboardingGroupResult {
+    pub group: String,
+    pub imported: Vec<ImportedVariant>,
+    pub linked: Vec<LinkedVariant>,
+    /// Set when the group failed part-way; earlier imports and links are kept.
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ImportedVariant {
+    pub skill_id: String,
+    pub name: String,
+    pub central_path: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LinkedVariant {
+    pub skill_id: String,
+    pub tool: String,
+    pub path: String,
+    pub mode: String,
+    /// History snapshot of the folder this link replaced, when its content differed
+    /// from the imported variant; restoring it undoes a wrong pick.
+    pub snapshot_id: Option<String>,
+}
+
+/// How the variants of one onboarding group differ, to pick one or merge them by hand.
+#[derive(Clone, Debug, Serialize)]
+pub struct VariantComparison {
+    pub group: String,
+    /// Distinct folders of the group; the first is the base the diffs are taken from.
+    pub variants: Vec<ComparedVariant>,
+    /// Every file across the variants; indexes point into `variants`.
+    pub files: Vec<ComparedFile>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ComparedVariant {
+    pub path: PathBuf,
+    /// Tools reporting this folder (several when they share a skills dir).
+    pub tools: Vec<String>,
+    pub fingerprint: Option<String>,
+    /// Changes from the base variant to this one; empty for the base itself.
+    pub diff: SkillDiff,
+}
+
 pub fn build_onboarding_plan<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     store: &SkillStore,
 ) -> Result<OnboardingPlan> {
-    let home =
-        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("failed to resolve home directory"))?;
     let central = resolve_central_repo_path(app, store)?;
-    let managed_targets = store
+    scan_and_record(store, &home_dir()?, &central)
+}
+
+/// Scan the tool dirs, reusing fingerprints of folders unchanged since the previous
+/// scan, and replace that scan in `discovered_skills` with this one.
+fn scan_and_record(store: &SkillStore, home: &Path, central: &Path) -> Result<OnboardingPlan> {
+    let previous = store.list_discovered_skills()?;
+    let plan = build_onboarding_plan_in_home(
+        home,
+        Some(central),
+        Some(&managed_targets(store)),
+        &previous,
+    )?;
+    record_scan(store, &previous, &plan)?;
+    Ok(plan)
+}
+
+fn record_scan(
+    store: &SkillStore,
+    previous: &[DiscoveredSkillRecord],
+    plan: &OnboardingPlan,
+) -> Result<()> {
+    let now = now_ms();
+    let mut seen = HashSet::new();
+    for variant in plan.groups.iter().flat_map(|g| &g.variants) {
+        let found_path = variant.path.to_string_lossy().to_string();
+        store.upsert_discovered_skill(&DiscoveredSkillRecord {
+            id: Uuid::new_v4().to_string(),
+            tool: variant.tool.clone(),
+            found_path: found_path.clone(),
+            name_guess: Some(variant.name.clone()),
+            fingerprint: variant.fingerprint.clone(),
+            dir_stamp: variant.dir_stamp.clone(),
+            found_at: now,
+            imported_skill_id: None,
+        })?;
+        seen.insert((variant.tool.clone(), found_path));
+    }
+    // Imported folders are managed targets now and drop out of the scan; keep their rows
+    // as the record of where each skill came from.
+    for row in previous {
+        if row.imported_skill_id.is_none()
+            && !seen.contains(&(row.tool.clone(), row.found_path.clone()))
+        {
+            store.delete_discovered_skill(&row.id)?;
+        }
+    }
+    Ok(())
+}
+
+/// Act on the user's decisions for the current onboarding plan: import the chosen
+/// variants into the central repo, replace the tools' copies with managed links and
+/// record them in `skill_targets`. Groups without a decision are left alone, and a
+/// failing group does not stop the others.
+pub fn apply_onboarding_plan<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    decisions: &[OnboardingDecision],
+) -> Result<OnboardingApplyReport> {
+    apply_in_home(app, store, decisions, &home_dir()?)
+}
+
+fn apply_in_home<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    decisions: &[OnboardingDecision],
+    home: &Path,
+) -> Result<OnboardingApplyReport> {
+    // Re-scan rather than trusting paths from the frontend.
+    let central = resolve_central_repo_path(app, store)?;
+    let plan = scan_and_record(store, home, &central)?;
+
+    let mut report = OnboardingApplyReport::default();
+    for decision in decisions {
+        if matches!(decision, OnboardingDecision::Skip { .. }) {
+            continue;
+        }
+        let mut result = OnboardingGroupResult {
+            group: decision.group().to_string(),
+            ..Default::default()
+        };
+        let applied = match plan.groups.iter().find(|g| g.name == decision.group()) {
+            Some(group) => apply_decision(app, store, home, group, decision, &mut result),
+            None => Err(anyhow::anyhow!(
+                "skill {:?} is no longer in the onboarding plan",
+                decision.group()
+            )),
+        };
+        if let Err(err) = applied {
+            result.error = Some(format!("{:#}", err));
+        }
+        report.groups.push(result);
+    }
+    Ok(report)
+}
+
+fn apply_decision<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    home: &Path,
+    group: &OnboardingGroup,
+    decision: &OnboardingDecision,
+    result: &mut OnboardingGroupResult,
+) -> Result<()> {
+    let find = |path: &Path| {
+        group
+            .variants
+            .iter()
+            .find(|v| v.path == path)
+            .with_context(|| format!("{:?} is not a variant of {:?}", path, group.name))
+    };
+    match decision {
+        OnboardingDecision::Pick {
+            variant_path, name, ..
+        } => {
+            let chosen = find(variant_path)?;
+            let name = name.clone().unwrap_or_else(|| group.name.clone());
+            let all: Vec<&OnboardingVariant> = group.variants.iter().collect();
+            import_and_link(app, store, home, chosen, &name, &all, result)
+        }
+        OnboardingDecision::KeepBoth { variants, .. } => {
+            let mut names = HashSet::new();
+            let mut chosen = Vec::new();
+            for named in variants {
+                if named.name.trim().is_empty() || !names.insert(named.name.as_str()) {
+                    anyhow::bail!("each kept variant needs its own name");
+                }
+                chosen.push((find(&named.path)?, named.name.as_str()));
+            }
+            for (variant, name) in &chosen {
+                // Unlisted variants follow the listed one with the same content.
+                let followers: Vec<&OnboardingVariant> = group
+                    .variants
+                    .iter()
+                    .filter(|v| {
+                        v.path == variant.path
+                            || (v.fingerprint.is_some()
+                                && v.fingerprint == variant.fingerprint
+                                && !chosen.iter().any(|(c, _)| c.path == v.path))
+                    })
+                    .collect();
+                import_and_link(app, store, home, variant, name, &followers, result)?;
+            }
+            Ok(())
+        }
+        OnboardingDecision::Skip { .. } => Ok(()),
+    }
+}
+
+fn import_and_link<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    home: &Path,
+    source: &OnboardingVariant,
+    name: &str,
+    variants: &[&OnboardingVariant],
+    result: &mut OnboardingGroupResult,
+) -> Result<()> {
+    // Import through the link, if it is one, before the variant gets replaced.
+    let source_dir = std::fs::canonicalize(&source.path)
+        .with_context(|| format!("resolve {:?}", source.path))?;
+    let installed = install_local_skill(app, store, &source_dir, Some(name.to_string()))?;
+    result.imported.push(ImportedVariant {
+        skill_id: installed.skill_id.clone(),
+        name: installed.name.clone(),
+        central_path: installed.central_path.to_string_lossy().to_string(),
+    });
+
+    let mut done = HashSet::new();
+    for variant in variants {
+        // Tools sharing a skills dir report the same folder once per tool.
+        if !done.insert(variant.path.clone()) {
+            continue;
+        }
+        let adapter = adapter_by_key(&variant.tool)
+            .with_context(|| format!("unknown tool {:?}", variant.tool))?;
+        // The overwrite below drops the variant's own content; keep it in the new
+        // skill's history when it is not what got imported.
+        let differs = variant.fingerprint.is_none() || variant.fingerprint != source.fingerprint;
+        let snapshot_id = if variant.path != source.path && differs {
+            let snapshot = resolve_history_root(app).and_then(|history_root| {
+                let dir = std::fs::canonicalize(&variant.path)?;
+                snapshot_dir(store, &history_root, &installed.skill_id, &dir, None)
+            });
+            Some(
+                snapshot
+                    .with_context(|| format!("snapshot {:?} before replacing it", variant.path))?
+                    .id,
+            )
+        } else {
+            None
+        };
+        let outcome = sync_dir_for_tool_with_mode(
+            store,
+            &variant.tool,
+            &installed.central_path,
+            &variant.path,
+            true,
+            preferred_sync_mode(store, &variant.tool),
+        )?;
+        // Rendered targets are files next to the old folder.
+        if outcome.target_path != variant.path {
+            remove_path_any(&variant.path)?;
+        }
+
+        let mode = outcome.mode_used.as_key();
+        let target_path = outcome.target_path.to_string_lossy().to_string();
+        for a in adapters_sharing_skills_dir(&adapter) {
+            if !a.detect_dir_in(home).exists() {
+                continue;
+            }
+            store.upsert_skill_target(&SkillTargetRecord {
+                id: Uuid::new_v4().to_string(),
+                skill_id: installed.skill_id.clone(),
+                tool: a.id.as_key().to_string(),
+                scope: TargetScope::GLOBAL_KEY.to_string(),
+                target_path: target_path.clone(),
+                mode: mode.to_string(),
+                status: "ok".to_string(),
+                last_error: None,
+                synced_at: Some(now_ms()),
+                rendered_hash: outcome.rendered_hash.clone(),
+            })?;
+            result.linked.push(LinkedVariant {
+                skill_id: installed.skill_id.clone(),
+                tool: a.id.as_key().to_string(),
+                path: target_path.clone(),
+                mode: mode.to_string(),
+                snapshot_id: snapshot_id.clone(),
+            });
+        }
+        // Only now is the folder actually managed.
+        store.mark_discovered_imported(&variant.path.to_string_lossy(), &installed.skill_id)?;
+    }
+    Ok(())
+}
+
+/// Compare the variants of `group` in the current onboarding plan: which files each
+/// one has, where their contents agree, and a text diff of each against `base`
+/// (default the first variant).
+pub fn compare_onboarding_variants<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    group: &str,
+    base: Option<&Path>,
+) -> Result<VariantComparison> {
+    let central = resolve_central_repo_path(app, store)?;
+    let plan = scan_and_record(store, &home_dir()?, &central)?;
+    let group = plan
+        .groups
+        .iter()
+        .find(|g| g.name == group)
+        .with_context(|| format!("skill {:?} is not in the onboarding plan", group))?;
+    compare_variants(group, base)
+}
+
+fn compare_variants(group: &OnboardingGroup, base: Option<&Path>) -> Result<VariantComparison> {
+    let mut variants: Vec<ComparedVariant> = Vec::new();
+    for variant in &group.variants {
+        match variants.iter_mut().find(|v| v.path == variant.path) {
+            Some(existing) => existing.tools.push(variant.tool.clone()),
+            None => variants.push(ComparedVariant {
+                path: variant.path.clone(),
+                tools: vec![variant.tool.clone()],
+                fingerprint: variant.fingerprint.clone(),
+                diff: SkillDiff::default(),
+            }),
+        }
+    }
+    if let Some(base) = base {
+        let index = variants
+            .iter()
+            .position(|v| v.path == base)
+            .with_context(|| format!("{:?} is not a variant of {:?}", base, group.name))?;
+        let base = variants.remove(index);
+        variants.insert(0, base);
+    }
+
+    let dirs: Vec<&Path> = variants.iter().map(|v| v.path.as_path()).collect();
+    let files = compare_dirs(&dirs)?;
+    if let Some((base, others)) = variants.split_first_mut() {
+        for other in others {
+            other.diff = diff_dirs(&base.path, &other.path)?;
+        }
+    }
+    Ok(VariantComparison {
+        group: group.name.clone(),
+        variants,
+        files,
+    })
+}
+
+fn managed_targets(store: &SkillStore) -> HashSet<String> {
+    store
         .list_all_skill_target_paths()
         .unwrap_or_default()
         .into_iter()
         .map(|(tool, path)| managed_target_key(&tool, Path::new(&path)))
-        .collect::<std::collections::HashSet<_>>();
-    build_onboarding_plan_in_home(&home, Some(&central), Some(&managed_targets))
+        .collect()
+}
+
+fn home_dir() -> Result<PathBuf> {
+    dirs::home_dir().ok_or_else(|| anyhow::anyhow!("failed to resolve home directory"))
 }
 
 fn build_onboarding_plan_in_home(
     home: &Path,
     exclude_root: Option<&Path>,
-    exclude_managed_targets: Option<&std::collections::HashSet<String>>,
+    exclude_managed_targets: Option<&HashSet<String>>,
+    previous: &[DiscoveredSkillRecord],
 ) -> Result<OnboardingPlan> {
     let adapters = default_tool_adapters();
     let mut all_detected: Vec<DetectedSkill> = Vec::new();
     let mut scanned = 0usize;
 
     for adapter in &adapters {
-        if !home.join(adapter.relative_detect_dir).exists() {
+        if !adapter.detect_dir_in(home).exists() {
             continue;
         }
         scanned += 1;
-        let dir = home.join(adapter.relative_skills_dir);
+        let dir = adapter.skills_dir_in(home);
         let detected = scan_tool_dir(adapter, &dir)?;
         all_detected.extend(filter_detected(
             detected,
@@ -72,17 +473,37 @@ fn build_onboarding_plan_in_home(
         ));
     }
 
+    let previous: HashMap<(&str, &str), &DiscoveredSkillRecord> = previous
+        .iter()
+        .map(|row| ((row.tool.as_str(), row.found_path.as_str()), row))
+        .collect();
+    let mut new_since_last_scan = 0usize;
     let mut grouped: HashMap<String, Vec<OnboardingVariant>> = HashMap::new();
     for skill in all_detected.iter() {
-        let fingerprint = hash_dir(&skill.path).ok();
+        let tool = skill.tool.as_key();
+        let found_path = skill.path.to_string_lossy();
+        let known = previous.get(&(tool, found_path.as_ref())).copied();
+        let stamp = dir_stamp(&skill.path).ok();
+        // Only read file contents when sizes or mtimes moved since the last scan.
+        let fingerprint = match known {
+            Some(row) if stamp.is_some() && row.dir_stamp == stamp && row.fingerprint.is_some() => {
+                row.fingerprint.clone()
+            }
+            _ => hash_dir(&skill.path).ok(),
+        };
+        if known.is_none() {
+            new_since_last_scan += 1;
+        }
         let entry = grouped.entry(skill.name.clone()).or_default();
         entry.push(OnboardingVariant {
-            tool: skill.tool.as_key().to_string(),
+            tool: tool.to_string(),
             name: skill.name.clone(),
             path: skill.path.clone(),
             fingerprint,
             is_link: skill.is_link,
             link_target: skill.link_target.clone(),
+            is_new: known.is_none(),
+            dir_stamp: stamp,
         });
     }
 
@@ -92,7 +513,7 @@ fn build_onboarding_plan_in_home(
             let mut uniq = variants
                 .iter()
                 .filter_map(|v| v.fingerprint.as_ref())
-                .collect::<std::collections::HashSet<_>>()
+                .collect::<HashSet<_>>()
                 .len();
             if uniq == 0 {
                 uniq = 1;
@@ -108,6 +529,7 @@ fn build_onboarding_plan_in_home(
     Ok(OnboardingPlan {
         total_tools_scanned: scanned,
         total_skills_found: all_detected.len(),
+        new_since_last_scan,
         groups,
     })
 }
@@ -115,7 +537,7 @@ fn build_onboarding_plan_in_home(
 fn filter_detected(
     detected: Vec<DetectedSkill>,
     exclude_root: Option<&Path>,
-    exclude_managed_targets: Option<&std::collections::HashSet<String>>,
+    exclude_managed_targets: Option<&HashSet<String>>,
 ) -> Vec<DetectedSkill> {
     if exclude_root.is_none() && exclude_managed_targets.is_none() {
         return detected;
@@ -166,6 +588,13 @@ fn normalize_path_for_key(path: &Path) -> String {
     }
 }
 
+fn now_ms() -> i64 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    now.as_millis() as i64
+}
+
 #[cfg(test)]
 #[path = "tests/onboarding.rs"]
 mod tests;
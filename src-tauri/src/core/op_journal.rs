@@ -0,0 +1,186 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use uuid::Uuid;
+
+use super::skill_store::{OperationRecord, SkillStore};
+use super::sync_engine::remove_path_any;
+
+const PHASE_STAGING: &str = "staging";
+const PHASE_READY: &str = "ready";
+
+/// Replaces a directory (or file) without a window where neither the old nor the new
+/// version exists: the new content is built next to `target`, the old one is renamed
+/// aside, the new one renamed in, and only then is the old one deleted. Each step is
+/// journaled so `recover_operations` can finish or undo it after a crash.
+pub struct Swap {
+    journal: SkillStore,
+    id: String,
+    target: PathBuf,
+    staged: PathBuf,
+    backup: PathBuf,
+}
+
+impl Swap {
+    /// Start replacing `target`, journaled in `store`; build the new content at
+    /// `staged()`, then `commit`.
+    pub fn begin(store: &SkillStore, kind: &str, target: &Path) -> Result<Self> {
+        let id = Uuid::new_v4().to_string();
+        let staged = sibling(target, "skills-hub-swap", &id)?;
+        Self::journaled(store, kind, id, target, staged, PHASE_STAGING)
+    }
+
+    /// Replace `target` with `staged`, which is already complete (e.g. an update built
+    /// in a staging dir). `staged` must be on the same filesystem as `target`.
+    pub fn adopt(store: &SkillStore, kind: &str, staged: &Path, target: &Path) -> Result<Self> {
+        let id = Uuid::new_v4().to_string();
+        Self::journaled(store, kind, id, target, staged.to_path_buf(), PHASE_READY)
+    }
+
+    fn journaled(
+        store: &SkillStore,
+        kind: &str,
+        id: String,
+        target: &Path,
+        staged: PathBuf,
+        phase: &str,
+    ) -> Result<Self> {
+        let backup = sibling(target, "skills-hub-old", &id)?;
+        store
+            .insert_operation(&OperationRecord {
+                id: id.clone(),
+                kind: kind.to_string(),
+                target_path: target.to_string_lossy().to_string(),
+                staged_path: staged.to_string_lossy().to_string(),
+                backup_path: backup.to_string_lossy().to_string(),
+                phase: phase.to_string(),
+                created_at: now_ms(),
+            })
+            .context("failed to journal operation")?;
+        Ok(Self {
+            journal: store.clone(),
+            id,
+            target: target.to_path_buf(),
+            staged,
+            backup,
+        })
+    }
+
+    pub fn staged(&self) -> &Path {
+        &self.staged
+    }
+
+    /// Move the staged content into place. On failure the old `target` is restored and
+    /// the staged content and journal entry are dropped, so recovery never replays a
+    /// swap that was reported as failed.
+    pub fn commit(self) -> Result<()> {
+        if let Err(err) = self.journal.set_operation_phase(&self.id, PHASE_READY) {
+            self.abort();
+            return Err(err);
+        }
+        let had_target = std::fs::symlink_metadata(&self.target).is_ok();
+        if had_target {
+            if let Err(err) = std::fs::rename(&self.target, &self.backup) {
+                let context = format!("move {:?} aside to {:?}", self.target, self.backup);
+                self.abort();
+                return Err(err).context(context);
+            }
+        }
+        if let Err(err) = std::fs::rename(&self.staged, &self.target) {
+            if had_target {
+                let _ = std::fs::rename(&self.backup, &self.target);
+            }
+            let target = self.target.clone();
+            self.abort();
+            return Err(err).with_context(|| format!("move {:?} into place", target));
+        }
+        if had_target {
+            if let Err(err) = remove_path_any(&self.backup) {
+                // The new content is in place; a leftover backup is only clutter.
+                log::warn!("failed to remove {:?}: {:#}", self.backup, err);
+            }
+        }
+        self.finish();
+        Ok(())
+    }
+
+    /// Drop the staged content and leave `target` as it was.
+    pub fn abort(self) {
+        let _ = remove_path_any(&self.staged);
+        self.finish();
+    }
+
+    fn finish(&self) {
+        if let Err(err) = self.journal.delete_operation(&self.id) {
+            log::warn!("failed to clear journal entry {}: {:#}", self.id, err);
+        }
+    }
+}
+
+/// Finish or undo swaps left in the journal by a crash. A swap whose staged content
+/// was complete is replayed; one still being built is rolled back. Either way the
+/// target ends up with a whole version of its content. Returns how many were handled.
+pub fn recover_operations(store: &SkillStore) -> Result<usize> {
+    let operations = store.list_operations()?;
+    for op in &operations {
+        let target = Path::new(&op.target_path);
+        let staged = Path::new(&op.staged_path);
+        let backup = Path::new(&op.backup_path);
+        let exists = |p: &Path| std::fs::symlink_metadata(p).is_ok();
+
+        let result = if op.phase == PHASE_READY && exists(staged) {
+            log::info!("[op_journal] replaying {} of {:?}", op.kind, target);
+            replay(staged, target, backup)
+        } else {
+            log::info!("[op_journal] rolling back {} of {:?}", op.kind, target);
+            roll_back(staged, target, backup)
+        };
+        if let Err(err) = result {
+            // Keep the entry so the next start tries again; the paths are all still there.
+            log::warn!("[op_journal] recovery of {:?} failed: {:#}", target, err);
+            continue;
+        }
+        store.delete_operation(&op.id)?;
+    }
+    Ok(operations.len())
+}
+
+fn replay(staged: &Path, target: &Path, backup: &Path) -> Result<()> {
+    if std::fs::symlink_metadata(target).is_ok() {
+        if std::fs::symlink_metadata(backup).is_ok() {
+            remove_path_any(target)?;
+        } else {
+            std::fs::rename(target, backup)
+                .with_context(|| format!("move {:?} aside to {:?}", target, backup))?;
+        }
+    }
+    std::fs::rename(staged, target).with_context(|| format!("move {:?} into place", target))?;
+    remove_path_any(backup)
+}
+
+fn roll_back(staged: &Path, target: &Path, backup: &Path) -> Result<()> {
+    remove_path_any(staged)?;
+    if std::fs::symlink_metadata(target).is_err() && std::fs::symlink_metadata(backup).is_ok() {
+        std::fs::rename(backup, target).with_context(|| format!("restore {:?}", target))?;
+    }
+    remove_path_any(backup)
+}
+
+/// Hidden path next to `target`, so renames stay on one filesystem and tool scans skip it.
+fn sibling(target: &Path, tag: &str, id: &str) -> Result<PathBuf> {
+    let name = target
+        .file_name()
+        .with_context(|| format!("invalid target path {:?}", target))?;
+    Ok(target.with_file_name(format!(".{}.{}-{}", name.to_string_lossy(), tag, id)))
+}
+
+fn now_ms() -> i64 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    now.as_millis() as i64
+}
+
+#[cfg(test)]
+#[path = "tests/op_journal.rs"]
+mod tests;
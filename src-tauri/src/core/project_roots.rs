@@ -0,0 +1,77 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use super::skill_store::SkillStore;
+use super::tool_adapters::TargetScope;
+
+pub const PROJECT_ROOTS_KEY: &str = "project_roots";
+
+/// Project roots (repository directories) skills can be synced into, in the order
+/// they were added.
+pub fn get_project_roots(store: &SkillStore) -> Vec<String> {
+    store
+        .get_setting(PROJECT_ROOTS_KEY)
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn add_project_root(store: &SkillStore, path: &str) -> Result<Vec<String>> {
+    let root = normalize_root(path);
+    if !root.is_absolute() {
+        anyhow::bail!("project root must be an absolute path: {}", path);
+    }
+    if !root.is_dir() {
+        anyhow::bail!("project root not found: {:?}", root);
+    }
+    let root = root.to_string_lossy().to_string();
+    let mut roots = get_project_roots(store);
+    if !roots.contains(&root) {
+        roots.push(root);
+        store.set_setting(PROJECT_ROOTS_KEY, &serde_json::to_string(&roots)?)?;
+    }
+    Ok(roots)
+}
+
+/// Forget a project root. Targets already synced into it stay recorded so they can
+/// still be unsynced.
+pub fn remove_project_root(store: &SkillStore, path: &str) -> Result<Vec<String>> {
+    let root = normalize_root(path).to_string_lossy().to_string();
+    let mut roots = get_project_roots(store);
+    roots.retain(|r| *r != root);
+    store.set_setting(PROJECT_ROOTS_KEY, &serde_json::to_string(&roots)?)?;
+    Ok(roots)
+}
+
+/// Scope named by a command argument: `None` or `global` for the home directory,
+/// otherwise a registered project root.
+pub fn resolve_target_scope(store: &SkillStore, scope: Option<&str>) -> Result<TargetScope> {
+    let scope = scope.map(str::trim).unwrap_or_default();
+    match TargetScope::from_key(scope) {
+        TargetScope::Global => Ok(TargetScope::Global),
+        TargetScope::Project(root) => {
+            let root = normalize_root(&root.to_string_lossy());
+            let key = root.to_string_lossy().to_string();
+            if !get_project_roots(store).contains(&key) {
+                anyhow::bail!("project root is not registered: {}", key);
+            }
+            Ok(TargetScope::Project(root))
+        }
+    }
+}
+
+fn normalize_root(path: &str) -> PathBuf {
+    let trimmed = path.trim();
+    let stripped = trimmed.trim_end_matches(['/', '\\']);
+    // Keep a bare filesystem root (`/`) intact.
+    if stripped.is_empty() {
+        return PathBuf::from(trimmed);
+    }
+    Path::new(stripped).components().collect()
+}
+
+#[cfg(test)]
+#[path = "tests/project_roots.rs"]
+mod tests;
@@ -0,0 +1,441 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::installer::{
+    install_archive_skill, install_git_skill_at, install_git_skill_from_selection_at,
+    install_local_skill_from_selection, set_skill_pin, set_skill_source,
+    update_managed_skill_from_source, LocalChangesPolicy, SkillSource,
+};
+use super::skill_store::{SkillRecord, SkillStore, SkillTargetRecord};
+use super::sync_engine::sync_dir_for_tool_with_mode;
+use super::team_manifest::{
+    load_lockfile, load_manifest, lockfile_path, manifest_file, write_lockfile, LockedSkill,
+    Lockfile, ManifestSkill, TeamManifest,
+};
+use super::tool_adapters::{adapter_by_key, adapters_sharing_skills_dir, TargetScope};
+use super::tool_sync_modes::preferred_sync_mode;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReconcileAction {
+    Install,
+    Update,
+    Sync,
+    Unchanged,
+    Skip,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ReconcileStep {
+    pub skill: String,
+    pub action: ReconcileAction,
+    pub tool: Option<String>,
+    /// Why the step is needed or skipped, e.g. `source_changed`, `lockfile_drift`,
+    /// `update_lock`, `target_missing`, `tool_not_installed`.
+    pub reason: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ReconcilePlan {
+    pub manifest_path: String,
+    pub lockfile_path: String,
+    pub steps: Vec<ReconcileStep>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ReconcileStepResult {
+    #[serde(flatten)]
+    pub step: ReconcileStep,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ReconcileReport {
+    pub lockfile_path: String,
+    pub steps: Vec<ReconcileStepResult>,
+}
+
+/// What applying the manifest at `manifest_path` (or in that directory) would do. Read-only: nothing is
+/// fetched, so drift is judged against the installed records and `skills.lock`.
+pub fn plan_team_manifest(store: &SkillStore, manifest_path: &Path) -> Result<ReconcilePlan> {
+    plan_in_home(store, manifest_path, &home_dir()?)
+}
+
+/// Install missing skills, update drifted ones and sync targets so this machine
+/// matches the manifest. Git skills with a lock entry for their declared source are
+/// installed at the locked revision; entries without one are added to `skills.lock`.
+/// Skills not in the manifest are left alone. A failed step is reported and does not
+/// stop the others.
+pub fn apply_team_manifest<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    manifest_path: &Path,
+    policy: LocalChangesPolicy,
+) -> Result<ReconcileReport> {
+    apply_in_home(app, store, manifest_path, policy, &home_dir()?, false)
+}
+
+/// Like [`apply_team_manifest`], but every skill is pulled from its source again (the
+/// manifest's `ref`, or the branch head) instead of the locked revision, and
+/// `skills.lock` is rewritten to match. This is the only way the lock moves forward.
+pub fn update_team_lock<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    manifest_path: &Path,
+    policy: LocalChangesPolicy,
+) -> Result<ReconcileReport> {
+    apply_in_home(app, store, manifest_path, policy, &home_dir()?, true)
+}
+
+fn home_dir() -> Result<PathBuf> {
+    dirs::home_dir().ok_or_else(|| anyhow::anyhow!("failed to resolve home directory"))
+}
+
+struct Loaded {
+    manifest: TeamManifest,
+    base_dir: PathBuf,
+    lock_path: PathBuf,
+    lock: Lockfile,
+}
+
+fn load(manifest_path: &Path) -> Result<Loaded> {
+    let manifest = load_manifest(manifest_path)?;
+    let base_dir = manifest_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    let lock_path = lockfile_path(manifest_path);
+    let lock = load_lockfile(&lock_path)?;
+    Ok(Loaded {
+        manifest,
+        base_dir,
+        lock_path,
+        lock,
+    })
+}
+
+fn plan_in_home(store: &SkillStore, manifest_path: &Path, home: &Path) -> Result<ReconcilePlan> {
+    let manifest_path = &manifest_file(manifest_path);
+    let loaded = load(manifest_path)?;
+    let skills = store.list_skills()?;
+    let mut steps = Vec::new();
+
+    for entry in &loaded.manifest.skills {
+        let record = skills.iter().find(|s| s.name == entry.name);
+        let (action, reason) = match record {
+            None => (ReconcileAction::Install, None),
+            Some(record) => match drift(entry, record, &loaded) {
+                Some(reason) => (ReconcileAction::Update, Some(reason.to_string())),
+                None => (ReconcileAction::Unchanged, None),
+            },
+        };
+        steps.push(ReconcileStep {
+            skill: entry.name.clone(),
+            action,
+            tool: None,
+            reason,
+        });
+
+        let mut seen_dirs = HashSet::new();
+        for tool in entry.tools(&loaded.manifest) {
+            let Some(adapter) = adapter_by_key(tool) else {
+                continue;
+            };
+            // Tools sharing a skills dir are covered by one sync.
+            if !seen_dirs.insert(adapter.relative_skills_dir.clone()) {
+                continue;
+            }
+            let step = |action, reason: &str| ReconcileStep {
+                skill: entry.name.clone(),
+                action,
+                tool: Some(tool.clone()),
+                reason: Some(reason.to_string()),
+            };
+            if !adapter.detect_dir_in(home).exists() {
+                steps.push(step(ReconcileAction::Skip, "tool_not_installed"));
+                continue;
+            }
+            let synced = record
+                .and_then(|r| {
+                    store
+                        .get_skill_target(&r.id, tool, TargetScope::GLOBAL_KEY)
+                        .ok()
+                        .flatten()
+                })
+                .is_some_and(|t| std::fs::symlink_metadata(&t.target_path).is_ok());
+            if !synced {
+                steps.push(step(ReconcileAction::Sync, "target_missing"));
+            }
+        }
+    }
+
+    Ok(ReconcilePlan {
+        manifest_path: manifest_path.to_string_lossy().to_string(),
+        lockfile_path: loaded.lock_path.to_string_lossy().to_string(),
+        steps,
+    })
+}
+
+/// Why an installed skill no longer matches its manifest entry, if it does not.
+fn drift(entry: &ManifestSkill, record: &SkillRecord, loaded: &Loaded) -> Option<&'static str> {
+    let source = entry.resolved_source(&loaded.base_dir);
+    let subpath_changed = entry.kind() != "local"
+        && entry.subpath.is_some()
+        && record.source_subpath != entry.subpath;
+    if record.source_type != entry.kind()
+        || record.source_ref.as_deref() != Some(source.as_str())
+        || subpath_changed
+    {
+        return Some("source_changed");
+    }
+    let locked = loaded
+        .lock
+        .get(&entry.name)
+        .filter(|l| l.source == entry.declared_source())?;
+    let differs =
+        |locked: &Option<String>, current: &Option<String>| locked.is_some() && locked != current;
+    if differs(&locked.source_revision, &record.source_revision)
+        || differs(&locked.content_hash, &record.content_hash)
+    {
+        return Some("lockfile_drift");
+    }
+    None
+}
+
+/// Revision `skills.lock` holds for a git entry, if it was locked for the same
+/// declared source.
+fn locked_revision<'a>(entry: &ManifestSkill, loaded: &'a Loaded) -> Option<&'a str> {
+    if entry.kind() != "git" {
+        return None;
+    }
+    loaded
+        .lock
+        .get(&entry.name)
+        .filter(|l| l.source == entry.declared_source())?
+        .source_revision
+        .as_deref()
+}
+
+fn apply_in_home<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    manifest_path: &Path,
+    policy: LocalChangesPolicy,
+    home: &Path,
+    update_lock: bool,
+) -> Result<ReconcileReport> {
+    let manifest_path = &manifest_file(manifest_path);
+    let plan = plan_in_home(store, manifest_path, home)?;
+    let loaded = load(manifest_path)?;
+    let mut failed_skills = HashSet::new();
+    let mut results = Vec::new();
+
+    for mut step in plan.steps {
+        let entry = loaded
+            .manifest
+            .skills
+            .iter()
+            .find(|s| s.name == step.skill)
+            .context("manifest changed while applying")?;
+        if update_lock && step.tool.is_none() && step.action == ReconcileAction::Unchanged {
+            step.action = ReconcileAction::Update;
+            step.reason = Some("update_lock".to_string());
+        }
+        let locked = if update_lock {
+            None
+        } else {
+            locked_revision(entry, &loaded)
+        };
+        let outcome = if failed_skills.contains(&step.skill) {
+            Err(anyhow::anyhow!(
+                "skipped: {} failed to install or update",
+                step.skill
+            ))
+        } else {
+            match step.action {
+                ReconcileAction::Install => {
+                    install_entry(app, store, entry, &loaded.base_dir, locked)
+                }
+                ReconcileAction::Update => {
+                    update_entry(app, store, entry, &loaded.base_dir, &step, locked, policy)
+                }
+                ReconcileAction::Sync => sync_entry(store, entry, &step, home),
+                ReconcileAction::Unchanged | ReconcileAction::Skip => Ok(()),
+            }
+        };
+        let error = match outcome {
+            Ok(()) => None,
+            Err(err) => {
+                if step.tool.is_none() {
+                    failed_skills.insert(step.skill.clone());
+                }
+                Some(format!("{:#}", err))
+            }
+        };
+        results.push(ReconcileStepResult { step, error });
+    }
+
+    let lock = next_lockfile(store, &loaded, &failed_skills, update_lock)?;
+    write_lockfile(&loaded.lock_path, &lock)?;
+
+    Ok(ReconcileReport {
+        lockfile_path: plan.lockfile_path,
+        steps: results,
+    })
+}
+
+/// `locked` is the revision from `skills.lock` a git entry is checked out at.
+fn install_entry<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    entry: &ManifestSkill,
+    base_dir: &Path,
+    locked: Option<&str>,
+) -> Result<()> {
+    let source = entry.resolved_source(base_dir);
+    let name = Some(entry.name.clone());
+    match (entry.kind(), entry.subpath.as_deref()) {
+        ("git", Some(subpath)) => {
+            install_git_skill_from_selection_at(app, store, &source, subpath, name, locked)?;
+        }
+        ("git", None) => {
+            install_git_skill_at(app, store, &source, name, locked)?;
+        }
+        ("local", _) => {
+            install_local_skill_from_selection(app, store, Path::new(&source), ".", name)?;
+        }
+        (_, subpath) => {
+            install_archive_skill(app, store, &source, subpath, name)?;
+        }
+    }
+    Ok(())
+}
+
+fn update_entry<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+    entry: &ManifestSkill,
+    base_dir: &Path,
+    step: &ReconcileStep,
+    locked: Option<&str>,
+    policy: LocalChangesPolicy,
+) -> Result<()> {
+    let record = find_record(store, &entry.name)?;
+    let kind = entry.kind();
+    let pin = locked.or(entry.pin.as_deref()).filter(|_| kind == "git");
+    if step.reason.as_deref() == Some("source_changed") {
+        let source = SkillSource {
+            source_type: kind.to_string(),
+            source_ref: entry.resolved_source(base_dir),
+            subpath: entry.subpath.clone().filter(|_| kind != "local"),
+            pin: pin.map(str::to_string),
+        };
+        set_skill_source(app, store, &record.id, source, policy)?;
+    } else if kind == "git" {
+        set_skill_pin(app, store, &record.id, pin, policy)?;
+    } else {
+        update_managed_skill_from_source(app, store, &record.id, policy)?;
+    }
+    Ok(())
+}
+
+fn sync_entry(
+    store: &SkillStore,
+    entry: &ManifestSkill,
+    step: &ReconcileStep,
+    home: &Path,
+) -> Result<()> {
+    let record = find_record(store, &entry.name)?;
+    let tool = step.tool.as_deref().context("sync step without tool")?;
+    let adapter = adapter_by_key(tool).ok_or_else(|| anyhow::anyhow!("unknown tool"))?;
+    let target = adapter.skills_dir_in(home).join(&record.name);
+    let result = sync_dir_for_tool_with_mode(
+        store,
+        tool,
+        Path::new(&record.central_path),
+        &target,
+        false,
+        preferred_sync_mode(store, tool),
+    )
+    .map_err(|err| {
+        if err.to_string().contains("target already exists") {
+            anyhow::anyhow!("TARGET_EXISTS|{}", target.to_string_lossy())
+        } else {
+            err
+        }
+    })?;
+
+    let mode = result.mode_used.as_key();
+    for a in adapters_sharing_skills_dir(&adapter) {
+        if !a.detect_dir_in(home).exists() {
+            continue;
+        }
+        store.upsert_skill_target(&SkillTargetRecord {
+            id: Uuid::new_v4().to_string(),
+            skill_id: record.id.clone(),
+            tool: a.id.as_key().to_string(),
+            scope: TargetScope::GLOBAL_KEY.to_string(),
+            target_path: result.target_path.to_string_lossy().to_string(),
+            mode: mode.to_string(),
+            status: "ok".to_string(),
+            last_error: None,
+            synced_at: Some(now_ms()),
+            rendered_hash: result.rendered_hash.clone(),
+        })?;
+    }
+    Ok(())
+}
+
+fn find_record(store: &SkillStore, name: &str) -> Result<SkillRecord> {
+    store
+        .list_skills()?
+        .into_iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| anyhow::anyhow!("skill not installed: {}", name))
+}
+
+/// Lock entries for the manifest skills. An entry already locked for its declared
+/// source is kept unless `update_lock` is set; new or re-pointed skills are locked at
+/// what was just installed. Skills whose install or update failed keep their previous
+/// entry so the drift stays visible.
+fn next_lockfile(
+    store: &SkillStore,
+    loaded: &Loaded,
+    failed_skills: &HashSet<String>,
+    update_lock: bool,
+) -> Result<Lockfile> {
+    let skills = store.list_skills()?;
+    let mut lock = Lockfile::default();
+    for entry in &loaded.manifest.skills {
+        let previous = loaded.lock.get(&entry.name);
+        let record = skills.iter().find(|s| s.name == entry.name);
+        let keep_previous = failed_skills.contains(&entry.name)
+            || (!update_lock && previous.is_some_and(|l| l.source == entry.declared_source()));
+        let locked = match record {
+            Some(record) if !keep_previous => Some(LockedSkill {
+                name: entry.name.clone(),
+                source: entry.declared_source(),
+                source_revision: record.source_revision.clone(),
+                content_hash: record.content_hash.clone(),
+            }),
+            _ => previous.cloned(),
+        };
+        lock.skills.extend(locked);
+    }
+    Ok(lock)
+}
+
+fn now_ms() -> i64 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    now.as_millis() as i64
+}
+
+#[cfg(test)]
+#[path = "tests/reconcile.rs"]
+mod tests;
@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use similar::TextDiff;
+use walkdir::WalkDir;
+
+use super::content_hash::is_ignored;
+
+/// Larger files are reported as modified without a text diff.
+const MAX_TEXT_DIFF_BYTES: usize = 512 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FileChange {
+    /// Path relative to the skill root, always with `/` separators.
+    pub path: String,
+    pub kind: FileChangeKind,
+    pub binary: bool,
+    /// Unified diff (`a/<path>` -> `b/<path>`); `None` for binary or oversized files.
+    pub unified_diff: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SkillDiff {
+    pub files: Vec<FileChange>,
+}
+
+/// File-level diff of two skill dirs, sorted by path. Either side may be missing
+/// (treated as empty), and the same names `hash_dir` skips are ignored here too.
+pub fn diff_dirs(old_dir: &Path, new_dir: &Path) -> Result<SkillDiff> {
+    let old_files = list_files(old_dir)?;
+    let new_files = list_files(new_dir)?;
+
+    let mut paths: Vec<&String> = old_files.keys().chain(new_files.keys()).collect();
+    paths.sort();
+    paths.dedup();
+
+    let mut files = Vec::new();
+    for path in paths {
+        let old = old_files.get(path).map(|p| read_file(p)).transpose()?;
+        let new = new_files.get(path).map(|p| read_file(p)).transpose()?;
+        let kind = match (&old, &new) {
+            (None, Some(_)) => FileChangeKind::Added,
+            (Some(_), None) => FileChangeKind::Removed,
+            (Some(a), Some(b)) if a != b => FileChangeKind::Modified,
+            _ => continue,
+        };
+
+        let old_text = old.as_deref().map_or(Some(""), as_text);
+        let new_text = new.as_deref().map_or(Some(""), as_text);
+        let (binary, unified_diff) = match (old_text, new_text) {
+            (Some(a), Some(b)) if a.len().max(b.len()) <= MAX_TEXT_DIFF_BYTES => {
+                (false, Some(unified_diff(path, a, b)))
+            }
+            (Some(_), Some(_)) => (false, None),
+            _ => (true, None),
+        };
+
+        files.push(FileChange {
+            path: path.clone(),
+            kind,
+            binary,
+            unified_diff,
+        });
+    }
+
+    Ok(SkillDiff { files })
+}
+
+/// One file across several dirs compared with `compare_dirs`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ComparedFile {
+    /// Path relative to each root, always with `/` separators.
+    pub path: String,
+    /// Indexes of the dirs that contain the file.
+    pub present_in: Vec<usize>,
+    /// Indexes grouped by identical content; a single group means every dir holding the
+    /// file has the same bytes.
+    pub content_groups: Vec<Vec<usize>>,
+}
+
+/// Which of `dirs` hold each file and where their contents agree, sorted by path.
+/// Ignores the same names as `diff_dirs`.
+pub fn compare_dirs(dirs: &[&Path]) -> Result<Vec<ComparedFile>> {
+    let listed = dirs
+        .iter()
+        .map(|dir| list_files(dir))
+        .collect::<Result<Vec<_>>>()?;
+    let mut paths: Vec<&String> = listed.iter().flat_map(|files| files.keys()).collect();
+    paths.sort();
+    paths.dedup();
+
+    let mut compared = Vec::new();
+    for path in paths {
+        let mut present_in = Vec::new();
+        let mut groups: Vec<(Vec<u8>, Vec<usize>)> = Vec::new();
+        for (index, files) in listed.iter().enumerate() {
+            let Some(file) = files.get(path) else {
+                continue;
+            };
+            present_in.push(index);
+            let bytes = read_file(file)?;
+            match groups.iter_mut().find(|(content, _)| *content == bytes) {
+                Some((_, members)) => members.push(index),
+                None => groups.push((bytes, vec![index])),
+            }
+        }
+        compared.push(ComparedFile {
+            path: path.clone(),
+            present_in,
+            content_groups: groups.into_iter().map(|(_, members)| members).collect(),
+        });
+    }
+    Ok(compared)
+}
+
+/// Files under `root` keyed by `/`-separated relative path; a missing root is empty.
+pub fn list_files(root: &Path) -> Result<BTreeMap<String, PathBuf>> {
+    let mut files = BTreeMap::new();
+    if !root.exists() {
+        return Ok(files);
+    }
+    for entry in WalkDir::new(root)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| !is_ignored(entry))
+    {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(root)
+            .with_context(|| format!("strip prefix {:?}", entry.path()))?;
+        let key = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        files.insert(key, entry.path().to_path_buf());
+    }
+    Ok(files)
+}
+
+pub fn read_file(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("read file {:?}", path))
+}
+
+pub fn as_text(bytes: &[u8]) -> Option<&str> {
+    if bytes.contains(&0) {
+        return None;
+    }
+    std::str::from_utf8(bytes).ok()
+}
+
+fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string()
+}
+
+#[cfg(test)]
+#[path = "tests/skill_diff.rs"]
+mod tests;
@@ -0,0 +1,228 @@
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use serde::Deserialize;
+
+pub const INDEX_FILE: &str = "skills-index.toml";
+pub const DEFAULT_MAX_DEPTH: usize = 4;
+/// Upper bound for any requested depth, so a huge checkout cannot stall the picker.
+const MAX_DEPTH_LIMIT: usize = 10;
+
+/// Optional repo-level index (`skills-index.toml`) telling discovery where skills live.
+///
+/// ```toml
+/// # Either list the skill folders outright...
+/// skills = ["plugins/pdf/skills/pdf", "packages/docs/skills/review"]
+/// # ...or narrow the search.
+/// roots = ["plugins", "packages"]
+/// max_depth = 5
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SkillIndex {
+    #[serde(default)]
+    pub skills: Vec<String>,
+    #[serde(default)]
+    pub roots: Vec<String>,
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+}
+
+/// A folder discovery considers a skill candidate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FoundSkillDir {
+    /// `/`-separated path relative to the base, `.` for the base itself.
+    pub subpath: String,
+    /// Search root it was found under, or `INDEX_FILE` when the index listed it.
+    pub root: String,
+    /// Directory levels below `root`.
+    pub depth: usize,
+    /// False for folders directly inside a `skills` dir that hold no `SKILL.md` (and no
+    /// skill below), reported so the picker can say why they cannot be installed.
+    pub has_skill_md: bool,
+}
+
+/// Find skill folders under `base`: every directory holding a `SKILL.md`, at most
+/// `max_depth` levels below each search root (default: the index file's value, then
+/// `DEFAULT_MAX_DEPTH`). Paths matched by `.gitignore` files are skipped, and skills do
+/// not nest, except that `base` itself may be a skill next to others.
+pub fn discover_skill_dirs(base: &Path, max_depth: Option<usize>) -> Result<Vec<FoundSkillDir>> {
+    let index = read_index(base)?;
+    if !index.skills.is_empty() {
+        return Ok(index
+            .skills
+            .iter()
+            .filter(|entry| is_relative_inside(entry))
+            .map(|entry| {
+                let subpath = normalize_subpath(entry);
+                FoundSkillDir {
+                    has_skill_md: base.join(&subpath).join("SKILL.md").is_file(),
+                    depth: if subpath == "." {
+                        0
+                    } else {
+                        subpath.split('/').count()
+                    },
+                    root: INDEX_FILE.to_string(),
+                    subpath,
+                }
+            })
+            .collect());
+    }
+
+    let max_depth = max_depth
+        .or(index.max_depth)
+        .unwrap_or(DEFAULT_MAX_DEPTH)
+        .min(MAX_DEPTH_LIMIT);
+    let mut roots: Vec<PathBuf> = index
+        .roots
+        .iter()
+        .filter(|root| is_relative_inside(root))
+        .map(|root| PathBuf::from(normalize_subpath(root)))
+        .filter(|root| *root != Path::new("."))
+        .collect();
+    if roots.is_empty() {
+        roots.push(PathBuf::new());
+    }
+    let deepest_root = roots.iter().map(|r| r.components().count()).max();
+
+    let walk_roots = roots.clone();
+    let filter_base = base.to_path_buf();
+    let walker = WalkBuilder::new(base)
+        .standard_filters(false)
+        .git_ignore(true)
+        .require_git(false)
+        .follow_links(false)
+        .max_depth(Some(deepest_root.unwrap_or(0) + max_depth))
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(move |entry| {
+            if entry.file_name() == ".git" {
+                return false;
+            }
+            let Ok(rel) = entry.path().strip_prefix(&filter_base) else {
+                return true;
+            };
+            // Walk down towards each root and anywhere inside one.
+            walk_roots
+                .iter()
+                .any(|root| root.starts_with(rel) || rel.starts_with(root))
+        })
+        .build();
+
+    let mut found: Vec<FoundSkillDir> = Vec::new();
+    let mut missing: Vec<FoundSkillDir> = Vec::new();
+    for entry in walker {
+        let entry = entry?;
+        if !entry.file_type().is_some_and(|t| t.is_dir()) {
+            continue;
+        }
+        let rel = entry
+            .path()
+            .strip_prefix(base)
+            .with_context(|| format!("strip prefix {:?}", entry.path()))?;
+        let Some((root, depth)) = placement(rel, &roots, max_depth) else {
+            continue;
+        };
+        // Skills do not nest: nothing below a skill folder (other than the base) counts.
+        if found
+            .iter()
+            .any(|f| f.subpath != "." && rel.starts_with(&f.subpath))
+        {
+            continue;
+        }
+        let candidate = FoundSkillDir {
+            subpath: to_subpath(rel),
+            root: to_subpath(root),
+            depth,
+            has_skill_md: true,
+        };
+        if entry.path().join("SKILL.md").is_file() {
+            found.push(candidate);
+        } else if is_skills_dir_child(rel) {
+            missing.push(FoundSkillDir {
+                has_skill_md: false,
+                ..candidate
+            });
+        }
+    }
+
+    missing.retain(|m| {
+        !found
+            .iter()
+            .any(|f| Path::new(&f.subpath).starts_with(&m.subpath))
+    });
+    found.extend(missing);
+    Ok(found)
+}
+
+fn read_index(base: &Path) -> Result<SkillIndex> {
+    let path = base.join(INDEX_FILE);
+    if !path.is_file() {
+        return Ok(SkillIndex::default());
+    }
+    let text = std::fs::read_to_string(&path).with_context(|| format!("read {:?}", path))?;
+    toml::from_str(&text).with_context(|| format!("invalid {}", INDEX_FILE))
+}
+
+/// The search root `rel` falls under and its depth below it, or `None` when `rel` is
+/// outside every root (or too deep). Ancestors of a root are walked through but are
+/// not candidates themselves.
+fn placement<'a>(rel: &Path, roots: &'a [PathBuf], max_depth: usize) -> Option<(&'a Path, usize)> {
+    roots
+        .iter()
+        .filter(|root| rel.starts_with(root))
+        .max_by_key(|root| root.components().count())
+        .map(|root| {
+            (
+                root.as_path(),
+                rel.components().count() - root.components().count(),
+            )
+        })
+        .filter(|(_, depth)| *depth <= max_depth)
+}
+
+/// Whether `rel` is a non-hidden folder directly inside a dir named `skills` (or one of
+/// its hidden groups like `skills/.curated`).
+fn is_skills_dir_child(rel: &Path) -> bool {
+    let names: Vec<String> = rel
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    match names.as_slice() {
+        [.., parent, name] if !name.starts_with('.') => {
+            parent == "skills"
+                || (parent.starts_with('.')
+                    && names.len() >= 3
+                    && names[names.len() - 3] == "skills")
+        }
+        _ => false,
+    }
+}
+
+/// Index entries must stay inside the base.
+fn is_relative_inside(raw: &str) -> bool {
+    Path::new(raw.trim())
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+fn normalize_subpath(raw: &str) -> String {
+    to_subpath(Path::new(raw.trim()))
+}
+
+fn to_subpath(rel: &Path) -> String {
+    let parts: Vec<String> = rel
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    if parts.is_empty() {
+        ".".to_string()
+    } else {
+        parts.join("/")
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/skill_discovery.rs"]
+mod tests;
@@ -0,0 +1,129 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use tauri::Manager;
+use uuid::Uuid;
+
+use super::content_hash::hash_dir;
+use super::skill_store::{SkillRecord, SkillSnapshotRecord, SkillStore};
+use super::sync_engine::copy_dir_recursive;
+
+const HISTORY_DIR_NAME: &str = "skill-history";
+const BASE_DIR_NAME: &str = "base";
+pub const MAX_SNAPSHOTS_PER_SKILL: usize = 5;
+
+/// Snapshots live under app data (not the central repo) so they are never scanned,
+/// synced, or moved along with `set_central_repo_path`.
+pub fn resolve_history_root<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<PathBuf> {
+    let app_dir = app
+        .path()
+        .app_data_dir()
+        .context("failed to resolve app data dir")?;
+    Ok(app_dir.join(HISTORY_DIR_NAME))
+}
+
+/// Copy the current central dir of `skill` into the history store and record it,
+/// then drop the oldest snapshots beyond `MAX_SNAPSHOTS_PER_SKILL`.
+pub fn snapshot_skill_dir(
+    store: &SkillStore,
+    history_root: &Path,
+    skill: &SkillRecord,
+) -> Result<SkillSnapshotRecord> {
+    let central_path = Path::new(&skill.central_path);
+    if !central_path.exists() {
+        anyhow::bail!("central path not found: {:?}", central_path);
+    }
+    let record = snapshot_dir(
+        store,
+        history_root,
+        &skill.id,
+        central_path,
+        skill.source_revision.clone(),
+    )?;
+    prune_skill_snapshots(store, &skill.id, MAX_SNAPSHOTS_PER_SKILL)?;
+    Ok(record)
+}
+
+/// Record a copy of `source` as a snapshot of `skill_id`, e.g. a tool's own folder that
+/// is about to be replaced by the skill, so it can be restored later. Nothing is pruned
+/// here: onboarding snapshots every differing variant of a group, however many there are.
+pub fn snapshot_dir(
+    store: &SkillStore,
+    history_root: &Path,
+    skill_id: &str,
+    source: &Path,
+    source_revision: Option<String>,
+) -> Result<SkillSnapshotRecord> {
+    let id = Uuid::new_v4().to_string();
+    let snapshot_path = history_root.join(skill_id).join(&id);
+    copy_dir_recursive(source, &snapshot_path)
+        .with_context(|| format!("snapshot {:?} -> {:?}", source, snapshot_path))?;
+
+    let record = SkillSnapshotRecord {
+        id,
+        skill_id: skill_id.to_string(),
+        content_hash: hash_dir(&snapshot_path).ok(),
+        source_revision,
+        snapshot_path: snapshot_path.to_string_lossy().to_string(),
+        created_at: now_ms(),
+    };
+    if let Err(err) = store.insert_skill_snapshot(&record) {
+        let _ = std::fs::remove_dir_all(&snapshot_path);
+        return Err(err);
+    }
+    Ok(record)
+}
+
+/// Keep the newest `keep` snapshots of a skill; returns how many were removed.
+pub fn prune_skill_snapshots(store: &SkillStore, skill_id: &str, keep: usize) -> Result<usize> {
+    let snapshots = store.list_skill_snapshots(skill_id)?;
+    let mut removed = 0usize;
+    for snapshot in snapshots.into_iter().skip(keep) {
+        let path = PathBuf::from(&snapshot.snapshot_path);
+        if path.exists() {
+            std::fs::remove_dir_all(&path)
+                .with_context(|| format!("remove snapshot {:?}", path))?;
+        }
+        store.delete_skill_snapshot(&snapshot.id)?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+/// Pristine copy of what the last install/update put into central, before any local
+/// edits; the common ancestor when merging those edits with an upstream update.
+pub fn base_copy_path(history_root: &Path, skill_id: &str) -> PathBuf {
+    history_root.join(skill_id).join(BASE_DIR_NAME)
+}
+
+pub fn save_base_copy(history_root: &Path, skill_id: &str, source: &Path) -> Result<()> {
+    let dest = base_copy_path(history_root, skill_id);
+    let tmp = dest.with_file_name(format!(".{}-{}", BASE_DIR_NAME, Uuid::new_v4()));
+    copy_dir_recursive(source, &tmp).with_context(|| format!("copy {:?} -> {:?}", source, tmp))?;
+    if dest.exists() {
+        std::fs::remove_dir_all(&dest).with_context(|| format!("remove {:?}", dest))?;
+    }
+    std::fs::rename(&tmp, &dest).with_context(|| format!("rename {:?} -> {:?}", tmp, dest))?;
+    Ok(())
+}
+
+/// Best-effort removal of every on-disk snapshot of a deleted skill
+/// (the DB rows go away with the skill via ON DELETE CASCADE).
+pub fn remove_skill_history_dir(history_root: &Path, skill_id: &str) -> Result<()> {
+    let dir = history_root.join(skill_id);
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).with_context(|| format!("remove {:?}", dir))?;
+    }
+    Ok(())
+}
+
+fn now_ms() -> i64 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    now.as_millis() as i64
+}
+
+#[cfg(test)]
+#[path = "tests/skill_history.rs"]
+mod tests;
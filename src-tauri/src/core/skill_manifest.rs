@@ -0,0 +1,276 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::Value;
+
+use super::skill_store::SkillStore;
+
+/// The YAML frontmatter of a `SKILL.md`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct SkillManifest {
+    pub name: String,
+    pub description: Option<String>,
+    pub version: Option<String>,
+    pub license: Option<String>,
+    /// `allowed-tools`, given either as a YAML list or as one comma/space separated string.
+    pub allowed_tools: Vec<String>,
+    pub metadata: BTreeMap<String, serde_json::Value>,
+    /// `author`, or `metadata.author` when only that is set.
+    pub author: Option<String>,
+}
+
+/// Why a `SKILL.md` could not be read. `reason` is the code the frontend maps
+/// (`read_failed`, `invalid_frontmatter`, `missing_name`); `line`/`column` are 1-based
+/// positions in the file when the YAML parser reported one.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ManifestError {
+    pub reason: &'static str,
+    pub message: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl ManifestError {
+    fn new(reason: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            reason,
+            message: message.into(),
+            line: None,
+            column: None,
+        }
+    }
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(
+                f,
+                "{} at line {} column {}: {}",
+                self.reason, line, column, self.message
+            ),
+            _ => write!(f, "{}: {}", self.reason, self.message),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+/// Frontmatter keys as written; values are loosely typed (`version: 1.0`, a list or a
+/// string for `allowed-tools`) and normalized into `SkillManifest`.
+#[derive(Debug, Default, Deserialize)]
+struct RawFrontmatter {
+    name: Option<Value>,
+    description: Option<Value>,
+    version: Option<Value>,
+    license: Option<Value>,
+    #[serde(rename = "allowed-tools", alias = "allowed_tools")]
+    allowed_tools: Option<Value>,
+    metadata: Option<Value>,
+    author: Option<Value>,
+}
+
+impl SkillManifest {
+    /// Read the manifest of the `SKILL.md` at `path`.
+    pub fn read(path: &Path) -> Result<Self, ManifestError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| ManifestError::new("read_failed", err.to_string()))?;
+        Self::parse(&text)
+    }
+
+    /// Parse a `SKILL.md`; a manifest without a `name` is an error.
+    pub fn parse(text: &str) -> Result<Self, ManifestError> {
+        let manifest = Self::parse_unchecked(text)?;
+        if manifest.name.is_empty() {
+            return Err(ManifestError::new(
+                "missing_name",
+                "frontmatter has no `name`",
+            ));
+        }
+        Ok(manifest)
+    }
+
+    /// Like `parse`, but an empty `name` is left for the caller to fill in.
+    pub fn parse_unchecked(text: &str) -> Result<Self, ManifestError> {
+        let (yaml, _) = split_frontmatter(text).ok_or_else(|| {
+            ManifestError::new(
+                "invalid_frontmatter",
+                "SKILL.md must start with a `---` frontmatter block",
+            )
+        })?;
+        let raw: RawFrontmatter = match serde_yaml_ng::from_str::<Option<RawFrontmatter>>(yaml) {
+            Ok(raw) => raw.unwrap_or_default(),
+            Err(err) => {
+                let location = err.location();
+                return Err(ManifestError {
+                    reason: "invalid_frontmatter",
+                    message: err.to_string(),
+                    // The YAML starts on the line after the opening `---`.
+                    line: location.as_ref().map(|l| l.line() + 1),
+                    column: location.as_ref().map(|l| l.column()),
+                });
+            }
+        };
+
+        let metadata: BTreeMap<String, serde_json::Value> = match raw.metadata {
+            None | Some(Value::Null) => BTreeMap::new(),
+            Some(value @ Value::Mapping(_)) => serde_json::to_value(&value)
+                .ok()
+                .and_then(|json| serde_json::from_value(json).ok())
+                .ok_or_else(|| {
+                    ManifestError::new("invalid_frontmatter", "`metadata` keys must be strings")
+                })?,
+            Some(_) => {
+                return Err(ManifestError::new(
+                    "invalid_frontmatter",
+                    "`metadata` must be a mapping",
+                ))
+            }
+        };
+        let author = match raw.author {
+            // `author: {name: ..., email: ...}`
+            Some(Value::Mapping(map)) => map.get("name").and_then(scalar),
+            other => other.as_ref().and_then(scalar),
+        }
+        .or_else(|| {
+            metadata
+                .get("author")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        });
+
+        Ok(SkillManifest {
+            name: field(raw.name.as_ref(), "name")?.unwrap_or_default(),
+            description: field(raw.description.as_ref(), "description")?,
+            version: field(raw.version.as_ref(), "version")?,
+            license: field(raw.license.as_ref(), "license")?,
+            allowed_tools: allowed_tools(raw.allowed_tools.as_ref())?,
+            metadata,
+            author,
+        })
+    }
+}
+
+/// Split `text` into the YAML between the opening and closing `---` lines and the body
+/// after them, or `None` when it has no frontmatter block.
+pub fn split_frontmatter(text: &str) -> Option<(&str, &str)> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let (first, rest) = text.split_once('\n')?;
+    if first.trim_end() != "---" {
+        return None;
+    }
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed == "---" || trimmed == "..." {
+            return Some((&rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Store the manifest of the skill in `skill_dir`. A `SKILL.md` that cannot be read
+/// drops the stored one, so the UI never shows stale fields.
+pub fn refresh_skill_manifest(store: &SkillStore, skill_id: &str, skill_dir: &Path) -> Result<()> {
+    match SkillManifest::read(&skill_dir.join("SKILL.md")) {
+        Ok(manifest) => store.upsert_skill_manifest(skill_id, &manifest),
+        Err(err) => {
+            log::info!("[skill_manifest] {:?}: {}", skill_dir, err);
+            store.delete_skill_manifest(skill_id)
+        }
+    }
+}
+
+/// Read manifests for managed skills that have none stored yet (e.g. installed before
+/// manifests were kept). Returns how many were stored.
+pub fn backfill_skill_manifests(store: &SkillStore) -> Result<usize> {
+    let known = store.list_skill_manifests()?;
+    let mut stored = 0;
+    for skill in store.list_skills()? {
+        if known.contains_key(&skill.id) {
+            continue;
+        }
+        let path = Path::new(&skill.central_path).join("SKILL.md");
+        if let Ok(manifest) = SkillManifest::read(&path) {
+            store.upsert_skill_manifest(&skill.id, &manifest)?;
+            stored += 1;
+        }
+    }
+    Ok(stored)
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+    .filter(|s| !s.is_empty())
+}
+
+fn field(value: Option<&Value>, key: &str) -> Result<Option<String>, ManifestError> {
+    match value {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => scalar(value).map(Some).ok_or_else(|| {
+            ManifestError::new("invalid_frontmatter", format!("`{}` must be a string", key))
+        }),
+    }
+}
+
+fn allowed_tools(value: Option<&Value>) -> Result<Vec<String>, ManifestError> {
+    match value {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Sequence(items)) => items
+            .iter()
+            .map(|item| {
+                scalar(item).ok_or_else(|| {
+                    ManifestError::new(
+                        "invalid_frontmatter",
+                        "`allowed-tools` entries must be strings",
+                    )
+                })
+            })
+            .collect(),
+        Some(value) => scalar(value).map(|s| split_tools(&s)).ok_or_else(|| {
+            ManifestError::new(
+                "invalid_frontmatter",
+                "`allowed-tools` must be a list or a string",
+            )
+        }),
+    }
+}
+
+/// `Read, Grep` or `Bash(git add:*) Read`: split on commas and whitespace outside
+/// parentheses.
+fn split_tools(raw: &str) -> Vec<String> {
+    let mut tools = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    for c in raw.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if depth == 0 && (c == ',' || c.is_whitespace()) {
+            if !current.is_empty() {
+                tools.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        tools.push(current);
+    }
+    tools
+}
+
+#[cfg(test)]
+#[path = "tests/skill_manifest.rs"]
+mod tests;
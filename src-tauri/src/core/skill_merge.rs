@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::content_hash::hash_dir;
+use super::skill_diff::{as_text, list_files, read_file};
+use super::skill_store::SkillRecord;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct MergeReport {
+    /// Files where local edits were carried into the result without conflict.
+    pub merged: Vec<String>,
+    /// Files changed on both sides that could not be merged cleanly. Text files get
+    /// conflict markers; binary files keep the local version.
+    pub conflicts: Vec<String>,
+}
+
+/// True when central no longer matches the `content_hash` recorded at the last
+/// install/update, i.e. the user edited it in place. Unknown (no hash) counts as clean.
+pub fn has_local_changes(record: &SkillRecord) -> Result<bool> {
+    let Some(expected) = record.content_hash.as_deref() else {
+        return Ok(false);
+    };
+    let central_path = Path::new(&record.central_path);
+    if !central_path.exists() {
+        return Ok(false);
+    }
+    let actual =
+        hash_dir(central_path).with_context(|| format!("hash central dir {:?}", central_path))?;
+    Ok(actual != expected)
+}
+
+/// Three-way merge of `local` edits (relative to `base`) into `upstream`, in place.
+pub fn merge_into_upstream(base: &Path, local: &Path, upstream: &Path) -> Result<MergeReport> {
+    let base_files = list_files(base)?;
+    let local_files = list_files(local)?;
+    let upstream_files = list_files(upstream)?;
+
+    let mut paths: Vec<&String> = base_files
+        .keys()
+        .chain(local_files.keys())
+        .chain(upstream_files.keys())
+        .collect();
+    paths.sort();
+    paths.dedup();
+
+    let mut report = MergeReport::default();
+    for path in paths {
+        let b = base_files.get(path).map(|p| read_file(p)).transpose()?;
+        let l = local_files.get(path).map(|p| read_file(p)).transpose()?;
+        let u = upstream_files.get(path).map(|p| read_file(p)).transpose()?;
+        if l == b || l == u {
+            continue;
+        }
+
+        let dest = upstream.join(path);
+        if u == b {
+            // Only changed locally: keep the local side (including deletions).
+            match &l {
+                Some(bytes) => write_file(&dest, bytes)?,
+                None => std::fs::remove_file(&dest)
+                    .with_context(|| format!("remove file {:?}", dest))?,
+            }
+            report.merged.push(path.clone());
+            continue;
+        }
+
+        let texts = (
+            b.as_deref().map_or(Some(""), as_text),
+            l.as_deref().map(as_text),
+            u.as_deref().map(as_text),
+        );
+        match texts {
+            (Some(base_text), Some(Some(local_text)), Some(Some(upstream_text))) => {
+                match diffy::merge(base_text, local_text, upstream_text) {
+                    Ok(merged) => {
+                        write_file(&dest, merged.as_bytes())?;
+                        report.merged.push(path.clone());
+                    }
+                    Err(with_markers) => {
+                        write_file(&dest, with_markers.as_bytes())?;
+                        report.conflicts.push(path.clone());
+                    }
+                }
+            }
+            _ => {
+                // Binary, or edited on one side and deleted on the other: prefer whatever
+                // still has content, favouring local edits.
+                if let Some(bytes) = l.as_ref() {
+                    write_file(&dest, bytes)?;
+                }
+                report.conflicts.push(path.clone());
+            }
+        }
+    }
+    Ok(report)
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("create dir {:?}", parent))?;
+    }
+    std::fs::write(path, bytes).with_context(|| format!("write file {:?}", path))
+}
+
+#[cfg(test)]
+#[path = "tests/skill_merge.rs"]
+mod tests;
@@ -1,14 +1,21 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use anyhow::{Context, Result};
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, Row};
 use tauri::Manager;
 
+use super::skill_manifest::SkillManifest;
+
 const DB_FILE_NAME: &str = "skills_hub.db";
 const LEGACY_APP_IDENTIFIERS: &[&str] = &["com.tauri.dev", "com.tauri.dev.skillshub"];
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+const STATEMENT_CACHE_CAPACITY: usize = 32;
 
-// Schema versioning: bump when making changes and add a migration step.
-const SCHEMA_VERSION: i32 = 1;
+// Schema versioning: bump when making changes and append a step to `MIGRATIONS`.
+const SCHEMA_VERSION: i32 = 9;
 
 // Minimal schema for MVP: skills, skill_targets, settings, discovered_skills(optional).
 const SCHEMA_V1: &str = r#"
@@ -60,9 +67,208 @@ CREATE INDEX IF NOT EXISTS idx_skills_name ON skills(name);
 CREATE INDEX IF NOT EXISTS idx_skills_updated_at ON skills(updated_at);
 "#;
 
+// V2: prior versions of a skill kept on disk so an update can be rolled back.
+const SCHEMA_V2: &str = r#"
+CREATE TABLE IF NOT EXISTS skill_history (
+  id TEXT PRIMARY KEY,
+  skill_id TEXT NOT NULL,
+  content_hash TEXT NULL,
+  source_revision TEXT NULL,
+  snapshot_path TEXT NOT NULL,
+  created_at INTEGER NOT NULL,
+  FOREIGN KEY(skill_id) REFERENCES skills(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_skill_history_skill_id ON skill_history(skill_id, created_at);
+"#;
+
+// V3: folder inside a git source (selection installs only kept the repo URL in source_ref).
+const SCHEMA_V3: &str = r#"
+ALTER TABLE skills ADD COLUMN source_subpath TEXT NULL;
+"#;
+
+// V4: tag/commit a git skill is pinned to (NULL tracks the branch tip).
+const SCHEMA_V4: &str = r#"
+ALTER TABLE skills ADD COLUMN source_pin TEXT NULL;
+"#;
+
+// V5: targets are scoped to `global` (tool dirs under home) or a project root path, so
+// the unique key gains `scope`. SQLite cannot alter a constraint, hence the table rebuild.
+const SCHEMA_V5: &str = r#"
+CREATE TABLE skill_targets_v5 (
+  id TEXT PRIMARY KEY,
+  skill_id TEXT NOT NULL,
+  tool TEXT NOT NULL,
+  scope TEXT NOT NULL DEFAULT 'global',
+  target_path TEXT NOT NULL,
+  mode TEXT NOT NULL,
+  status TEXT NOT NULL,
+  last_error TEXT NULL,
+  synced_at INTEGER NULL,
+  UNIQUE(skill_id, tool, scope),
+  FOREIGN KEY(skill_id) REFERENCES skills(id) ON DELETE CASCADE
+);
+
+INSERT INTO skill_targets_v5 (
+  id, skill_id, tool, scope, target_path, mode, status, last_error, synced_at
+)
+SELECT id, skill_id, tool, 'global', target_path, mode, status, last_error, synced_at
+FROM skill_targets;
+
+DROP TABLE skill_targets;
+ALTER TABLE skill_targets_v5 RENAME TO skill_targets;
+"#;
+
+// V6: hash of what a format transformer wrote for tools that take rule files instead of
+// SKILL.md folders (NULL for linked/copied targets).
+const SCHEMA_V6: &str = r#"
+ALTER TABLE skill_targets ADD COLUMN rendered_hash TEXT NULL;
+"#;
+
+// V7: in-flight directory swaps (see `core/op_journal.rs`), replayed or rolled back on
+// startup so a crash mid-update never leaves a skill without its folder.
+const SCHEMA_V7: &str = r#"
+CREATE TABLE IF NOT EXISTS operation_journal (
+  id TEXT PRIMARY KEY,
+  kind TEXT NOT NULL,
+  target_path TEXT NOT NULL,
+  staged_path TEXT NOT NULL,
+  backup_path TEXT NOT NULL,
+  phase TEXT NOT NULL,
+  created_at INTEGER NOT NULL
+);
+"#;
+
+// V8: onboarding scans are persisted in `discovered_skills`, one row per tool folder.
+// `dir_stamp` summarizes file sizes and mtimes so an unchanged folder keeps its
+// fingerprint without being re-hashed. The table was never written before, but drop any
+// duplicates so the unique index can be built.
+const SCHEMA_V8: &str = r#"
+ALTER TABLE discovered_skills ADD COLUMN dir_stamp TEXT NULL;
+
+DELETE FROM discovered_skills
+WHERE rowid NOT IN (
+  SELECT MIN(rowid) FROM discovered_skills GROUP BY tool, found_path
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_discovered_skills_tool_path
+  ON discovered_skills(tool, found_path);
+"#;
+
+// V9: SKILL.md frontmatter of each managed skill (see `core/skill_manifest.rs`), refreshed
+// whenever its central folder changes. `allowed_tools` and `metadata` hold JSON.
+const SCHEMA_V9: &str = r#"
+CREATE TABLE IF NOT EXISTS skill_manifests (
+  skill_id TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  description TEXT NULL,
+  version TEXT NULL,
+  license TEXT NULL,
+  author TEXT NULL,
+  allowed_tools TEXT NOT NULL DEFAULT '[]',
+  metadata TEXT NOT NULL DEFAULT '{}',
+  FOREIGN KEY(skill_id) REFERENCES skills(id) ON DELETE CASCADE
+);
+"#;
+
+/// One schema step. `version` is the `user_version` the database is at after `apply` runs.
+struct Migration {
+    version: i32,
+    apply: fn(&Connection) -> Result<()>,
+}
+
+// Ordered list of schema steps; each runs in its own transaction together with the
+// `user_version` bump, so a failed step leaves the database at the previous version.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        apply: migrate_v1,
+    },
+    Migration {
+        version: 2,
+        apply: migrate_v2,
+    },
+    Migration {
+        version: 3,
+        apply: migrate_v3,
+    },
+    Migration {
+        version: 4,
+        apply: migrate_v4,
+    },
+    Migration {
+        version: 5,
+        apply: migrate_v5,
+    },
+    Migration {
+        version: 6,
+        apply: migrate_v6,
+    },
+    Migration {
+        version: 7,
+        apply: migrate_v7,
+    },
+    Migration {
+        version: 8,
+        apply: migrate_v8,
+    },
+    Migration {
+        version: 9,
+        apply: migrate_v9,
+    },
+];
+
+fn migrate_v1(conn: &Connection) -> Result<()> {
+    conn.execute_batch(SCHEMA_V1)?;
+    Ok(())
+}
+
+fn migrate_v2(conn: &Connection) -> Result<()> {
+    conn.execute_batch(SCHEMA_V2)?;
+    Ok(())
+}
+
+fn migrate_v3(conn: &Connection) -> Result<()> {
+    conn.execute_batch(SCHEMA_V3)?;
+    Ok(())
+}
+
+fn migrate_v4(conn: &Connection) -> Result<()> {
+    conn.execute_batch(SCHEMA_V4)?;
+    Ok(())
+}
+
+fn migrate_v5(conn: &Connection) -> Result<()> {
+    conn.execute_batch(SCHEMA_V5)?;
+    Ok(())
+}
+
+fn migrate_v6(conn: &Connection) -> Result<()> {
+    conn.execute_batch(SCHEMA_V6)?;
+    Ok(())
+}
+
+fn migrate_v7(conn: &Connection) -> Result<()> {
+    conn.execute_batch(SCHEMA_V7)?;
+    Ok(())
+}
+
+fn migrate_v8(conn: &Connection) -> Result<()> {
+    conn.execute_batch(SCHEMA_V8)?;
+    Ok(())
+}
+
+fn migrate_v9(conn: &Connection) -> Result<()> {
+    conn.execute_batch(SCHEMA_V9)?;
+    Ok(())
+}
+
 #[derive(Clone, Debug)]
 pub struct SkillStore {
     db_path: PathBuf,
+    // One connection shared by every clone of the store (the app hands clones to each
+    // command). Opened lazily so constructing a store never touches the filesystem.
+    conn: Arc<Mutex<Option<Connection>>>,
 }
 
 #[derive(Clone, Debug)]
@@ -72,6 +278,10 @@ pub struct SkillRecord {
     pub source_type: String,
     pub source_ref: Option<String>,
     pub source_revision: Option<String>,
+    /// Skill folder inside a git source; `None` means the repo root (or not a git source).
+    pub source_subpath: Option<String>,
+    /// Tag or commit a git skill is pinned to; updates only move when this changes.
+    pub source_pin: Option<String>,
     pub central_path: String,
     pub content_hash: Option<String>,
     pub created_at: i64,
@@ -86,16 +296,83 @@ pub struct SkillTargetRecord {
     pub id: String,
     pub skill_id: String,
     pub tool: String,
+    /// `global` or the project root the target lives in (see `TargetScope`).
+    pub scope: String,
     pub target_path: String,
     pub mode: String,
     pub status: String,
     pub last_error: Option<String>,
     pub synced_at: Option<i64>,
+    /// `sha256:` of the rendered file when a format transformer produced the target.
+    pub rendered_hash: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SkillSnapshotRecord {
+    pub id: String,
+    pub skill_id: String,
+    pub content_hash: Option<String>,
+    pub source_revision: Option<String>,
+    pub snapshot_path: String,
+    pub created_at: i64,
+}
+
+/// A journaled directory swap: `staged` replaces `target`, the old `target` waits at
+/// `backup` until the new one is in place.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OperationRecord {
+    pub id: String,
+    pub kind: String,
+    pub target_path: String,
+    pub staged_path: String,
+    pub backup_path: String,
+    /// `staging` while `staged_path` is being built, `ready` once it is complete.
+    pub phase: String,
+    pub created_at: i64,
+}
+
+/// A skill folder found in a tool directory by the last onboarding scan.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiscoveredSkillRecord {
+    pub id: String,
+    pub tool: String,
+    pub found_path: String,
+    pub name_guess: Option<String>,
+    pub fingerprint: Option<String>,
+    pub dir_stamp: Option<String>,
+    /// When the folder was first seen.
+    pub found_at: i64,
+    /// Managed skill the folder was imported as (and replaced by a link to).
+    pub imported_skill_id: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SkillWithTargets {
+    pub skill: SkillRecord,
+    pub targets: Vec<SkillTargetRecord>,
 }
 
+const SKILL_COLUMNS: &str = "id, name, source_type, source_ref, source_revision, central_path, \
+     content_hash, created_at, updated_at, last_sync_at, last_seen_at, status, source_subpath, \
+     source_pin";
+const SKILL_COLUMN_COUNT: usize = 14;
+const TARGET_COLUMNS: &str =
+    "id, skill_id, tool, target_path, mode, status, last_error, synced_at, scope, rendered_hash";
+const SNAPSHOT_COLUMNS: &str =
+    "id, skill_id, content_hash, source_revision, snapshot_path, created_at";
+const OPERATION_COLUMNS: &str =
+    "id, kind, target_path, staged_path, backup_path, phase, created_at";
+const MANIFEST_COLUMNS: &str =
+    "skill_id, name, description, version, license, author, allowed_tools, metadata";
+const DISCOVERED_COLUMNS: &str =
+    "id, tool, found_path, name_guess, fingerprint, dir_stamp, found_at, imported_skill_id";
+
 impl SkillStore {
     pub fn new(db_path: PathBuf) -> Self {
-        Self { db_path }
+        Self {
+            db_path,
+            conn: Arc::new(Mutex::new(None)),
+        }
     }
 
     #[allow(dead_code)]
@@ -104,28 +381,12 @@ impl SkillStore {
     }
 
     pub fn ensure_schema(&self) -> Result<()> {
-        self.with_conn(|conn| {
-            conn.execute_batch("PRAGMA foreign_keys = ON;")?;
-
-            let user_version: i32 = conn.query_row("PRAGMA user_version;", [], |row| row.get(0))?;
-            if user_version == 0 {
-                conn.execute_batch(SCHEMA_V1)?;
-                conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
-            } else if user_version > SCHEMA_VERSION {
-                anyhow::bail!(
-                    "database schema version {} is newer than app supports {}",
-                    user_version,
-                    SCHEMA_VERSION
-                );
-            }
-
-            Ok(())
-        })
+        self.with_conn(|conn| run_migrations(conn, &self.db_path, MIGRATIONS, SCHEMA_VERSION))
     }
 
     pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
         self.with_conn(|conn| {
-            let mut stmt = conn.prepare("SELECT value FROM settings WHERE key = ?1")?;
+            let mut stmt = conn.prepare_cached("SELECT value FROM settings WHERE key = ?1")?;
             let mut rows = stmt.query(params![key])?;
             Ok(rows
                 .next()?
@@ -136,11 +397,11 @@ impl SkillStore {
 
     pub fn set_setting(&self, key: &str, value: &str) -> Result<()> {
         self.with_conn(|conn| {
-            conn.execute(
+            conn.prepare_cached(
                 "INSERT INTO settings (key, value) VALUES (?1, ?2)
          ON CONFLICT(key) DO UPDATE SET value = excluded.value",
-                params![key, value],
-            )?;
+            )?
+            .execute(params![key, value])?;
             Ok(())
         })
     }
@@ -155,13 +416,14 @@ impl SkillStore {
 
     pub fn upsert_skill(&self, record: &SkillRecord) -> Result<()> {
         self.with_conn(|conn| {
-            conn.execute(
+            conn.prepare_cached(
                 "INSERT INTO skills (
           id, name, source_type, source_ref, source_revision, central_path, content_hash,
-          created_at, updated_at, last_sync_at, last_seen_at, status
+          created_at, updated_at, last_sync_at, last_seen_at, status, source_subpath,
+          source_pin
         ) VALUES (
           ?1, ?2, ?3, ?4, ?5, ?6, ?7,
-          ?8, ?9, ?10, ?11, ?12
+          ?8, ?9, ?10, ?11, ?12, ?13, ?14
         )
         ON CONFLICT(id) DO UPDATE SET
           name = excluded.name,
@@ -174,79 +436,71 @@ impl SkillStore {
           updated_at = excluded.updated_at,
           last_sync_at = excluded.last_sync_at,
           last_seen_at = excluded.last_seen_at,
-          status = excluded.status",
-                params![
-                    record.id,
-                    record.name,
-                    record.source_type,
-                    record.source_ref,
-                    record.source_revision,
-                    record.central_path,
-                    record.content_hash,
-                    record.created_at,
-                    record.updated_at,
-                    record.last_sync_at,
-                    record.last_seen_at,
-                    record.status
-                ],
-            )?;
+          status = excluded.status,
+          source_subpath = excluded.source_subpath,
+          source_pin = excluded.source_pin",
+            )?
+            .execute(params![
+                record.id,
+                record.name,
+                record.source_type,
+                record.source_ref,
+                record.source_revision,
+                record.central_path,
+                record.content_hash,
+                record.created_at,
+                record.updated_at,
+                record.last_sync_at,
+                record.last_seen_at,
+                record.status,
+                record.source_subpath,
+                record.source_pin
+            ])?;
             Ok(())
         })
     }
 
     pub fn upsert_skill_target(&self, record: &SkillTargetRecord) -> Result<()> {
         self.with_conn(|conn| {
-            conn.execute(
+            conn.prepare_cached(
                 "INSERT INTO skill_targets (
-          id, skill_id, tool, target_path, mode, status, last_error, synced_at
+          id, skill_id, tool, target_path, mode, status, last_error, synced_at, scope,
+          rendered_hash
         ) VALUES (
-          ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8
+          ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10
         )
-        ON CONFLICT(skill_id, tool) DO UPDATE SET
+        ON CONFLICT(skill_id, tool, scope) DO UPDATE SET
           target_path = excluded.target_path,
           mode = excluded.mode,
           status = excluded.status,
           last_error = excluded.last_error,
-          synced_at = excluded.synced_at",
-                params![
-                    record.id,
-                    record.skill_id,
-                    record.tool,
-                    record.target_path,
-                    record.mode,
-                    record.status,
-                    record.last_error,
-                    record.synced_at
-                ],
-            )?;
+          synced_at = excluded.synced_at,
+          rendered_hash = excluded.rendered_hash",
+            )?
+            .execute(params![
+                record.id,
+                record.skill_id,
+                record.tool,
+                record.target_path,
+                record.mode,
+                record.status,
+                record.last_error,
+                record.synced_at,
+                record.scope,
+                record.rendered_hash
+            ])?;
             Ok(())
         })
     }
 
     pub fn list_skills(&self) -> Result<Vec<SkillRecord>> {
         self.with_conn(|conn| {
-            let mut stmt = conn.prepare(
-        "SELECT id, name, source_type, source_ref, source_revision, central_path, content_hash,
-                created_at, updated_at, last_sync_at, last_seen_at, status
+            let mut stmt = conn.prepare_cached(&format!(
+                "SELECT {SKILL_COLUMNS}
          FROM skills
-         ORDER BY updated_at DESC",
-      )?;
-            let rows = stmt.query_map([], |row| {
-                Ok(SkillRecord {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    source_type: row.get(2)?,
-                    source_ref: row.get(3)?,
-                    source_revision: row.get(4)?,
-                    central_path: row.get(5)?,
-                    content_hash: row.get(6)?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                    last_sync_at: row.get(9)?,
-                    last_seen_at: row.get(10)?,
-                    status: row.get(11)?,
-                })
-            })?;
+         ORDER BY updated_at DESC"
+            ))?;
+            let rows = stmt.query_map([], |row| skill_from_row(row, 0))?;
 
             let mut items = Vec::new();
             for row in rows {
@@ -256,31 +510,57 @@ impl SkillStore {
         })
     }
 
+    /// All skills (newest first) with their targets, in a single query.
+    pub fn list_skills_with_targets(&self) -> Result<Vec<SkillWithTargets>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare_cached(
+                "SELECT s.id, s.name, s.source_type, s.source_ref, s.source_revision,
+                s.central_path, s.content_hash, s.created_at, s.updated_at,
+                s.last_sync_at, s.last_seen_at, s.status, s.source_subpath, s.source_pin,
+                t.id, t.skill_id, t.tool, t.target_path, t.mode, t.status,
+                t.last_error, t.synced_at, t.scope, t.rendered_hash
+         FROM skills s
+         LEFT JOIN skill_targets t ON t.skill_id = s.id
+         ORDER BY s.updated_at DESC, s.id ASC, t.tool ASC, t.scope ASC",
+            )?;
+            let mut rows = stmt.query([])?;
+
+            let mut items: Vec<SkillWithTargets> = Vec::new();
+            while let Some(row) = rows.next()? {
+                let skill_id: String = row.get(0)?;
+                if items
+                    .last()
+                    .map(|item| item.skill.id != skill_id)
+                    .unwrap_or(true)
+                {
+                    items.push(SkillWithTargets {
+                        skill: skill_from_row(row, 0)?,
+                        targets: Vec::new(),
+                    });
+                }
+                // LEFT JOIN: a skill without targets yields one row of NULL target columns.
+                if row.get::<_, Option<String>>(SKILL_COLUMN_COUNT)?.is_some() {
+                    let target = target_from_row(row, SKILL_COLUMN_COUNT)?;
+                    if let Some(item) = items.last_mut() {
+                        item.targets.push(target);
+                    }
+                }
+            }
+            Ok(items)
+        })
+    }
+
     pub fn get_skill_by_id(&self, skill_id: &str) -> Result<Option<SkillRecord>> {
         self.with_conn(|conn| {
-            let mut stmt = conn.prepare(
-        "SELECT id, name, source_type, source_ref, source_revision, central_path, content_hash,
-                created_at, updated_at, last_sync_at, last_seen_at, status
+            let mut stmt = conn.prepare_cached(&format!(
+                "SELECT {SKILL_COLUMNS}
          FROM skills
          WHERE id = ?1
-         LIMIT 1",
-      )?;
+         LIMIT 1"
+            ))?;
             let mut rows = stmt.query(params![skill_id])?;
             if let Some(row) = rows.next()? {
-                Ok(Some(SkillRecord {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    source_type: row.get(2)?,
-                    source_ref: row.get(3)?,
-                    source_revision: row.get(4)?,
-                    central_path: row.get(5)?,
-                    content_hash: row.get(6)?,
-                    created_at: row.get(7)?,
-                    updated_at: row.get(8)?,
-                    last_sync_at: row.get(9)?,
-                    last_seen_at: row.get(10)?,
-                    status: row.get(11)?,
-                }))
+                Ok(Some(skill_from_row(row, 0)?))
             } else {
                 Ok(None)
             }
@@ -289,31 +569,21 @@ impl SkillStore {
 
     pub fn delete_skill(&self, skill_id: &str) -> Result<()> {
         self.with_conn(|conn| {
-            conn.execute("DELETE FROM skills WHERE id = ?1", params![skill_id])?;
+            conn.prepare_cached("DELETE FROM skills WHERE id = ?1")?
+                .execute(params![skill_id])?;
             Ok(())
         })
     }
 
     pub fn list_skill_targets(&self, skill_id: &str) -> Result<Vec<SkillTargetRecord>> {
         self.with_conn(|conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, skill_id, tool, target_path, mode, status, last_error, synced_at
+            let mut stmt = conn.prepare_cached(&format!(
+                "SELECT {TARGET_COLUMNS}
          FROM skill_targets
          WHERE skill_id = ?1
-         ORDER BY tool ASC",
-            )?;
-            let rows = stmt.query_map(params![skill_id], |row| {
-                Ok(SkillTargetRecord {
-                    id: row.get(0)?,
-                    skill_id: row.get(1)?,
-                    tool: row.get(2)?,
-                    target_path: row.get(3)?,
-                    mode: row.get(4)?,
-                    status: row.get(5)?,
-                    last_error: row.get(6)?,
-                    synced_at: row.get(7)?,
-                })
-            })?;
+         ORDER BY tool ASC, scope ASC"
+            ))?;
+            let rows = stmt.query_map(params![skill_id], |row| target_from_row(row, 0))?;
 
             let mut items = Vec::new();
             for row in rows {
@@ -325,7 +595,7 @@ impl SkillStore {
 
     pub fn list_all_skill_target_paths(&self) -> Result<Vec<(String, String)>> {
         self.with_conn(|conn| {
-            let mut stmt = conn.prepare(
+            let mut stmt = conn.prepare_cached(
                 "SELECT tool, target_path
          FROM skill_targets",
             )?;
@@ -343,48 +613,441 @@ impl SkillStore {
         &self,
         skill_id: &str,
         tool: &str,
+        scope: &str,
     ) -> Result<Option<SkillTargetRecord>> {
         self.with_conn(|conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, skill_id, tool, target_path, mode, status, last_error, synced_at
+            let mut stmt = conn.prepare_cached(&format!(
+                "SELECT {TARGET_COLUMNS}
          FROM skill_targets
-         WHERE skill_id = ?1 AND tool = ?2",
-            )?;
-            let mut rows = stmt.query(params![skill_id, tool])?;
+         WHERE skill_id = ?1 AND tool = ?2 AND scope = ?3"
+            ))?;
+            let mut rows = stmt.query(params![skill_id, tool, scope])?;
             if let Some(row) = rows.next()? {
-                Ok(Some(SkillTargetRecord {
-                    id: row.get(0)?,
-                    skill_id: row.get(1)?,
-                    tool: row.get(2)?,
-                    target_path: row.get(3)?,
-                    mode: row.get(4)?,
-                    status: row.get(5)?,
-                    last_error: row.get(6)?,
-                    synced_at: row.get(7)?,
-                }))
+                Ok(Some(target_from_row(row, 0)?))
             } else {
                 Ok(None)
             }
         })
     }
 
-    pub fn delete_skill_target(&self, skill_id: &str, tool: &str) -> Result<()> {
+    pub fn delete_skill_target(&self, skill_id: &str, tool: &str, scope: &str) -> Result<()> {
         self.with_conn(|conn| {
-            conn.execute(
-                "DELETE FROM skill_targets WHERE skill_id = ?1 AND tool = ?2",
-                params![skill_id, tool],
-            )?;
+            conn.prepare_cached(
+                "DELETE FROM skill_targets WHERE skill_id = ?1 AND tool = ?2 AND scope = ?3",
+            )?
+            .execute(params![skill_id, tool, scope])?;
+            Ok(())
+        })
+    }
+
+    pub fn insert_skill_snapshot(&self, record: &SkillSnapshotRecord) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.prepare_cached(
+                "INSERT INTO skill_history (
+          id, skill_id, content_hash, source_revision, snapshot_path, created_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?
+            .execute(params![
+                record.id,
+                record.skill_id,
+                record.content_hash,
+                record.source_revision,
+                record.snapshot_path,
+                record.created_at
+            ])?;
+            Ok(())
+        })
+    }
+
+    /// Snapshots of one skill, newest first.
+    pub fn list_skill_snapshots(&self, skill_id: &str) -> Result<Vec<SkillSnapshotRecord>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare_cached(&format!(
+                "SELECT {SNAPSHOT_COLUMNS}
+         FROM skill_history
+         WHERE skill_id = ?1
+         ORDER BY created_at DESC, rowid DESC"
+            ))?;
+            let rows = stmt.query_map(params![skill_id], snapshot_from_row)?;
+
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok(items)
+        })
+    }
+
+    pub fn get_skill_snapshot(&self, snapshot_id: &str) -> Result<Option<SkillSnapshotRecord>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare_cached(&format!(
+                "SELECT {SNAPSHOT_COLUMNS}
+         FROM skill_history
+         WHERE id = ?1"
+            ))?;
+            let mut rows = stmt.query(params![snapshot_id])?;
+            if let Some(row) = rows.next()? {
+                Ok(Some(snapshot_from_row(row)?))
+            } else {
+                Ok(None)
+            }
+        })
+    }
+
+    pub fn delete_skill_snapshot(&self, snapshot_id: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.prepare_cached("DELETE FROM skill_history WHERE id = ?1")?
+                .execute(params![snapshot_id])?;
+            Ok(())
+        })
+    }
+
+    pub fn insert_operation(&self, record: &OperationRecord) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.prepare_cached(&format!(
+                "INSERT INTO operation_journal ({OPERATION_COLUMNS})
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+            ))?
+            .execute(params![
+                record.id,
+                record.kind,
+                record.target_path,
+                record.staged_path,
+                record.backup_path,
+                record.phase,
+                record.created_at
+            ])?;
+            Ok(())
+        })
+    }
+
+    pub fn set_operation_phase(&self, id: &str, phase: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.prepare_cached("UPDATE operation_journal SET phase = ?2 WHERE id = ?1")?
+                .execute(params![id, phase])?;
+            Ok(())
+        })
+    }
+
+    /// Unfinished operations, oldest first.
+    pub fn list_operations(&self) -> Result<Vec<OperationRecord>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare_cached(&format!(
+                "SELECT {OPERATION_COLUMNS}
+         FROM operation_journal
+         ORDER BY created_at ASC, rowid ASC"
+            ))?;
+            let rows = stmt.query_map([], operation_from_row)?;
+
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok(items)
+        })
+    }
+
+    pub fn delete_operation(&self, id: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.prepare_cached("DELETE FROM operation_journal WHERE id = ?1")?
+                .execute(params![id])?;
+            Ok(())
+        })
+    }
+
+    pub fn upsert_skill_manifest(&self, skill_id: &str, manifest: &SkillManifest) -> Result<()> {
+        let allowed_tools = serde_json::to_string(&manifest.allowed_tools)?;
+        let metadata = serde_json::to_string(&manifest.metadata)?;
+        self.with_conn(|conn| {
+            conn.prepare_cached(&format!(
+                "INSERT INTO skill_manifests ({MANIFEST_COLUMNS})
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        ON CONFLICT(skill_id) DO UPDATE SET
+          name = excluded.name,
+          description = excluded.description,
+          version = excluded.version,
+          license = excluded.license,
+          author = excluded.author,
+          allowed_tools = excluded.allowed_tools,
+          metadata = excluded.metadata"
+            ))?
+            .execute(params![
+                skill_id,
+                manifest.name,
+                manifest.description,
+                manifest.version,
+                manifest.license,
+                manifest.author,
+                allowed_tools,
+                metadata
+            ])?;
+            Ok(())
+        })
+    }
+
+    /// Stored manifests keyed by skill id.
+    pub fn list_skill_manifests(&self) -> Result<HashMap<String, SkillManifest>> {
+        self.with_conn(|conn| {
+            let mut stmt =
+                conn.prepare_cached(&format!("SELECT {MANIFEST_COLUMNS} FROM skill_manifests"))?;
+            let rows = stmt.query_map([], manifest_from_row)?;
+
+            let mut items = HashMap::new();
+            for row in rows {
+                let (skill_id, manifest) = row?;
+                items.insert(skill_id, manifest);
+            }
+            Ok(items)
+        })
+    }
+
+    pub fn delete_skill_manifest(&self, skill_id: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.prepare_cached("DELETE FROM skill_manifests WHERE skill_id = ?1")?
+                .execute(params![skill_id])?;
+            Ok(())
+        })
+    }
+
+    /// Insert or refresh a scanned folder. An existing row for the same tool and path
+    /// keeps its `id`, `found_at` and `imported_skill_id`.
+    pub fn upsert_discovered_skill(&self, record: &DiscoveredSkillRecord) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.prepare_cached(&format!(
+                "INSERT INTO discovered_skills ({DISCOVERED_COLUMNS})
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        ON CONFLICT(tool, found_path) DO UPDATE SET
+          name_guess = excluded.name_guess,
+          fingerprint = excluded.fingerprint,
+          dir_stamp = excluded.dir_stamp"
+            ))?
+            .execute(params![
+                record.id,
+                record.tool,
+                record.found_path,
+                record.name_guess,
+                record.fingerprint,
+                record.dir_stamp,
+                record.found_at,
+                record.imported_skill_id
+            ])?;
+            Ok(())
+        })
+    }
+
+    pub fn list_discovered_skills(&self) -> Result<Vec<DiscoveredSkillRecord>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare_cached(&format!(
+                "SELECT {DISCOVERED_COLUMNS}
+         FROM discovered_skills
+         ORDER BY found_at ASC, rowid ASC"
+            ))?;
+            let rows = stmt.query_map([], discovered_from_row)?;
+
+            let mut items = Vec::new();
+            for row in rows {
+                items.push(row?);
+            }
+            Ok(items)
+        })
+    }
+
+    pub fn delete_discovered_skill(&self, id: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.prepare_cached("DELETE FROM discovered_skills WHERE id = ?1")?
+                .execute(params![id])?;
+            Ok(())
+        })
+    }
+
+    /// Record that the folder at `found_path` (for every tool that reported it) was
+    /// imported as `skill_id`.
+    pub fn mark_discovered_imported(&self, found_path: &str, skill_id: &str) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.prepare_cached(
+                "UPDATE discovered_skills SET imported_skill_id = ?2 WHERE found_path = ?1",
+            )?
+            .execute(params![found_path, skill_id])?;
             Ok(())
         })
     }
 
     fn with_conn<T>(&self, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
-        let conn = Connection::open(&self.db_path)
-            .with_context(|| format!("failed to open db at {:?}", self.db_path))?;
-        // Enforce foreign key constraints on every connection (rusqlite PRAGMA is per-connection).
-        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
-        f(&conn)
+        let mut guard = self.conn.lock().unwrap_or_else(|err| err.into_inner());
+        let conn = match &mut *guard {
+            Some(conn) => conn,
+            slot @ None => slot.insert(open_connection(&self.db_path)?),
+        };
+        f(conn)
+    }
+}
+
+fn open_connection(db_path: &Path) -> Result<Connection> {
+    let conn =
+        Connection::open(db_path).with_context(|| format!("failed to open db at {:?}", db_path))?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    // WAL keeps readers (e.g. the Skills page) from blocking on a concurrent sync write.
+    let journal_mode: String =
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get(0))?;
+    if !journal_mode.eq_ignore_ascii_case("wal") {
+        log::warn!(
+            "[skill_store] WAL unavailable for {:?}; journal_mode={}",
+            db_path,
+            journal_mode
+        );
     }
+    // Enforce foreign key constraints (rusqlite PRAGMA is per-connection).
+    conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+    conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+    Ok(conn)
+}
+
+fn skill_from_row(row: &Row<'_>, offset: usize) -> rusqlite::Result<SkillRecord> {
+    Ok(SkillRecord {
+        id: row.get(offset)?,
+        name: row.get(offset + 1)?,
+        source_type: row.get(offset + 2)?,
+        source_ref: row.get(offset + 3)?,
+        source_revision: row.get(offset + 4)?,
+        central_path: row.get(offset + 5)?,
+        content_hash: row.get(offset + 6)?,
+        created_at: row.get(offset + 7)?,
+        updated_at: row.get(offset + 8)?,
+        last_sync_at: row.get(offset + 9)?,
+        last_seen_at: row.get(offset + 10)?,
+        status: row.get(offset + 11)?,
+        source_subpath: row.get(offset + 12)?,
+        source_pin: row.get(offset + 13)?,
+    })
+}
+
+fn snapshot_from_row(row: &Row<'_>) -> rusqlite::Result<SkillSnapshotRecord> {
+    Ok(SkillSnapshotRecord {
+        id: row.get(0)?,
+        skill_id: row.get(1)?,
+        content_hash: row.get(2)?,
+        source_revision: row.get(3)?,
+        snapshot_path: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+fn operation_from_row(row: &Row<'_>) -> rusqlite::Result<OperationRecord> {
+    Ok(OperationRecord {
+        id: row.get(0)?,
+        kind: row.get(1)?,
+        target_path: row.get(2)?,
+        staged_path: row.get(3)?,
+        backup_path: row.get(4)?,
+        phase: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+fn manifest_from_row(row: &Row<'_>) -> rusqlite::Result<(String, SkillManifest)> {
+    let json = |index: usize| -> rusqlite::Result<String> { row.get(index) };
+    let manifest = SkillManifest {
+        name: row.get(1)?,
+        description: row.get(2)?,
+        version: row.get(3)?,
+        license: row.get(4)?,
+        author: row.get(5)?,
+        // Written by `upsert_skill_manifest`; unreadable JSON only loses the extras.
+        allowed_tools: serde_json::from_str(&json(6)?).unwrap_or_default(),
+        metadata: serde_json::from_str(&json(7)?).unwrap_or_default(),
+    };
+    Ok((row.get(0)?, manifest))
+}
+
+fn discovered_from_row(row: &Row<'_>) -> rusqlite::Result<DiscoveredSkillRecord> {
+    Ok(DiscoveredSkillRecord {
+        id: row.get(0)?,
+        tool: row.get(1)?,
+        found_path: row.get(2)?,
+        name_guess: row.get(3)?,
+        fingerprint: row.get(4)?,
+        dir_stamp: row.get(5)?,
+        found_at: row.get(6)?,
+        imported_skill_id: row.get(7)?,
+    })
+}
+
+fn target_from_row(row: &Row<'_>, offset: usize) -> rusqlite::Result<SkillTargetRecord> {
+    Ok(SkillTargetRecord {
+        id: row.get(offset)?,
+        skill_id: row.get(offset + 1)?,
+        tool: row.get(offset + 2)?,
+        target_path: row.get(offset + 3)?,
+        mode: row.get(offset + 4)?,
+        status: row.get(offset + 5)?,
+        last_error: row.get(offset + 6)?,
+        synced_at: row.get(offset + 7)?,
+        scope: row.get(offset + 8)?,
+        rendered_hash: row.get(offset + 9)?,
+    })
+}
+
+fn run_migrations(
+    conn: &Connection,
+    db_path: &Path,
+    migrations: &[Migration],
+    target_version: i32,
+) -> Result<()> {
+    let user_version: i32 = conn.query_row("PRAGMA user_version;", [], |row| row.get(0))?;
+    if user_version > target_version {
+        anyhow::bail!(
+            "database schema version {} is newer than app supports {}",
+            user_version,
+            target_version
+        );
+    }
+    if user_version == target_version {
+        return Ok(());
+    }
+
+    // A fresh database (version 0) has nothing worth keeping; anything else gets a copy
+    // before we touch it so a broken step never costs the user their skills.
+    if user_version > 0 {
+        backup_db_before_migration(conn, db_path, user_version)?;
+    }
+
+    for step in migrations
+        .iter()
+        .filter(|m| m.version > user_version && m.version <= target_version)
+    {
+        let tx = conn.unchecked_transaction()?;
+        (step.apply)(&tx)
+            .with_context(|| format!("failed to migrate db schema to v{}", step.version))?;
+        tx.pragma_update(None, "user_version", step.version)?;
+        tx.commit()?;
+        log::info!("[skill_store] migrated db schema to v{}", step.version);
+    }
+
+    let migrated: i32 = conn.query_row("PRAGMA user_version;", [], |row| row.get(0))?;
+    if migrated != target_version {
+        anyhow::bail!(
+            "database schema stopped at version {} (expected {})",
+            migrated,
+            target_version
+        );
+    }
+    Ok(())
+}
+
+fn backup_db_before_migration(conn: &Connection, db_path: &Path, from_version: i32) -> Result<()> {
+    let backup = db_path.with_extension(format!(
+        "bak-v{}-{}",
+        from_version,
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    ));
+    // VACUUM INTO produces a consistent snapshot even while other connections are open.
+    conn.execute("VACUUM INTO ?1", params![backup.to_string_lossy()])
+        .with_context(|| format!("failed to backup db {:?} -> {:?}", db_path, backup))?;
+    log::info!("[skill_store] backed up db before migration: {:?}", backup);
+    Ok(())
 }
 
 pub fn default_db_path<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<PathBuf> {
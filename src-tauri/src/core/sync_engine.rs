@@ -1,6 +1,14 @@
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use sha2::Digest;
+
+use super::cache_cleanup::CACHE_META_FILE;
+use super::op_journal::Swap;
+use super::skill_store::SkillStore;
+use super::tool_adapters::{
+    adapter_by_key, custom_sync_mode, transformer_for, SkillDocument, SkillTransformer,
+};
 
 #[allow(dead_code)]
 #[derive(Clone, Debug)]
@@ -9,6 +17,40 @@ pub enum SyncMode {
     Symlink,
     Junction,
     Copy,
+    /// A tree of per-file hardlinks to the central files (same filesystem only).
+    Hardlink,
+    /// A tree of copy-on-write clones (FICLONE on btrfs/xfs, clonefile on APFS).
+    Reflink,
+    /// Rendered into the tool's native format by a `SkillTransformer`.
+    Render,
+}
+
+impl SyncMode {
+    /// Value stored in `skill_targets.mode`.
+    pub fn as_key(&self) -> &'static str {
+        match self {
+            SyncMode::Auto => "auto",
+            SyncMode::Symlink => "symlink",
+            SyncMode::Junction => "junction",
+            SyncMode::Copy => "copy",
+            SyncMode::Hardlink => "hardlink",
+            SyncMode::Reflink => "reflink",
+            SyncMode::Render => "render",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "auto" => Some(SyncMode::Auto),
+            "symlink" => Some(SyncMode::Symlink),
+            "junction" => Some(SyncMode::Junction),
+            "copy" => Some(SyncMode::Copy),
+            "hardlink" => Some(SyncMode::Hardlink),
+            "reflink" => Some(SyncMode::Reflink),
+            "render" => Some(SyncMode::Render),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -16,6 +58,8 @@ pub struct SyncOutcome {
     pub mode_used: SyncMode,
     pub target_path: PathBuf,
     pub replaced: bool,
+    /// `sha256:` of the rendered file, for `SyncMode::Render`.
+    pub rendered_hash: Option<String>,
 }
 
 pub fn sync_dir_hybrid(source: &Path, target: &Path) -> Result<SyncOutcome> {
@@ -25,6 +69,7 @@ pub fn sync_dir_hybrid(source: &Path, target: &Path) -> Result<SyncOutcome> {
                 mode_used: SyncMode::Symlink,
                 target_path: target.to_path_buf(),
                 replaced: false,
+                rendered_hash: None,
             });
         }
         anyhow::bail!("target already exists: {:?}", target);
@@ -37,6 +82,7 @@ pub fn sync_dir_hybrid(source: &Path, target: &Path) -> Result<SyncOutcome> {
             mode_used: SyncMode::Symlink,
             target_path: target.to_path_buf(),
             replaced: false,
+            rendered_hash: None,
         });
     }
 
@@ -46,6 +92,7 @@ pub fn sync_dir_hybrid(source: &Path, target: &Path) -> Result<SyncOutcome> {
             mode_used: SyncMode::Junction,
             target_path: target.to_path_buf(),
             replaced: false,
+            rendered_hash: None,
         });
     }
 
@@ -54,6 +101,7 @@ pub fn sync_dir_hybrid(source: &Path, target: &Path) -> Result<SyncOutcome> {
         mode_used: SyncMode::Copy,
         target_path: target.to_path_buf(),
         replaced: false,
+        rendered_hash: None,
     })
 }
 
@@ -69,6 +117,7 @@ pub fn sync_dir_hybrid_with_overwrite(
                 mode_used: SyncMode::Symlink,
                 target_path: target.to_path_buf(),
                 replaced: false,
+                rendered_hash: None,
             });
         }
 
@@ -89,42 +138,175 @@ pub fn sync_dir_hybrid_with_overwrite(
 }
 
 pub fn sync_dir_copy_with_overwrite(
+    store: &SkillStore,
     source: &Path,
     target: &Path,
     overwrite: bool,
 ) -> Result<SyncOutcome> {
-    let mut did_replace = false;
-    if std::fs::symlink_metadata(target).is_ok() {
-        if overwrite {
-            remove_path_any(target)
-                .with_context(|| format!("remove existing target {:?}", target))?;
-            did_replace = true;
-        } else {
-            anyhow::bail!("target already exists: {:?}", target);
-        }
+    let did_replace = std::fs::symlink_metadata(target).is_ok();
+    if did_replace && !overwrite {
+        anyhow::bail!("target already exists: {:?}", target);
     }
 
+    // Copy next to the target and swap it in, so the old copy stays until the new one
+    // is complete.
     ensure_parent_dir(target)?;
-    copy_dir_recursive(source, target)?;
+    let swap = Swap::begin(store, "copy_target", target)?;
+    if let Err(err) = copy_dir_recursive(source, swap.staged()) {
+        swap.abort();
+        return Err(err);
+    }
+    swap.commit()?;
 
     Ok(SyncOutcome {
         mode_used: SyncMode::Copy,
         target_path: target.to_path_buf(),
         replaced: did_replace,
+        rendered_hash: None,
     })
 }
 
-pub fn sync_dir_for_tool_with_overwrite(
+/// Materialize `source` at `target` as real files: a byte copy, or a hardlink/reflink
+/// tree for those modes. Links fall back to a copy (reported as `SyncMode::Copy`) when
+/// the filesystem cannot provide them, e.g. across devices.
+pub fn sync_dir_tree_with_overwrite(
+    store: &SkillStore,
+    source: &Path,
+    target: &Path,
+    overwrite: bool,
+    mode: SyncMode,
+) -> Result<SyncOutcome> {
+    if !matches!(mode, SyncMode::Hardlink | SyncMode::Reflink) {
+        return sync_dir_copy_with_overwrite(store, source, target, overwrite);
+    }
+    let did_replace = std::fs::symlink_metadata(target).is_ok();
+    if did_replace && !overwrite {
+        anyhow::bail!("target already exists: {:?}", target);
+    }
+
+    ensure_parent_dir(target)?;
+    let swap = Swap::begin(store, "copy_target", target)?;
+    let mode_used = match link_dir_tree(source, swap.staged(), &mode) {
+        Ok(()) => mode,
+        Err(err) => {
+            log::info!(
+                "{} {:?} -> {:?} unavailable, copying: {:#}",
+                mode.as_key(),
+                source,
+                target,
+                err
+            );
+            let copied = remove_path_any(swap.staged())
+                .and_then(|_| copy_dir_recursive(source, swap.staged()));
+            if let Err(err) = copied {
+                swap.abort();
+                return Err(err);
+            }
+            SyncMode::Copy
+        }
+    };
+    swap.commit()?;
+
+    Ok(SyncOutcome {
+        mode_used,
+        target_path: target.to_path_buf(),
+        replaced: did_replace,
+        rendered_hash: None,
+    })
+}
+
+/// Sync `source` into a tool's skills dir the way that tool needs it. `preferred` is the
+/// mode the user chose for the tool (see `core/tool_sync_modes.rs`) and takes
+/// precedence over its default. Copies are swapped in through the journal in `store`.
+pub fn sync_dir_for_tool_with_mode(
+    store: &SkillStore,
     tool_key: &str,
     source: &Path,
     target: &Path,
     overwrite: bool,
+    preferred: Option<SyncMode>,
 ) -> Result<SyncOutcome> {
-    // Cursor 目前不支持软链/junction：强制使用 copy，避免同步后在 Cursor 内不可用。
-    if tool_key.eq_ignore_ascii_case("cursor") {
-        return sync_dir_copy_with_overwrite(source, target, overwrite);
+    if let Some(transformer) = adapter_by_key(tool_key).as_ref().and_then(transformer_for) {
+        let output = transformer.output_path(target);
+        return sync_rendered_with_overwrite(transformer, source, &output, overwrite);
+    }
+    let mode = preferred.or_else(|| custom_sync_mode(tool_key));
+    // Cursor 目前不支持软链/junction：默认使用 copy，避免同步后在 Cursor 内不可用。
+    let mode = mode.unwrap_or(if tool_key.eq_ignore_ascii_case("cursor") {
+        SyncMode::Copy
+    } else {
+        SyncMode::Auto
+    });
+    match mode {
+        SyncMode::Copy | SyncMode::Hardlink | SyncMode::Reflink => {
+            sync_dir_tree_with_overwrite(store, source, target, overwrite, mode)
+        }
+        _ => sync_dir_hybrid_with_overwrite(source, target, overwrite),
     }
-    sync_dir_hybrid_with_overwrite(source, target, overwrite)
+}
+
+/// Render the skill in `source` with `transformer` and write it to `output`. An output
+/// that already holds the same rendering counts as synced.
+pub fn sync_rendered_with_overwrite(
+    transformer: &dyn SkillTransformer,
+    source: &Path,
+    output: &Path,
+    overwrite: bool,
+) -> Result<SyncOutcome> {
+    let rendered = transformer.render(&SkillDocument::read(source)?);
+    let rendered_hash = format!(
+        "sha256:{}",
+        hex::encode(sha2::Sha256::digest(rendered.as_bytes()))
+    );
+    let mut did_replace = false;
+    let mut unchanged = false;
+    if std::fs::symlink_metadata(output).is_ok() {
+        unchanged = std::fs::read_to_string(output).is_ok_and(|current| current == rendered);
+        if !unchanged {
+            if !overwrite {
+                anyhow::bail!("target already exists: {:?}", output);
+            }
+            // A folder (or link to one) cannot be renamed over; files are replaced below.
+            if std::fs::metadata(output).map_or(true, |m| m.is_dir()) {
+                remove_path_any(output)
+                    .with_context(|| format!("remove existing target {:?}", output))?;
+            }
+            did_replace = true;
+        }
+    }
+
+    if !unchanged {
+        ensure_parent_dir(output)?;
+        write_file_atomic(output, rendered.as_bytes())?;
+    }
+    Ok(SyncOutcome {
+        mode_used: SyncMode::Render,
+        target_path: output.to_path_buf(),
+        replaced: did_replace,
+        rendered_hash: Some(rendered_hash),
+    })
+}
+
+/// Write `bytes` to a hidden sibling and rename it over `path`, so readers see either
+/// the old file or the new one, never a missing or half-written one.
+fn write_file_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let name = path
+        .file_name()
+        .with_context(|| format!("invalid target path {:?}", path))?;
+    let tmp = path.with_file_name(format!(
+        ".{}.skills-hub-tmp-{}",
+        name.to_string_lossy(),
+        uuid::Uuid::new_v4()
+    ));
+    let written = std::fs::write(&tmp, bytes)
+        .with_context(|| format!("write {:?}", tmp))
+        .and_then(|_| {
+            std::fs::rename(&tmp, path).with_context(|| format!("move {:?} into place", path))
+        });
+    if written.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    written
 }
 
 fn ensure_parent_dir(path: &Path) -> Result<()> {
@@ -134,7 +316,7 @@ fn ensure_parent_dir(path: &Path) -> Result<()> {
     Ok(())
 }
 
-fn remove_path_any(path: &Path) -> Result<()> {
+pub fn remove_path_any(path: &Path) -> Result<()> {
     let meta = match std::fs::symlink_metadata(path) {
         Ok(meta) => meta,
         Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
@@ -188,8 +370,76 @@ fn try_junction(source: &Path, target: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Recreate the directories of `source` under `target` and link every file with
+/// `mode` (`Hardlink` or `Reflink`). Fails on the first file that cannot be linked.
+fn link_dir_tree(source: &Path, target: &Path, mode: &SyncMode) -> Result<()> {
+    for entry in walkdir::WalkDir::new(source)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| !should_skip_copy(entry))
+    {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(source)?;
+        let target_path = target.join(relative);
+        if entry.file_type().is_dir() {
+            std::fs::create_dir_all(&target_path)
+                .with_context(|| format!("create dir {:?}", target_path))?;
+        } else if entry.file_type().is_file() {
+            match mode {
+                SyncMode::Reflink => reflink_file(entry.path(), &target_path),
+                _ => std::fs::hard_link(entry.path(), &target_path)
+                    .with_context(|| format!("hardlink {:?} -> {:?}", target_path, entry.path())),
+            }?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn reflink_file(source: &Path, target: &Path) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let src = std::fs::File::open(source).with_context(|| format!("open {:?}", source))?;
+    let dst = std::fs::File::create(target).with_context(|| format!("create {:?}", target))?;
+    // SAFETY: both descriptors are open for the duration of the call.
+    let rc = unsafe { libc::ioctl(dst.as_raw_fd(), libc::FICLONE, src.as_raw_fd()) };
+    if rc != 0 {
+        let err = std::io::Error::last_os_error();
+        drop(dst);
+        let _ = std::fs::remove_file(target);
+        return Err(err).with_context(|| format!("reflink {:?} -> {:?}", target, source));
+    }
+    std::fs::set_permissions(target, src.metadata()?.permissions())?;
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn reflink_file(source: &Path, target: &Path) -> Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let src = CString::new(source.as_os_str().as_bytes())?;
+    let dst = CString::new(target.as_os_str().as_bytes())?;
+    // SAFETY: both arguments are valid NUL-terminated paths.
+    let rc = unsafe { libc::clonefile(src.as_ptr(), dst.as_ptr(), 0) };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error())
+            .with_context(|| format!("reflink {:?} -> {:?}", target, source));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn reflink_file(source: &Path, target: &Path) -> Result<()> {
+    anyhow::bail!(
+        "reflink {:?} -> {:?}: not supported on this platform",
+        target,
+        source
+    );
+}
+
 fn should_skip_copy(entry: &walkdir::DirEntry) -> bool {
-    entry.file_name() == ".git"
+    entry.file_name() == ".git" || entry.file_name() == CACHE_META_FILE
 }
 
 pub fn copy_dir_recursive(source: &Path, target: &Path) -> Result<()> {
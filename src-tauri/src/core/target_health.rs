@@ -0,0 +1,254 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::Serialize;
+use sha2::Digest;
+
+use super::content_hash::hash_dir;
+use super::skill_store::{SkillStore, SkillTargetRecord};
+use super::sync_engine::{
+    remove_path_any, sync_dir_for_tool_with_mode, sync_rendered_with_overwrite,
+};
+use super::tool_adapters::{adapter_by_key, transformer_for};
+use super::tool_sync_modes::preferred_sync_mode;
+
+/// State of a synced target on disk compared with what was recorded at sync time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetHealth {
+    Healthy,
+    /// Nothing at `target_path`.
+    Missing,
+    /// A link whose destination no longer exists.
+    BrokenLink,
+    /// A link to something other than the skill's central folder (e.g. after the
+    /// central repo moved).
+    WrongLinkTarget,
+    /// A copied or rendered target whose content no longer matches the skill.
+    Drifted,
+}
+
+impl TargetHealth {
+    /// Value stored in `skill_targets.status`.
+    pub fn as_status(&self) -> &'static str {
+        match self {
+            TargetHealth::Healthy => "ok",
+            TargetHealth::Missing => "missing",
+            TargetHealth::BrokenLink => "broken_link",
+            TargetHealth::WrongLinkTarget => "wrong_link_target",
+            TargetHealth::Drifted => "drifted",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TargetCheck {
+    pub target_id: String,
+    pub skill_id: String,
+    pub tool: String,
+    pub scope: String,
+    pub target_path: String,
+    pub health: TargetHealth,
+    pub detail: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct RepairReport {
+    /// Targets that were re-synced, as checked afterwards.
+    pub repaired: Vec<TargetCheck>,
+    pub failed: Vec<RepairFailure>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RepairFailure {
+    pub target_id: String,
+    pub tool: String,
+    pub error: String,
+}
+
+/// Check every recorded target against the filesystem and store the outcome in
+/// `status`/`last_error`.
+pub fn verify_targets(store: &SkillStore) -> Result<Vec<TargetCheck>> {
+    verify_matching(store, |_| true)
+}
+
+/// Like `verify_targets`, for the targets recorded at `paths` only.
+pub fn verify_target_paths(store: &SkillStore, paths: &[PathBuf]) -> Result<Vec<TargetCheck>> {
+    verify_matching(store, |t| {
+        paths.iter().any(|p| Path::new(&t.target_path) == p)
+    })
+}
+
+fn verify_matching(
+    store: &SkillStore,
+    matches: impl Fn(&SkillTargetRecord) -> bool,
+) -> Result<Vec<TargetCheck>> {
+    let mut checks = Vec::new();
+    for entry in store.list_skills_with_targets()? {
+        let central = PathBuf::from(&entry.skill.central_path);
+        for target in entry.targets.into_iter().filter(|t| matches(t)) {
+            let (health, detail) = classify(&target, &central);
+            persist(store, &target, health, detail.clone())?;
+            checks.push(to_check(&target, health, detail));
+        }
+    }
+    Ok(checks)
+}
+
+/// Re-sync unhealthy targets from the central repo, overwriting whatever is there.
+/// `target_ids` limits the repair to those targets; `None` repairs all of them.
+pub fn repair_targets(store: &SkillStore, target_ids: Option<&[String]>) -> Result<RepairReport> {
+    let mut report = RepairReport::default();
+    for entry in store.list_skills_with_targets()? {
+        let central = PathBuf::from(&entry.skill.central_path);
+        for target in entry.targets {
+            if target_ids.is_some_and(|ids| !ids.contains(&target.id)) {
+                continue;
+            }
+            let (health, _) = classify(&target, &central);
+            if health == TargetHealth::Healthy {
+                continue;
+            }
+            match resync(store, &target, &central) {
+                Ok(record) => {
+                    let (health, detail) = classify(&record, &central);
+                    persist(store, &record, health, detail.clone())?;
+                    report.repaired.push(to_check(&record, health, detail));
+                }
+                Err(err) => {
+                    persist(store, &target, health, Some(format!("{:#}", err)))?;
+                    report.failed.push(RepairFailure {
+                        target_id: target.id.clone(),
+                        tool: target.tool.clone(),
+                        error: format!("{:#}", err),
+                    });
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+fn classify(target: &SkillTargetRecord, central: &Path) -> (TargetHealth, Option<String>) {
+    let path = Path::new(&target.target_path);
+    if std::fs::symlink_metadata(path).is_err() {
+        return (TargetHealth::Missing, None);
+    }
+
+    // `read_link` also resolves Windows junctions.
+    if let Ok(link) = std::fs::read_link(path) {
+        if !path.exists() {
+            return (
+                TargetHealth::BrokenLink,
+                Some(format!("link points to missing {:?}", link)),
+            );
+        }
+        return match (std::fs::canonicalize(path), std::fs::canonicalize(central)) {
+            (Ok(actual), Ok(expected)) if actual == expected => (TargetHealth::Healthy, None),
+            _ => (
+                TargetHealth::WrongLinkTarget,
+                Some(format!("link points to {:?}, expected {:?}", link, central)),
+            ),
+        };
+    }
+
+    if target.mode == "render" {
+        let current = std::fs::read(path)
+            .ok()
+            .map(|bytes| format!("sha256:{}", hex::encode(sha2::Sha256::digest(bytes))));
+        return if current.is_some() && current == target.rendered_hash {
+            (TargetHealth::Healthy, None)
+        } else {
+            (
+                TargetHealth::Drifted,
+                Some("rendered file was edited".to_string()),
+            )
+        };
+    }
+
+    match (hash_dir(path), hash_dir(central)) {
+        (Ok(actual), Ok(expected)) if actual == expected => (TargetHealth::Healthy, None),
+        (Ok(_), Ok(_)) => (
+            TargetHealth::Drifted,
+            Some("content differs from the central copy".to_string()),
+        ),
+        (Err(err), _) | (_, Err(err)) => (TargetHealth::Drifted, Some(format!("{:#}", err))),
+    }
+}
+
+fn resync(
+    store: &SkillStore,
+    target: &SkillTargetRecord,
+    central: &Path,
+) -> Result<SkillTargetRecord> {
+    let stored = Path::new(&target.target_path);
+    let transformer = adapter_by_key(&target.tool)
+        .as_ref()
+        .and_then(transformer_for);
+    let result = match transformer {
+        Some(transformer) if target.mode == "render" => {
+            sync_rendered_with_overwrite(transformer, central, stored, true)?
+        }
+        _ => {
+            // A dangling link is not "existing" to the sync engine; clear it first.
+            if std::fs::symlink_metadata(stored).is_ok() && !stored.exists() {
+                remove_path_any(stored)?;
+            }
+            let preferred = preferred_sync_mode(store, &target.tool);
+            sync_dir_for_tool_with_mode(store, &target.tool, central, stored, true, preferred)?
+        }
+    };
+    let record = SkillTargetRecord {
+        target_path: result.target_path.to_string_lossy().to_string(),
+        mode: result.mode_used.as_key().to_string(),
+        synced_at: Some(now_ms()),
+        rendered_hash: result.rendered_hash,
+        ..target.clone()
+    };
+    store.upsert_skill_target(&record)?;
+    Ok(record)
+}
+
+fn persist(
+    store: &SkillStore,
+    target: &SkillTargetRecord,
+    health: TargetHealth,
+    detail: Option<String>,
+) -> Result<()> {
+    let status = health.as_status();
+    if target.status == status && target.last_error == detail {
+        return Ok(());
+    }
+    store.upsert_skill_target(&SkillTargetRecord {
+        status: status.to_string(),
+        last_error: detail,
+        ..target.clone()
+    })
+}
+
+fn to_check(
+    target: &SkillTargetRecord,
+    health: TargetHealth,
+    detail: Option<String>,
+) -> TargetCheck {
+    TargetCheck {
+        target_id: target.id.clone(),
+        skill_id: target.skill_id.clone(),
+        tool: target.tool.clone(),
+        scope: target.scope.clone(),
+        target_path: target.target_path.clone(),
+        health,
+        detail,
+    }
+}
+
+fn now_ms() -> i64 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    now.as_millis() as i64
+}
+
+#[cfg(test)]
+#[path = "tests/target_health.rs"]
+mod tests;
@@ -0,0 +1,267 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::central_repo::validate_skill_name;
+use super::tool_adapters::adapter_by_key;
+
+pub const MANIFEST_FILE: &str = "skills.toml";
+pub const LOCK_FILE: &str = "skills.lock";
+const LOCK_VERSION: u32 = 1;
+const LOCK_HEADER: &str =
+    "# Generated by Skills Hub from skills.toml. Commit it; do not edit by hand.\n\n";
+
+/// Team manifest (`skills.toml`): the skills every developer should have and the
+/// tools each one is synced to.
+///
+/// ```toml
+/// tools = ["claude_code", "cursor"]
+///
+/// [[skill]]
+/// name = "pdf"
+/// source = "https://github.com/anthropics/skills/tree/main/skills/pdf"
+/// ref = "v1.2.0"
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TeamManifest {
+    /// Tools used by skills that do not list their own.
+    #[serde(default)]
+    pub tools: Vec<String>,
+    #[serde(default, rename = "skill")]
+    pub skills: Vec<ManifestSkill>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestSkill {
+    pub name: String,
+    /// Git URL, archive URL/file, or local directory (relative to the manifest).
+    pub source: String,
+    /// `git`, `local` or `archive`; inferred from `source` when omitted.
+    #[serde(default, rename = "type")]
+    pub source_type: Option<String>,
+    /// Tag or commit to pin a git skill to.
+    #[serde(default, rename = "ref")]
+    pub pin: Option<String>,
+    /// Skill folder inside the source, for sources holding several skills.
+    #[serde(default)]
+    pub subpath: Option<String>,
+    #[serde(default)]
+    pub tools: Option<Vec<String>>,
+}
+
+impl ManifestSkill {
+    pub fn kind(&self) -> &str {
+        match self.source_type.as_deref() {
+            Some(kind) => kind,
+            None => infer_source_type(&self.source),
+        }
+    }
+
+    pub fn tools<'a>(&'a self, manifest: &'a TeamManifest) -> &'a [String] {
+        self.tools.as_deref().unwrap_or(&manifest.tools)
+    }
+
+    /// Source as written in the manifest, with `@<ref>` for pinned git skills. Lock
+    /// entries are keyed on it so the lockfile reads the same on every machine.
+    pub fn declared_source(&self) -> String {
+        let source = self.source.trim();
+        match self.pin.as_deref().map(str::trim) {
+            Some(pin) if !pin.is_empty() => format!("{}@{}", source, pin),
+            _ => source.to_string(),
+        }
+    }
+
+    /// `source_ref` the installer records for this entry. Local paths (and local
+    /// archives) are resolved against `base_dir`, the manifest's directory.
+    pub fn resolved_source(&self, base_dir: &Path) -> String {
+        let source = self.source.trim();
+        match self.kind() {
+            "git" => self.declared_source(),
+            "local" => resolve_path(base_dir, source)
+                .join(self.subpath.as_deref().unwrap_or("."))
+                .components()
+                .collect::<PathBuf>()
+                .to_string_lossy()
+                .to_string(),
+            _ if is_remote(source) => source.to_string(),
+            _ => resolve_path(base_dir, source).to_string_lossy().to_string(),
+        }
+    }
+}
+
+/// Lockfile (`skills.lock`) next to the manifest, recording what each skill resolved
+/// to so teammates can tell when their copy has drifted.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(default, rename = "skill")]
+    pub skills: Vec<LockedSkill>,
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            version: LOCK_VERSION,
+            skills: Vec::new(),
+        }
+    }
+}
+
+impl Lockfile {
+    pub fn get(&self, name: &str) -> Option<&LockedSkill> {
+        self.skills.iter().find(|s| s.name == name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedSkill {
+    pub name: String,
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_revision: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
+
+pub fn parse_manifest(text: &str) -> Result<TeamManifest> {
+    let manifest: TeamManifest = toml::from_str(text).context("invalid skills.toml")?;
+    validate_manifest(&manifest)?;
+    Ok(manifest)
+}
+
+pub fn load_manifest(path: &Path) -> Result<TeamManifest> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("read {:?}", path))?;
+    parse_manifest(&text).with_context(|| format!("{:?}", path))
+}
+
+/// Lockfile at `path`; a missing file is an empty lockfile.
+pub fn load_lockfile(path: &Path) -> Result<Lockfile> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Lockfile::default()),
+        Err(err) => return Err(err).with_context(|| format!("read {:?}", path)),
+    };
+    let lock: Lockfile =
+        toml::from_str(&text).with_context(|| format!("invalid lockfile {:?}", path))?;
+    if lock.version != LOCK_VERSION {
+        anyhow::bail!(
+            "unsupported lockfile version {} in {:?}",
+            lock.version,
+            path
+        );
+    }
+    Ok(lock)
+}
+
+pub fn write_lockfile(path: &Path, lock: &Lockfile) -> Result<()> {
+    let body = toml::to_string(lock).context("serialize lockfile")?;
+    std::fs::write(path, format!("{}{}", LOCK_HEADER, body))
+        .with_context(|| format!("write {:?}", path))
+}
+
+/// Manifest path for `path`, which may be the manifest itself or the directory
+/// (usually a project root) holding `skills.toml`.
+pub fn manifest_file(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        path.join(MANIFEST_FILE)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// `skills.lock` belonging to the manifest at `manifest_path`.
+pub fn lockfile_path(manifest_path: &Path) -> PathBuf {
+    manifest_path.with_file_name(LOCK_FILE)
+}
+
+fn validate_manifest(manifest: &TeamManifest) -> Result<()> {
+    let mut names = HashSet::new();
+    for tool in &manifest.tools {
+        validate_tool(tool)?;
+    }
+    for skill in &manifest.skills {
+        let name = skill.name.as_str();
+        validate_skill_name(name)?;
+        if name.starts_with('.') {
+            anyhow::bail!("invalid skill name {:?}", name);
+        }
+        if !names.insert(name) {
+            anyhow::bail!("duplicate skill name {:?}", name);
+        }
+        if skill.source.trim().is_empty() {
+            anyhow::bail!("skill {:?} has no source", name);
+        }
+        match skill.kind() {
+            "git" => {}
+            "local" | "archive" if skill.pin.is_none() => {}
+            "local" | "archive" => anyhow::bail!("skill {:?}: ref is only valid for git", name),
+            other => anyhow::bail!("skill {:?}: unknown type {:?}", name, other),
+        }
+        if let Some(subpath) = &skill.subpath {
+            if Path::new(subpath)
+                .components()
+                .any(|c| !matches!(c, std::path::Component::Normal(_)))
+            {
+                anyhow::bail!("skill {:?}: invalid subpath {:?}", name, subpath);
+            }
+        }
+        for tool in skill.tools(manifest) {
+            validate_tool(tool)?;
+        }
+    }
+    Ok(())
+}
+
+fn validate_tool(tool: &str) -> Result<()> {
+    if adapter_by_key(tool).is_none() {
+        anyhow::bail!("unknown tool {:?}", tool);
+    }
+    Ok(())
+}
+
+fn infer_source_type(source: &str) -> &'static str {
+    let source = source.trim();
+    let lower = source
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    // A remote `.md` is usually a forge `/blob/<ref>/.../SKILL.md` link, so only a
+    // local one counts as a bare SKILL.md; use `type = "archive"` for raw URLs.
+    if [".zip", ".tar.gz", ".tgz", ".tar"]
+        .iter()
+        .any(|ext| lower.ends_with(ext))
+        || (lower.ends_with(".md") && !is_remote(source))
+    {
+        "archive"
+    } else if is_remote(source) {
+        "git"
+    } else if source.starts_with(['.', '/', '~', '\\']) || Path::new(source).is_absolute() {
+        "local"
+    } else {
+        // `owner/repo` GitHub shorthand.
+        "git"
+    }
+}
+
+fn is_remote(source: &str) -> bool {
+    (source.contains("://") && !source.starts_with("file://")) || source.starts_with("git@")
+}
+
+fn resolve_path(base_dir: &Path, source: &str) -> PathBuf {
+    let source = source.strip_prefix("file://").unwrap_or(source);
+    if let Some(rest) = source.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    base_dir.join(source)
+}
+
+#[cfg(test)]
+#[path = "tests/team_manifest.rs"]
+mod tests;
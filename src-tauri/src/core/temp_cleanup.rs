@@ -7,7 +7,6 @@ use tauri::Manager;
 const TEMP_PREFIX: &str = "skills-hub-git-";
 const TEMP_MARKER: &str = ".skills-hub-git-temp";
 
-#[allow(dead_code)]
 pub fn mark_temp_dir(dir: &Path) -> Result<()> {
     let marker = dir.join(TEMP_MARKER);
     if marker.exists() {
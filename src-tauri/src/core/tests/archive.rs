@@ -0,0 +1,221 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use zip::write::SimpleFileOptions;
+
+use super::{fetch_archive, fetch_archive_with_limits, ArchiveKind, Limits, DEFAULT_LIMITS};
+
+fn write_zip(path: &Path, files: &[(&str, &[u8])]) {
+    let mut zip = zip::ZipWriter::new(fs::File::create(path).unwrap());
+    for (name, data) in files {
+        zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(data).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+fn write_tar_gz(path: &Path, files: &[(&str, &[u8])]) {
+    let gz = flate2::write::GzEncoder::new(
+        fs::File::create(path).unwrap(),
+        flate2::Compression::default(),
+    );
+    let mut tar = tar::Builder::new(gz);
+    for (name, data) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        // Write the name bytes directly so tests can build paths `set_path` refuses.
+        header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+        header.set_cksum();
+        tar.append(&header, *data).unwrap();
+    }
+    tar.into_inner().unwrap().finish().unwrap();
+}
+
+#[test]
+fn extracts_zip_and_unwraps_single_top_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("skill.zip");
+    write_zip(
+        &archive,
+        &[
+            ("repo-main/SKILL.md", b"---\nname: x\n---\n"),
+            ("repo-main/scripts/run.sh", b"echo hi"),
+            ("__MACOSX/repo-main/._SKILL.md", b"junk"),
+        ],
+    );
+
+    let work = dir.path().join("work");
+    let fetched = fetch_archive(archive.to_str().unwrap(), &work, None).unwrap();
+    assert!(fetched.root.ends_with("repo-main"));
+    assert_eq!(
+        fs::read(fetched.root.join("scripts/run.sh")).unwrap(),
+        b"echo hi"
+    );
+    assert!(fetched.checksum.starts_with("sha256:"));
+    assert!(!work.join("content/__MACOSX").exists());
+
+    // Same bytes, same checksum.
+    let again = fetch_archive(archive.to_str().unwrap(), &dir.path().join("w2"), None).unwrap();
+    assert_eq!(again.checksum, fetched.checksum);
+}
+
+#[test]
+fn extracts_tar_gz_and_sniffs_format_without_extension() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("download");
+    write_tar_gz(
+        &archive,
+        &[
+            ("skills/a/SKILL.md", b"---\nname: a\n---\n"),
+            ("skills/b/SKILL.md", b"---\nname: b\n---\n"),
+        ],
+    );
+
+    let mut file = fs::File::open(&archive).unwrap();
+    assert_eq!(super::sniff_kind(&mut file).unwrap(), ArchiveKind::TarGz);
+
+    let fetched = fetch_archive(archive.to_str().unwrap(), &dir.path().join("work"), None).unwrap();
+    assert!(fetched.root.join("skills/a/SKILL.md").exists());
+    assert!(fetched.root.join("skills/b/SKILL.md").exists());
+}
+
+#[test]
+fn accepts_a_bare_skill_md() {
+    let dir = tempfile::tempdir().unwrap();
+    let skill = dir.path().join("SKILL.md");
+    fs::write(&skill, b"---\nname: solo\n---\nbody").unwrap();
+
+    let fetched = fetch_archive(
+        &format!("file://{}", skill.display()),
+        &dir.path().join("work"),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        fs::read(fetched.root.join("SKILL.md")).unwrap(),
+        b"---\nname: solo\n---\nbody"
+    );
+}
+
+#[test]
+fn rejects_entries_outside_the_extraction_dir() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let zip = dir.path().join("slip.zip");
+    write_zip(&zip, &[("ok/SKILL.md", b"x"), ("../evil.txt", b"x")]);
+    let err = fetch_archive(zip.to_str().unwrap(), &dir.path().join("w1"), None)
+        .err()
+        .expect("zip slip must fail");
+    assert!(format!("{:#}", err).contains("escapes"));
+
+    let tgz = dir.path().join("slip.tar.gz");
+    write_tar_gz(&tgz, &[("a/../../evil.txt", b"x")]);
+    let err = fetch_archive(tgz.to_str().unwrap(), &dir.path().join("w2"), None)
+        .err()
+        .expect("tar slip must fail");
+    assert!(format!("{:#}", err).contains("escapes"));
+
+    assert!(!dir.path().join("evil.txt").exists());
+}
+
+#[test]
+fn rejects_symlinks() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let zip_path = dir.path().join("link.zip");
+    let mut zip = zip::ZipWriter::new(fs::File::create(&zip_path).unwrap());
+    zip.add_symlink("skill/secret", "/etc/passwd", SimpleFileOptions::default())
+        .unwrap();
+    zip.finish().unwrap();
+    let err = fetch_archive(zip_path.to_str().unwrap(), &dir.path().join("w1"), None)
+        .err()
+        .expect("zip symlink must fail");
+    assert!(format!("{:#}", err).contains("symlink"));
+
+    let tar_path = dir.path().join("link.tar");
+    let mut tar = tar::Builder::new(fs::File::create(&tar_path).unwrap());
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Symlink);
+    header.set_size(0);
+    tar.append_link(&mut header, "skill/secret", "/etc/passwd")
+        .unwrap();
+    tar.finish().unwrap();
+    let err = fetch_archive(tar_path.to_str().unwrap(), &dir.path().join("w2"), None)
+        .err()
+        .expect("tar symlink must fail");
+    assert!(format!("{:#}", err).contains("link"));
+}
+
+#[test]
+fn enforces_size_and_entry_limits() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("bomb.zip");
+    // Highly compressible, so the archive is tiny but expands past the cap.
+    let big = vec![0u8; 64 * 1024];
+    write_zip(&archive, &[("a", &big), ("b", &big)]);
+
+    let limits = Limits {
+        extracted_bytes: 100 * 1024,
+        ..DEFAULT_LIMITS
+    };
+    let err = fetch_archive_with_limits(
+        archive.to_str().unwrap(),
+        &dir.path().join("w1"),
+        None,
+        &limits,
+    )
+    .err()
+    .expect("size cap must fail");
+    assert!(format!("{:#}", err).contains("expands"));
+
+    let limits = Limits {
+        entries: 1,
+        ..DEFAULT_LIMITS
+    };
+    assert!(fetch_archive_with_limits(
+        archive.to_str().unwrap(),
+        &dir.path().join("w2"),
+        None,
+        &limits
+    )
+    .is_err());
+
+    let limits = Limits {
+        archive_bytes: 16,
+        ..DEFAULT_LIMITS
+    };
+    let err = fetch_archive_with_limits(
+        archive.to_str().unwrap(),
+        &dir.path().join("w3"),
+        None,
+        &limits,
+    )
+    .err()
+    .expect("download cap must fail");
+    assert!(format!("{:#}", err).contains("larger than"));
+}
+
+#[test]
+fn downloads_with_bearer_token() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("skill.zip");
+    write_zip(&archive, &[("SKILL.md", b"---\nname: x\n---\n")]);
+
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/releases/skill.zip")
+        .match_query(mockito::Matcher::Any)
+        .match_header("authorization", "Bearer secret")
+        .with_body(fs::read(&archive).unwrap())
+        .create();
+
+    let url = format!("{}/releases/skill.zip?download=1", server.url());
+    let fetched = fetch_archive(&url, &dir.path().join("work"), Some("secret")).unwrap();
+    mock.assert();
+    assert!(fetched.root.join("SKILL.md").exists());
+
+    let missing = format!("{}/missing.zip", server.url());
+    assert!(fetch_archive(&missing, &dir.path().join("w2"), None).is_err());
+}
@@ -1,14 +1,9 @@
 use std::path::PathBuf;
 
-use crate::core::central_repo::{ensure_central_repo, resolve_central_repo_path};
-use crate::core::skill_store::SkillStore;
-
-fn make_store() -> (tempfile::TempDir, SkillStore) {
-    let dir = tempfile::tempdir().expect("tempdir");
-    let store = SkillStore::new(dir.path().join("test.db"));
-    store.ensure_schema().expect("ensure_schema");
-    (dir, store)
-}
+use crate::core::central_repo::{
+    ensure_central_repo, resolve_central_repo_path, skill_dir_in, validate_skill_name,
+};
+use crate::core::test_fixtures::make_store;
 
 #[test]
 fn resolve_uses_setting_when_present() {
@@ -31,3 +26,16 @@ fn ensure_central_repo_creates_dir() {
     ensure_central_repo(&p).unwrap();
     assert!(p.exists());
 }
+
+#[test]
+fn skill_names_must_stay_inside_central_repo() {
+    let central = PathBuf::from("central");
+    assert_eq!(
+        skill_dir_in(&central, "my-skill").unwrap(),
+        central.join("my-skill")
+    );
+    for name in ["", " pad", ".", "..", "a/b", "a\\b", "/abs", "tab\tname"] {
+        assert!(validate_skill_name(name).is_err(), "{:?}", name);
+    }
+    assert!(validate_skill_name(".tmp-skill").is_ok());
+}
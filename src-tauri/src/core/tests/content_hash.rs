@@ -1,6 +1,6 @@
 use std::fs;
 
-use crate::core::content_hash::hash_dir;
+use crate::core::content_hash::{dir_stamp, hash_dir};
 
 #[test]
 fn hash_changes_with_content_and_ignores_git_dir() {
@@ -22,3 +22,16 @@ fn hash_changes_with_content_and_ignores_git_dir() {
     let h3 = hash_dir(root).unwrap();
     assert_ne!(h2, h3);
 }
+
+#[test]
+fn stamp_is_stable_until_a_file_changes() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let root = dir.path();
+    fs::write(root.join("SKILL.md"), b"hello").unwrap();
+
+    let s1 = dir_stamp(root).unwrap();
+    assert_eq!(s1, dir_stamp(root).unwrap());
+
+    fs::write(root.join("SKILL.md"), b"hello, world").unwrap();
+    assert_ne!(s1, dir_stamp(root).unwrap());
+}
@@ -0,0 +1,78 @@
+use std::fs;
+
+use super::{url_host, CredentialBackend, CredentialStore, DEFAULT_TOKEN_USERNAME};
+use crate::core::test_fixtures::make_store;
+
+#[test]
+fn plain_file_round_trip() {
+    let (dir, store) = make_store();
+    let creds = CredentialStore::new(dir.path(), false);
+
+    let info = creds
+        .set(
+            &store,
+            "https://GitLab.example.com/",
+            Some("oauth2"),
+            "glpat-secret",
+        )
+        .unwrap();
+    assert_eq!(info.host, "gitlab.example.com");
+    assert_eq!(info.backend, CredentialBackend::PlainFile);
+    creds.set(&store, "github.com", None, "ghp_secret").unwrap();
+
+    // Tokens sit in a plain file only the user can read, never in the settings index.
+    let file = dir.path().join("credentials.json");
+    assert!(fs::read_to_string(&file).unwrap().contains("ghp_secret"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    let index = store.get_setting("credentials").unwrap().unwrap();
+    assert!(!index.contains("secret"));
+
+    let t = creds.token_for_host(&store, "gitlab.example.com").unwrap();
+    assert_eq!(
+        (t.username.as_str(), t.token.as_str()),
+        ("oauth2", "glpat-secret")
+    );
+    let t = creds.token_for_host(&store, "GITHUB.COM").unwrap();
+    assert_eq!(t.username, DEFAULT_TOKEN_USERNAME);
+    assert_eq!(t.token, "ghp_secret");
+    assert!(creds.token_for_host(&store, "bitbucket.org").is_none());
+
+    // A fresh handle (e.g. after restart) reads the same file.
+    let reopened = CredentialStore::new(dir.path(), false);
+    creds.delete(&store, "gitlab.example.com").unwrap();
+    assert!(reopened
+        .token_for_host(&store, "gitlab.example.com")
+        .is_none());
+    assert_eq!(reopened.list(&store).len(), 1);
+    assert_eq!(
+        reopened.token_for_host(&store, "github.com").unwrap().token,
+        "ghp_secret"
+    );
+}
+
+#[test]
+fn rejects_empty_tokens_and_hosts() {
+    let (dir, store) = make_store();
+    let creds = CredentialStore::new(dir.path(), false);
+    assert!(creds.set(&store, "github.com", None, "  ").is_err());
+    assert!(creds.set(&store, " ", None, "t").is_err());
+    assert!(creds.list(&store).is_empty());
+}
+
+#[test]
+fn extracts_hosts_from_https_urls_only() {
+    assert_eq!(
+        url_host("https://user@Git.Example.com:8443/team/repo.git").as_deref(),
+        Some("git.example.com:8443")
+    );
+    // A token must never travel over plain HTTP.
+    assert!(url_host("http://git.example.com/team/repo.git").is_none());
+    assert!(url_host("git@github.com:owner/repo.git").is_none());
+    assert!(url_host("ssh://git@github.com/owner/repo.git").is_none());
+    assert!(url_host("/local/repo").is_none());
+}
@@ -0,0 +1,119 @@
+use super::{into_loaded, load_custom_tools, parse_custom_tools, registry_from};
+use crate::core::sync_engine::SyncMode;
+use crate::core::tool_adapters::ToolId;
+
+#[test]
+fn parses_custom_tools_and_builds_adapters() {
+    let tools = parse_custom_tools(
+        r#"
+[[tool]]
+key = "my_agent"
+name = "My Agent"
+skills_dir = ".my-agent/skills"
+detect_dir = ".my-agent"
+sync_mode = "copy"
+
+[[tool]]
+key = "other"
+name = "Other"
+skills_dir = ".other/skills"
+project_skills_dir = ".other/project-skills"
+detect_dir = ".other"
+"#,
+    )
+    .unwrap();
+    assert_eq!(tools.len(), 2);
+
+    let loaded = into_loaded(tools[0].clone());
+    assert_eq!(loaded.adapter.id, ToolId::Custom("my_agent".into()));
+    assert_eq!(loaded.adapter.id.as_key(), "my_agent");
+    assert_eq!(loaded.adapter.display_name, "My Agent");
+    // Project dir falls back to the global one.
+    assert_eq!(
+        loaded.adapter.relative_project_skills_dir,
+        ".my-agent/skills"
+    );
+    assert!(matches!(loaded.sync_mode, SyncMode::Copy));
+
+    let other = into_loaded(tools[1].clone());
+    assert_eq!(
+        other.adapter.relative_project_skills_dir,
+        ".other/project-skills"
+    );
+    assert!(matches!(other.sync_mode, SyncMode::Auto));
+}
+
+#[test]
+fn rejects_invalid_custom_tools() {
+    let tool = |fields: &str| format!("[[tool]]\n{}\n", fields);
+    let base = r#"name = "X"
+skills_dir = ".x/skills"
+detect_dir = ".x""#;
+    let cases = [
+        (
+            tool(&format!("key = \"Bad Key\"\n{}", base)),
+            "invalid tool key",
+        ),
+        (tool(&format!("key = \"cursor\"\n{}", base)), "built in"),
+        (
+            format!(
+                "{}{}",
+                tool(&format!("key = \"x\"\n{}", base)),
+                tool(&format!("key = \"x\"\n{}", base))
+            ),
+            "duplicate",
+        ),
+        (
+            tool("key = \"x\"\nname = \"X\"\nskills_dir = \"../skills\"\ndetect_dir = \".x\""),
+            "relative path",
+        ),
+        (
+            tool("key = \"x\"\nname = \"X\"\nskills_dir = \"/abs\"\ndetect_dir = \".x\""),
+            "relative path",
+        ),
+        (
+            tool(&format!("key = \"x\"\n{}\nsync_mode = \"mirror\"", base)),
+            "sync_mode",
+        ),
+        (
+            tool(&format!("key = \"x\"\n{}\nextra = 1", base)),
+            "unknown field",
+        ),
+    ];
+    for (text, expected) in cases {
+        let err = parse_custom_tools(&text).expect_err(&text);
+        assert!(
+            format!("{:#}", err).contains(expected),
+            "{:#} should mention {}",
+            err,
+            expected
+        );
+    }
+}
+
+#[test]
+fn missing_config_file_means_no_custom_tools() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(load_custom_tools(&dir.path().join("tools.toml"))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn invalid_file_keeps_the_error_for_the_ui() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("tools.toml");
+    std::fs::write(&path, "[[tool]]\nkey = \"Bad Key\"\n").unwrap();
+    let registry = registry_from(&path);
+    assert!(registry.tools.is_empty());
+    assert!(registry.error.unwrap().contains("tools.toml"));
+
+    std::fs::write(
+        &path,
+        "[[tool]]\nkey = \"ok\"\nname = \"Ok\"\nskills_dir = \".ok/skills\"\ndetect_dir = \".ok\"\n",
+    )
+    .unwrap();
+    let registry = registry_from(&path);
+    assert_eq!(registry.tools.len(), 1);
+    assert!(registry.error.is_none());
+}
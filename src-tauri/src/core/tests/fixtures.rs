@@ -0,0 +1,30 @@
+//! Store and record factories shared by the unit tests.
+
+use crate::core::skill_store::{SkillRecord, SkillStore};
+
+pub fn make_store() -> (tempfile::TempDir, SkillStore) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let db = dir.path().join("test.db");
+    let store = SkillStore::new(db);
+    store.ensure_schema().expect("ensure_schema");
+    (dir, store)
+}
+
+pub fn make_skill(id: &str, name: &str, central_path: &str, updated_at: i64) -> SkillRecord {
+    SkillRecord {
+        id: id.to_string(),
+        name: name.to_string(),
+        source_type: "local".to_string(),
+        source_ref: Some("/tmp/source".to_string()),
+        source_revision: None,
+        source_subpath: None,
+        source_pin: None,
+        central_path: central_path.to_string(),
+        content_hash: None,
+        created_at: 1,
+        updated_at,
+        last_sync_at: None,
+        last_seen_at: 1,
+        status: "ok".to_string(),
+    }
+}
@@ -0,0 +1,194 @@
+use super::{
+    default_forge_hosts, forge_registry, get_forge_hosts, parse_forge_url, set_forge_hosts,
+    ForgeHost, ForgeKind, ForgeLocation,
+};
+use crate::core::test_fixtures::make_store;
+
+fn parse(url: &str) -> ForgeLocation {
+    parse_forge_url(url, &default_forge_hosts()).expect("known forge url")
+}
+
+fn loc(
+    clone_url: &str,
+    branch: Option<&str>,
+    pin: Option<&str>,
+    subpath: Option<&str>,
+) -> ForgeLocation {
+    ForgeLocation {
+        clone_url: clone_url.to_string(),
+        branch: branch.map(str::to_string),
+        pin: pin.map(str::to_string),
+        subpath: subpath.map(str::to_string),
+    }
+}
+
+#[test]
+fn parses_github_shapes() {
+    assert_eq!(
+        parse("https://github.com/owner/repo.git"),
+        loc("https://github.com/owner/repo.git", None, None, None)
+    );
+    assert_eq!(
+        parse("http://github.com/owner/repo/blob/dev/skills/x/SKILL.md"),
+        loc(
+            "https://github.com/owner/repo.git",
+            Some("dev"),
+            None,
+            Some("skills/x/SKILL.md")
+        )
+    );
+}
+
+#[test]
+fn parses_gitlab_shapes() {
+    assert_eq!(
+        parse("https://gitlab.com/group/repo"),
+        loc("https://gitlab.com/group/repo.git", None, None, None)
+    );
+    assert_eq!(
+        parse("https://gitlab.com/group/sub/repo/-/tree/main/skills/x?ref_type=heads"),
+        loc(
+            "https://gitlab.com/group/sub/repo.git",
+            Some("main"),
+            None,
+            Some("skills/x")
+        )
+    );
+    assert_eq!(
+        parse("https://gitlab.com/group/repo/-/blob/abc1234/skills/x/SKILL.md"),
+        loc(
+            "https://gitlab.com/group/repo.git",
+            None,
+            Some("abc1234"),
+            Some("skills/x/SKILL.md")
+        )
+    );
+    // A bare group is not a repository.
+    assert!(parse_forge_url("https://gitlab.com/group", &default_forge_hosts()).is_none());
+}
+
+#[test]
+fn parses_bitbucket_shapes() {
+    assert_eq!(
+        parse("https://bitbucket.org/ws/repo"),
+        loc("https://bitbucket.org/ws/repo.git", None, None, None)
+    );
+    assert_eq!(
+        parse("https://bitbucket.org/ws/repo/src/main/skills/x/?at=main"),
+        loc(
+            "https://bitbucket.org/ws/repo.git",
+            Some("main"),
+            None,
+            Some("skills/x")
+        )
+    );
+}
+
+#[test]
+fn parses_gitea_shapes() {
+    assert_eq!(
+        parse("https://codeberg.org/owner/repo/src/branch/main/skills/x"),
+        loc(
+            "https://codeberg.org/owner/repo.git",
+            Some("main"),
+            None,
+            Some("skills/x")
+        )
+    );
+    assert_eq!(
+        parse("https://gitea.com/owner/repo/src/tag/v1.0/skills/x"),
+        loc(
+            "https://gitea.com/owner/repo.git",
+            None,
+            Some("v1.0"),
+            Some("skills/x")
+        )
+    );
+    assert_eq!(
+        parse("https://codeberg.org/owner/repo/src/commit/0123abcd"),
+        loc(
+            "https://codeberg.org/owner/repo.git",
+            None,
+            Some("0123abcd"),
+            None
+        )
+    );
+}
+
+#[test]
+fn parses_self_hosted_forges() {
+    let forges = vec![
+        ForgeHost {
+            host: "git.example.com:8443".to_string(),
+            kind: ForgeKind::Gitea,
+        },
+        ForgeHost {
+            host: "example.com/gitlab".to_string(),
+            kind: ForgeKind::Gitlab,
+        },
+    ];
+
+    assert_eq!(
+        parse_forge_url(
+            "http://git.example.com:8443/team/repo/src/branch/main/skills/x",
+            &forges
+        ),
+        Some(loc(
+            "http://git.example.com:8443/team/repo.git",
+            Some("main"),
+            None,
+            Some("skills/x")
+        ))
+    );
+    assert_eq!(
+        parse_forge_url("https://Example.com/gitlab/team/repo/-/tree/dev", &forges),
+        Some(loc(
+            "https://Example.com/gitlab/team/repo.git",
+            Some("dev"),
+            None,
+            None
+        ))
+    );
+    assert!(parse_forge_url("https://example.com/gitlabx/team/repo", &forges).is_none());
+    assert!(parse_forge_url("https://github.com/owner/repo", &forges).is_none());
+    assert!(parse_forge_url("ssh://git.example.com:8443/team/repo", &forges).is_none());
+}
+
+#[test]
+fn forge_hosts_round_trip_through_settings() {
+    let (_dir, store) = make_store();
+    assert!(get_forge_hosts(&store).is_empty());
+
+    let saved = set_forge_hosts(
+        &store,
+        vec![
+            ForgeHost {
+                host: " https://Git.Example.com/ ".to_string(),
+                kind: ForgeKind::Gitlab,
+            },
+            ForgeHost {
+                host: "git.example.com".to_string(),
+                kind: ForgeKind::Gitea,
+            },
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        saved,
+        vec![ForgeHost {
+            host: "git.example.com".to_string(),
+            kind: ForgeKind::Gitlab,
+        }]
+    );
+    assert_eq!(get_forge_hosts(&store), saved);
+    assert_eq!(forge_registry(&store)[0], saved[0]);
+
+    assert!(set_forge_hosts(
+        &store,
+        vec![ForgeHost {
+            host: "  ".to_string(),
+            kind: ForgeKind::Gitea,
+        }]
+    )
+    .is_err());
+}
@@ -0,0 +1,77 @@
+use std::fs;
+use std::path::PathBuf;
+
+use super::{get_ssh_keys, git_auth_for, set_ssh_key};
+use crate::core::credentials::CredentialStore;
+use crate::core::test_fixtures::make_store;
+
+#[test]
+fn picks_most_specific_ssh_key_binding() {
+    let (dir, store) = make_store();
+    let host_key = dir.path().join("id_host");
+    let repo_key = dir.path().join("id_repo");
+    fs::write(&host_key, "k").unwrap();
+    fs::write(&repo_key, "k").unwrap();
+    let creds = CredentialStore::new(dir.path(), false);
+
+    set_ssh_key(&store, "git@git.example.com:", host_key.to_str()).unwrap();
+    set_ssh_key(
+        &store,
+        "git@git.example.com:team/private",
+        repo_key.to_str(),
+    )
+    .unwrap();
+    assert_eq!(get_ssh_keys(&store).len(), 2);
+
+    let auth = git_auth_for(&store, &creds, "git@git.example.com:team/private.git");
+    assert_eq!(auth.ssh_key, Some(repo_key.clone()));
+    let auth = git_auth_for(&store, &creds, "git@git.example.com:team/other.git");
+    assert_eq!(auth.ssh_key, Some(host_key));
+    assert!(
+        git_auth_for(&store, &creds, "https://github.com/owner/repo.git")
+            .ssh_key
+            .is_none()
+    );
+
+    // Removing a binding falls back to the broader one; missing keys are rejected.
+    set_ssh_key(&store, "git@git.example.com:team/private", None).unwrap();
+    assert_eq!(get_ssh_keys(&store).len(), 1);
+    assert!(set_ssh_key(
+        &store,
+        "git@other.example.com:",
+        Some(PathBuf::from("/no/such/key").to_str().unwrap())
+    )
+    .is_err());
+}
+
+#[test]
+fn https_remotes_get_the_host_token() {
+    let (dir, store) = make_store();
+    let creds = CredentialStore::new(dir.path(), false);
+    creds
+        .set(&store, "git.example.com", Some("ci"), "secret")
+        .unwrap();
+
+    let auth = git_auth_for(&store, &creds, "https://git.example.com/team/repo.git");
+    let https = auth.https.expect("token for host");
+    assert_eq!(
+        (https.username.as_str(), https.token.as_str()),
+        ("ci", "secret")
+    );
+    assert!(!format!("{:?}", https).contains("secret"));
+
+    assert!(git_auth_for(&store, &creds, "https://github.com/o/r.git")
+        .https
+        .is_none());
+    assert!(
+        git_auth_for(&store, &creds, "git@git.example.com:team/repo.git")
+            .https
+            .is_none()
+    );
+    // Same host over plain HTTP: the token is not handed out.
+    assert!(
+        git_auth_for(&store, &creds, "http://git.example.com/team/repo.git")
+            .https
+            .is_none()
+    );
+}
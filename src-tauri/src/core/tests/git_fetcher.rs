@@ -1,6 +1,8 @@
 use std::fs;
 
-use crate::core::git_fetcher::clone_or_pull;
+use crate::core::git_fetcher::{
+    checkout_revision, clone_or_pull, GitAuth, GitAuthError, GitRemoteMissingError,
+};
 
 fn commit_file(repo: &git2::Repository, path: &str, content: &[u8], msg: &str) -> git2::Oid {
     let workdir = repo.workdir().expect("workdir");
@@ -35,10 +37,224 @@ fn clone_then_pull_updates_head() {
     let dest_dir = tempfile::tempdir().unwrap();
     let dest = dest_dir.path().join("clone");
 
-    let h1 = clone_or_pull(origin_dir.path().to_string_lossy().as_ref(), &dest, None).unwrap();
+    let h1 = clone_or_pull(
+        origin_dir.path().to_string_lossy().as_ref(),
+        &dest,
+        None,
+        &GitAuth::default(),
+    )
+    .unwrap();
     assert_eq!(h1, c2.to_string(), "首次 clone 应指向最新提交");
 
     let c3 = commit_file(&origin, "b.txt", b"v3", "c3");
-    let h2 = clone_or_pull(origin_dir.path().to_string_lossy().as_ref(), &dest, None).unwrap();
+    let h2 = clone_or_pull(
+        origin_dir.path().to_string_lossy().as_ref(),
+        &dest,
+        None,
+        &GitAuth::default(),
+    )
+    .unwrap();
     assert_eq!(h2, c3.to_string(), "再次调用应更新到最新提交");
 }
+
+#[test]
+fn missing_remote_or_branch_is_typed() {
+    let origin_dir = tempfile::tempdir().unwrap();
+    let origin = git2::Repository::init(origin_dir.path()).unwrap();
+    commit_file(&origin, "a.txt", b"v1", "c1");
+    let dest_dir = tempfile::tempdir().unwrap();
+    let url = origin_dir.path().to_string_lossy().to_string();
+
+    let err = clone_or_pull(
+        &format!("{}-gone", url),
+        &dest_dir.path().join("a"),
+        None,
+        &GitAuth::default(),
+    )
+    .unwrap_err();
+    assert!(err.is::<GitRemoteMissingError>(), "{:#}", err);
+
+    let err = clone_or_pull(
+        &url,
+        &dest_dir.path().join("b"),
+        Some("no-such-branch"),
+        &GitAuth::default(),
+    )
+    .unwrap_err();
+    assert!(err.is::<GitRemoteMissingError>(), "{:#}", err);
+
+    // A bad pin in a repo that exists is not a missing remote.
+    let err = checkout_revision(
+        &url,
+        &dest_dir.path().join("c"),
+        "no-such-tag",
+        &GitAuth::default(),
+    )
+    .unwrap_err();
+    assert!(!err.is::<GitRemoteMissingError>(), "{:#}", err);
+}
+
+#[test]
+fn checkout_revision_pins_tags_and_commits() {
+    let origin_dir = tempfile::tempdir().unwrap();
+    let origin = git2::Repository::init(origin_dir.path()).unwrap();
+    let c1 = commit_file(&origin, "a.txt", b"v1", "c1");
+    origin
+        .tag_lightweight("v1.0.0", &origin.find_object(c1, None).unwrap(), false)
+        .unwrap();
+    let c2 = commit_file(&origin, "a.txt", b"v2", "c2");
+    let url = origin_dir.path().to_string_lossy().to_string();
+
+    let dest_dir = tempfile::tempdir().unwrap();
+    let dest = dest_dir.path().join("pinned");
+
+    let head = checkout_revision(&url, &dest, "v1.0.0", &GitAuth::default()).unwrap();
+    assert_eq!(head, c1.to_string());
+    assert_eq!(fs::read(dest.join("a.txt")).unwrap(), b"v1");
+
+    let head = checkout_revision(&url, &dest, &c2.to_string(), &GitAuth::default()).unwrap();
+    assert_eq!(head, c2.to_string());
+    assert_eq!(fs::read(dest.join("a.txt")).unwrap(), b"v2");
+
+    // Short SHAs cannot be fetched directly and fall back to a full fetch.
+    let short = &c1.to_string()[..8];
+    let head = checkout_revision(&url, &dest, short, &GitAuth::default()).unwrap();
+    assert_eq!(head, c1.to_string());
+
+    assert!(checkout_revision(&url, &dest, "no-such-tag", &GitAuth::default()).is_err());
+}
+
+/// Fake `ssh` that records its arguments and runs the remote command locally, or
+/// fails with `reject` on stderr like a real server or client would.
+#[cfg(unix)]
+fn write_fake_ssh(dir: &std::path::Path, name: &str, reject: Option<&str>) -> String {
+    use std::os::unix::fs::PermissionsExt;
+    let script = dir.join(name);
+    let log = dir.join("ssh-args.log");
+    let body = match reject {
+        None => format!(
+            "#!/bin/sh\necho \"$@\" >> '{}'\nfor last; do :; done\nexec sh -c \"$last\"\n",
+            log.display()
+        ),
+        Some(message) => format!("#!/bin/sh\necho '{}' >&2\nexit 255\n", message),
+    };
+    fs::write(&script, body).unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    script.to_string_lossy().to_string()
+}
+
+#[cfg(unix)]
+#[test]
+fn ssh_remotes_use_the_configured_key() {
+    let origin_dir = tempfile::tempdir().unwrap();
+    let origin = git2::Repository::init(origin_dir.path()).unwrap();
+    let c1 = commit_file(&origin, "a.txt", b"v1", "c1");
+    let url = format!("ssh://git@example.com{}", origin_dir.path().display());
+
+    let tools = tempfile::tempdir().unwrap();
+    let key = tools.path().join("id_test");
+    fs::write(&key, "not a real key").unwrap();
+    let auth = GitAuth {
+        ssh_key: Some(key.clone()),
+        https: None,
+    };
+    let fetch = |ssh: &str, dest: &str| {
+        let dest = tools.path().join("dest").join(dest);
+        super::clone_or_pull_via_git_cli(&url, &dest, None, &auth, ssh)
+    };
+
+    let ok = write_fake_ssh(tools.path(), "ssh-ok", None);
+    assert_eq!(fetch(&ok, "clone").unwrap(), c1.to_string());
+    let args = fs::read_to_string(tools.path().join("ssh-args.log")).unwrap();
+    assert!(args.contains(&format!("-i {}", key.display())), "{}", args);
+    assert!(args.contains("BatchMode=yes"), "{}", args);
+    // Host keys come from the user's known_hosts; nothing is accepted on their behalf.
+    assert!(!args.contains("StrictHostKeyChecking"), "{}", args);
+
+    let deny = write_fake_ssh(
+        tools.path(),
+        "ssh-deny",
+        Some("git@example.com: Permission denied (publickey)."),
+    );
+    let err = fetch(&deny, "denied").unwrap_err();
+    assert!(err.is::<GitAuthError>(), "{:#}", err);
+    assert!(
+        err.to_string().contains(&key.display().to_string()),
+        "{}",
+        err
+    );
+
+    let unknown = write_fake_ssh(
+        tools.path(),
+        "ssh-unknown-host",
+        Some("Host key verification failed."),
+    );
+    let err = fetch(&unknown, "unknown").unwrap_err();
+    assert!(err.is::<GitAuthError>(), "{:#}", err);
+    assert!(err.to_string().contains("known_hosts"), "{}", err);
+}
+
+#[test]
+fn https_token_is_served_by_credential_helper() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    use crate::core::git_fetcher::HttpsCredential;
+
+    let auth = GitAuth {
+        ssh_key: None,
+        https: Some(HttpsCredential {
+            host: "git.example.com".to_string(),
+            username: "x-access-token".to_string(),
+            token: "t0k".to_string(),
+        }),
+    };
+    let fill = |host: &str| {
+        let mut cmd = super::git_cmd(&auth, super::SSH_BIN);
+        cmd.args(["credential", "fill"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null());
+        assert!(cmd.get_args().all(|a| !a.to_string_lossy().contains("t0k")));
+
+        let mut child = cmd.spawn().unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(format!("protocol=https\nhost={}\n\n", host).as_bytes())
+            .unwrap();
+        let out = child.wait_with_output().unwrap();
+        String::from_utf8_lossy(&out.stdout).to_string()
+    };
+
+    let stdout = fill("git.example.com");
+    assert!(stdout.contains("username=x-access-token"), "{}", stdout);
+    assert!(stdout.contains("password=t0k"), "{}", stdout);
+
+    // A redirect, submodule or other remote on another host gets nothing.
+    let stdout = fill("other.example.com");
+    assert!(!stdout.contains("t0k"), "{}", stdout);
+    assert!(!stdout.contains("x-access-token"), "{}", stdout);
+}
+
+#[test]
+fn libgit2_only_offers_the_token_to_its_host() {
+    use crate::core::git_fetcher::HttpsCredential;
+
+    let auth = GitAuth {
+        ssh_key: None,
+        https: Some(HttpsCredential {
+            host: "git.example.com".to_string(),
+            username: "x-access-token".to_string(),
+            token: "t0k".to_string(),
+        }),
+    };
+    let token_for = |url: &str| super::https_credential_for(&auth, url).map(|c| c.token.as_str());
+    assert_eq!(
+        token_for("https://git.example.com/team/repo.git"),
+        Some("t0k")
+    );
+    assert_eq!(token_for("https://other.example.com/team/repo.git"), None);
+    assert_eq!(token_for("http://git.example.com/team/repo.git"), None);
+}
@@ -33,7 +33,7 @@ fn limit_is_clamped() {
         .with_body(json_one_repo())
         .create();
 
-    let out = search_github_repos_inner(&server.url(), "hello", 0).unwrap();
+    let out = search_github_repos_inner(&server.url(), "hello", 0, None).unwrap();
     assert_eq!(out.len(), 1);
 
     let _m2 = server
@@ -47,7 +47,7 @@ fn limit_is_clamped() {
         .with_body(json_one_repo())
         .create();
 
-    let _ = search_github_repos_inner(&server.url(), "hello", 999).unwrap();
+    let _ = search_github_repos_inner(&server.url(), "hello", 999, None).unwrap();
 }
 
 #[test]
@@ -64,7 +64,7 @@ fn maps_fields() {
         .with_body(json_one_repo())
         .create();
 
-    let out = search_github_repos_inner(&server.url(), "x", 2).unwrap();
+    let out = search_github_repos_inner(&server.url(), "x", 2, None).unwrap();
     assert_eq!(out[0].full_name, "o/r");
     assert_eq!(out[0].stars, 123);
 }
@@ -78,7 +78,25 @@ fn http_error_has_context() {
         .with_body("oops")
         .create();
 
-    let err = search_github_repos_inner(&server.url(), "x", 2).unwrap_err();
+    let err = search_github_repos_inner(&server.url(), "x", 2, None).unwrap_err();
     let msg = format!("{:#}", err);
     assert!(msg.contains("GitHub search returned error"), "{msg}");
 }
+
+#[test]
+fn sends_token_as_bearer_auth() {
+    let mut server = mockito::Server::new();
+    let _m = server
+        .mock("GET", "/search/repositories")
+        .match_query(Matcher::Any)
+        .match_header("authorization", "Bearer t0k")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(json_one_repo())
+        .create();
+
+    let out = search_github_repos_inner(&server.url(), "x", 2, Some("t0k")).unwrap();
+    assert_eq!(out.len(), 1);
+    // Without the token the mock does not match and mockito answers 501.
+    assert!(search_github_repos_inner(&server.url(), "x", 2, None).is_err());
+}
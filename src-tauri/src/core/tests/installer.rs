@@ -1,14 +1,10 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::core::skill_store::{SkillStore, SkillTargetRecord};
-
-fn make_store() -> (tempfile::TempDir, SkillStore) {
-    let dir = tempfile::tempdir().expect("tempdir");
-    let store = SkillStore::new(dir.path().join("test.db"));
-    store.ensure_schema().expect("ensure_schema");
-    (dir, store)
-}
+use crate::core::forge_url::{default_forge_hosts, ForgeHost, ForgeKind};
+use crate::core::skill_diff::FileChangeKind;
+use crate::core::skill_store::{SkillRecord, SkillStore, SkillTargetRecord};
+use crate::core::test_fixtures::make_store;
 
 fn set_central_path(store: &SkillStore, central: &Path) {
     store
@@ -57,35 +53,148 @@ fn commit_all(repo: &git2::Repository, msg: &str) -> git2::Oid {
     }
 }
 
+fn parse_github_url(input: &str) -> super::ParsedGitSource {
+    super::parse_github_url(input, &default_forge_hosts())
+}
+
 #[test]
 fn parses_github_urls() {
-    let p = super::parse_github_url("https://github.com/owner/repo");
+    let p = parse_github_url("https://github.com/owner/repo");
     assert_eq!(p.clone_url, "https://github.com/owner/repo.git");
     assert!(p.branch.is_none());
     assert!(p.subpath.is_none());
 
-    let p = super::parse_github_url("anthropics/skills");
+    let p = parse_github_url("anthropics/skills");
     assert_eq!(p.clone_url, "https://github.com/anthropics/skills.git");
     assert!(p.branch.is_none());
     assert!(p.subpath.is_none());
 
-    let p = super::parse_github_url("github.com/owner/repo");
+    let p = parse_github_url("github.com/owner/repo");
     assert_eq!(p.clone_url, "https://github.com/owner/repo.git");
     assert!(p.branch.is_none());
     assert!(p.subpath.is_none());
 
-    let p = super::parse_github_url("https://github.com/owner/repo/tree/main/skills/x");
+    let p = parse_github_url("https://github.com/owner/repo/tree/main/skills/x");
     assert_eq!(p.clone_url, "https://github.com/owner/repo.git");
     assert_eq!(p.branch.as_deref(), Some("main"));
     assert_eq!(p.subpath.as_deref(), Some("skills/x"));
 
-    let p = super::parse_github_url("owner/repo/tree/main/skills/x");
+    let p = parse_github_url("owner/repo/tree/main/skills/x");
     assert_eq!(p.clone_url, "https://github.com/owner/repo.git");
     assert_eq!(p.branch.as_deref(), Some("main"));
     assert_eq!(p.subpath.as_deref(), Some("skills/x"));
 
-    let p = super::parse_github_url("/local/path/to/repo");
+    let p = parse_github_url("/local/path/to/repo");
     assert_eq!(p.clone_url, "/local/path/to/repo");
+    assert!(p.pin.is_none());
+}
+
+#[test]
+fn parses_other_forge_urls() {
+    let p = parse_github_url("https://gitlab.com/group/sub/repo/-/tree/main/skills/x");
+    assert_eq!(p.clone_url, "https://gitlab.com/group/sub/repo.git");
+    assert_eq!(p.branch.as_deref(), Some("main"));
+    assert_eq!(p.subpath.as_deref(), Some("skills/x"));
+
+    // Scheme-less forge hosts are not mistaken for GitHub shorthand.
+    let p = parse_github_url("codeberg.org/owner/repo/src/tag/v1/skills/x");
+    assert_eq!(p.clone_url, "https://codeberg.org/owner/repo.git");
+    assert_eq!(p.pin.as_deref(), Some("v1"));
+    assert_eq!(p.subpath.as_deref(), Some("skills/x"));
+
+    let p = parse_github_url("https://bitbucket.org/ws/repo/src/main/skills/x@v2");
+    assert_eq!(p.clone_url, "https://bitbucket.org/ws/repo.git");
+    assert!(p.branch.is_none());
+    assert_eq!(p.pin.as_deref(), Some("v2"));
+
+    // Unknown hosts stay raw clone URLs until configured.
+    let url = "https://git.example.com/team/repo/-/tree/main/skills/x";
+    assert_eq!(parse_github_url(url).clone_url, url);
+    let mut forges = vec![ForgeHost {
+        host: "git.example.com".to_string(),
+        kind: ForgeKind::Gitlab,
+    }];
+    forges.extend(default_forge_hosts());
+    let p = super::parse_github_url(url, &forges);
+    assert_eq!(p.clone_url, "https://git.example.com/team/repo.git");
+    assert_eq!(p.subpath.as_deref(), Some("skills/x"));
+}
+
+#[test]
+fn parses_ssh_remotes_with_ref_suffix() {
+    let p = parse_github_url("git@git.example.com:team/skills.git");
+    assert_eq!(p.clone_url, "git@git.example.com:team/skills.git");
+    assert!(p.branch.is_none() && p.subpath.is_none() && p.pin.is_none());
+
+    let p = parse_github_url("git@git.example.com:team/skills.git#main:skills/x");
+    assert_eq!(p.clone_url, "git@git.example.com:team/skills.git");
+    assert_eq!(p.branch.as_deref(), Some("main"));
+    assert_eq!(p.subpath.as_deref(), Some("skills/x"));
+
+    let p = parse_github_url("ssh://git@host:2222/team/skills.git#:skills/x/");
+    assert_eq!(p.clone_url, "ssh://git@host:2222/team/skills.git");
+    assert!(p.branch.is_none());
+    assert_eq!(p.subpath.as_deref(), Some("skills/x"));
+
+    let p = parse_github_url("git@host:team/skills.git#abc1234");
+    assert_eq!(p.pin.as_deref(), Some("abc1234"));
+    assert!(p.branch.is_none());
+
+    let p = parse_github_url("git@host:team/skills.git@v1#dev:skills/x");
+    assert_eq!(p.clone_url, "git@host:team/skills.git");
+    assert_eq!(p.pin.as_deref(), Some("v1"));
+    assert!(p.branch.is_none());
+
+    // Web links keep their `#` anchors out of the ref.
+    let p = parse_github_url("https://github.com/owner/repo/blob/main/skills/x/SKILL.md#usage");
+    assert_eq!(p.branch.as_deref(), Some("main"));
+    assert_eq!(p.subpath.as_deref(), Some("skills/x/SKILL.md"));
+
+    assert_eq!(
+        super::derive_name_from_repo_url("git@host:skills.git"),
+        "skills"
+    );
+    assert_eq!(
+        super::derive_name_from_repo_url("ssh://git@host:2222/team/skills.git"),
+        "skills"
+    );
+}
+
+#[test]
+fn parses_pinned_git_refs() {
+    let p = parse_github_url("owner/repo@v1.2.0");
+    assert_eq!(p.clone_url, "https://github.com/owner/repo.git");
+    assert_eq!(p.pin.as_deref(), Some("v1.2.0"));
+    assert!(p.branch.is_none());
+
+    let sha = "0123456789abcdef0123456789abcdef01234567";
+    let p = parse_github_url(&format!(
+        "https://github.com/owner/repo/tree/{}/skills/x",
+        sha
+    ));
+    assert_eq!(p.pin.as_deref(), Some(sha));
+    assert!(p.branch.is_none());
+    assert_eq!(p.subpath.as_deref(), Some("skills/x"));
+
+    let p = parse_github_url("https://github.com/owner/repo/tree/main/skills/x@abc1234");
+    assert_eq!(p.pin.as_deref(), Some("abc1234"));
+    assert!(p.branch.is_none());
+    assert_eq!(p.subpath.as_deref(), Some("skills/x"));
+
+    let p = parse_github_url("https://github.com/owner/repo/tree/main/skills/x");
+    assert_eq!(p.branch.as_deref(), Some("main"));
+    assert!(p.pin.is_none());
+
+    let p = parse_github_url("/local/repo@v2");
+    assert_eq!(p.clone_url, "/local/repo");
+    assert_eq!(p.pin.as_deref(), Some("v2"));
+
+    // scp-style remotes keep their user part.
+    let p = parse_github_url("git@example.com:owner/repo.git");
+    assert_eq!(p.clone_url, "git@example.com:owner/repo.git");
+    assert!(p.pin.is_none());
+    let p = parse_github_url("git@example.com:repo.git");
+    assert!(p.pin.is_none());
 }
 
 #[test]
@@ -132,6 +241,8 @@ fn installs_local_skill_and_updates_from_source() {
 
     let skill = store.get_skill_by_id(&res.skill_id).unwrap().unwrap();
     assert_eq!(skill.name, "local1");
+    let manifests = store.list_skill_manifests().unwrap();
+    assert_eq!(manifests[&res.skill_id].name, "x");
 
     // add a copy target so update will resync it
     let target_root = tempfile::tempdir().unwrap();
@@ -140,16 +251,24 @@ fn installs_local_skill_and_updates_from_source() {
         id: "t1".to_string(),
         skill_id: res.skill_id.clone(),
         tool: "unknown_tool".to_string(),
+        scope: "global".to_string(),
         target_path: target.to_string_lossy().to_string(),
         mode: "copy".to_string(),
         status: "ok".to_string(),
         last_error: None,
         synced_at: None,
+        rendered_hash: None,
     };
     store.upsert_skill_target(&t).unwrap();
 
     fs::write(source.path().join("a.txt"), b"v2").unwrap();
-    let up = super::update_managed_skill_from_source(app.handle(), &store, &res.skill_id).unwrap();
+    let up = super::update_managed_skill_from_source(
+        app.handle(),
+        &store,
+        &res.skill_id,
+        super::LocalChangesPolicy::Refuse,
+    )
+    .unwrap();
     assert_eq!(up.skill_id, res.skill_id);
     assert!(up.updated_targets.contains(&"unknown_tool".to_string()));
     assert!(PathBuf::from(
@@ -178,6 +297,273 @@ fn installs_local_skill_and_updates_from_source() {
     assert!(format!("{:#}", err).contains("skill already exists"));
 }
 
+#[test]
+fn update_keeps_snapshot_and_rollback_restores_it() {
+    let app = tauri::test::mock_app();
+    let (_dir, store) = make_store();
+
+    let central_root = tempfile::tempdir().unwrap();
+    set_central_path(&store, central_root.path());
+
+    let source = tempfile::tempdir().unwrap();
+    fs::write(source.path().join("SKILL.md"), b"---\nname: x\n---\n").unwrap();
+    fs::write(source.path().join("a.txt"), b"v1").unwrap();
+
+    let res = super::install_local_skill(
+        app.handle(),
+        &store,
+        source.path(),
+        Some("hist".to_string()),
+    )
+    .unwrap();
+
+    let target_root = tempfile::tempdir().unwrap();
+    let target = target_root.path().join("target");
+    store
+        .upsert_skill_target(&SkillTargetRecord {
+            id: "t1".to_string(),
+            skill_id: res.skill_id.clone(),
+            tool: "unknown_tool".to_string(),
+            scope: "global".to_string(),
+            target_path: target.to_string_lossy().to_string(),
+            mode: "copy".to_string(),
+            status: "ok".to_string(),
+            last_error: None,
+            synced_at: None,
+            rendered_hash: None,
+        })
+        .unwrap();
+
+    fs::write(source.path().join("a.txt"), b"v2").unwrap();
+    super::update_managed_skill_from_source(
+        app.handle(),
+        &store,
+        &res.skill_id,
+        super::LocalChangesPolicy::Refuse,
+    )
+    .unwrap();
+    assert_eq!(fs::read(res.central_path.join("a.txt")).unwrap(), b"v2");
+
+    let history = store.list_skill_snapshots(&res.skill_id).unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(
+        fs::read(PathBuf::from(&history[0].snapshot_path).join("a.txt")).unwrap(),
+        b"v1"
+    );
+
+    let rolled =
+        super::rollback_managed_skill(app.handle(), &store, &res.skill_id, &history[0].id).unwrap();
+    assert!(rolled.updated_targets.contains(&"unknown_tool".to_string()));
+    assert_eq!(fs::read(res.central_path.join("a.txt")).unwrap(), b"v1");
+    assert_eq!(fs::read(target.join("a.txt")).unwrap(), b"v1");
+
+    // The replaced version (v2) is itself kept, so the rollback can be undone.
+    let history = store.list_skill_snapshots(&res.skill_id).unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(
+        fs::read(PathBuf::from(&history[0].snapshot_path).join("a.txt")).unwrap(),
+        b"v2"
+    );
+
+    let err = match super::rollback_managed_skill(app.handle(), &store, &res.skill_id, "missing") {
+        Ok(_) => panic!("expected error"),
+        Err(e) => e,
+    };
+    assert!(format!("{:#}", err).contains("snapshot not found"));
+}
+
+#[test]
+fn update_protects_local_edits_in_central() {
+    let app = tauri::test::mock_app();
+    let (_dir, store) = make_store();
+
+    let central_root = tempfile::tempdir().unwrap();
+    set_central_path(&store, central_root.path());
+
+    let source = tempfile::tempdir().unwrap();
+    let doc = "---\nname: x\n---\nintro\n\nbody\n\nfooter\n";
+    fs::write(source.path().join("SKILL.md"), doc).unwrap();
+
+    let res = super::install_local_skill(
+        app.handle(),
+        &store,
+        source.path(),
+        Some("edited".to_string()),
+    )
+    .unwrap();
+    assert!(res.content_hash.is_some());
+
+    let central_md = res.central_path.join("SKILL.md");
+    fs::write(&central_md, doc.replace("intro", "intro (local)")).unwrap();
+    fs::write(
+        source.path().join("SKILL.md"),
+        doc.replace("footer", "footer v2"),
+    )
+    .unwrap();
+
+    let err = match super::update_managed_skill_from_source(
+        app.handle(),
+        &store,
+        &res.skill_id,
+        super::LocalChangesPolicy::Refuse,
+    ) {
+        Ok(_) => panic!("expected error"),
+        Err(e) => e,
+    };
+    assert!(err.to_string().starts_with("LOCAL_CHANGES|"));
+    assert!(fs::read_to_string(&central_md).unwrap().contains("(local)"));
+    let leftovers: Vec<_> = fs::read_dir(central_root.path())
+        .unwrap()
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().starts_with(".skills-hub-"))
+        .collect();
+    assert!(leftovers.is_empty());
+
+    let prepared = super::prepare_skill_update(app.handle(), &store, &res.skill_id).unwrap();
+    assert!(prepared.local_changes);
+    let up = super::confirm_skill_update(
+        app.handle(),
+        &store,
+        prepared,
+        super::LocalChangesPolicy::Merge,
+    )
+    .unwrap();
+    let merge = up.merge.unwrap();
+    assert_eq!(merge.merged, vec!["SKILL.md"]);
+    assert!(merge.conflicts.is_empty());
+    let merged = fs::read_to_string(&central_md).unwrap();
+    assert!(merged.contains("intro (local)") && merged.contains("footer v2"));
+
+    // The merged-in edit is still a local change relative to upstream.
+    let record = store.get_skill_by_id(&res.skill_id).unwrap().unwrap();
+    assert!(crate::core::skill_merge::has_local_changes(&record).unwrap());
+
+    super::update_managed_skill_from_source(
+        app.handle(),
+        &store,
+        &res.skill_id,
+        super::LocalChangesPolicy::Overwrite,
+    )
+    .unwrap();
+    assert_eq!(
+        fs::read_to_string(&central_md).unwrap(),
+        doc.replace("footer", "footer v2")
+    );
+    // Every replaced version, edits included, is kept in history.
+    assert_eq!(store.list_skill_snapshots(&res.skill_id).unwrap().len(), 2);
+}
+
+#[test]
+fn prepared_update_shows_diff_and_applies_only_on_confirm() {
+    let app = tauri::test::mock_app();
+    let (_dir, store) = make_store();
+
+    let central_root = tempfile::tempdir().unwrap();
+    set_central_path(&store, central_root.path());
+
+    let source = tempfile::tempdir().unwrap();
+    fs::write(source.path().join("SKILL.md"), b"---\nname: x\n---\nv1\n").unwrap();
+    fs::write(source.path().join("old.txt"), b"old").unwrap();
+
+    let res = super::install_local_skill(
+        app.handle(),
+        &store,
+        source.path(),
+        Some("two-phase".to_string()),
+    )
+    .unwrap();
+
+    fs::write(source.path().join("SKILL.md"), b"---\nname: x\n---\nv2\n").unwrap();
+    fs::remove_file(source.path().join("old.txt")).unwrap();
+    fs::write(source.path().join("new.txt"), b"new").unwrap();
+
+    let prepared = super::prepare_skill_update(app.handle(), &store, &res.skill_id).unwrap();
+    let changes: Vec<(&str, FileChangeKind)> = prepared
+        .diff
+        .files
+        .iter()
+        .map(|f| (f.path.as_str(), f.kind))
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            ("SKILL.md", FileChangeKind::Modified),
+            ("new.txt", FileChangeKind::Added),
+            ("old.txt", FileChangeKind::Removed),
+        ]
+    );
+    assert!(prepared.diff.files[0]
+        .unified_diff
+        .as_deref()
+        .unwrap()
+        .contains("+v2"));
+
+    // Nothing is promoted until confirmed; aborting drops the staging dir.
+    assert!(res.central_path.join("old.txt").exists());
+    let staging = prepared.staging_dir.clone();
+    assert!(staging.exists());
+    super::abort_skill_update(prepared).unwrap();
+    assert!(!staging.exists());
+    assert!(res.central_path.join("old.txt").exists());
+
+    let prepared = super::prepare_skill_update(app.handle(), &store, &res.skill_id).unwrap();
+    super::confirm_skill_update(
+        app.handle(),
+        &store,
+        prepared,
+        super::LocalChangesPolicy::Refuse,
+    )
+    .unwrap();
+    assert!(!res.central_path.join("old.txt").exists());
+    assert!(res.central_path.join("new.txt").exists());
+    assert_eq!(store.list_skill_snapshots(&res.skill_id).unwrap().len(), 1);
+
+    // A diff reviewed against an older state of the skill is not applied.
+    let prepared = super::prepare_skill_update(app.handle(), &store, &res.skill_id).unwrap();
+    let staging = prepared.staging_dir.clone();
+    let mut record = store.get_skill_by_id(&res.skill_id).unwrap().unwrap();
+    record.updated_at += 1;
+    store.upsert_skill(&record).unwrap();
+    let err = match super::confirm_skill_update(
+        app.handle(),
+        &store,
+        prepared,
+        super::LocalChangesPolicy::Refuse,
+    ) {
+        Ok(_) => panic!("expected error"),
+        Err(e) => e,
+    };
+    assert!(format!("{:#}", err).contains("changed since the update was prepared"));
+    assert!(!staging.exists());
+}
+
+#[test]
+fn pending_updates_keep_one_prepared_update_per_skill() {
+    let app = tauri::test::mock_app();
+    let (_dir, store) = make_store();
+
+    let central_root = tempfile::tempdir().unwrap();
+    set_central_path(&store, central_root.path());
+
+    let source = tempfile::tempdir().unwrap();
+    fs::write(source.path().join("SKILL.md"), b"---\nname: x\n---\n").unwrap();
+    let res = super::install_local_skill(app.handle(), &store, source.path(), None).unwrap();
+
+    let pending = super::PendingUpdates::default();
+    let first = super::prepare_skill_update(app.handle(), &store, &res.skill_id).unwrap();
+    let (first_id, first_staging) = (first.id.clone(), first.staging_dir.clone());
+    pending.insert(first);
+    let second = super::prepare_skill_update(app.handle(), &store, &res.skill_id).unwrap();
+    let second_id = second.id.clone();
+    pending.insert(second);
+
+    assert!(!first_staging.exists());
+    assert!(pending.take(&first_id).is_none());
+    let second = pending.take(&second_id).unwrap();
+    assert!(pending.take(&second_id).is_none());
+    super::abort_skill_update(second).unwrap();
+}
+
 #[test]
 fn lists_and_installs_git_skills_without_network() {
     let app = tauri::test::mock_app();
@@ -200,6 +586,7 @@ fn lists_and_installs_git_skills_without_network() {
         app.handle(),
         &store,
         repo_dir.path().to_string_lossy().as_ref(),
+        None,
     )
     .unwrap();
     let subpaths: Vec<String> = candidates.into_iter().map(|c| c.subpath).collect();
@@ -217,6 +604,221 @@ fn lists_and_installs_git_skills_without_network() {
     assert!(res.central_path.exists());
 }
 
+#[test]
+fn check_updates_reports_status_without_applying() {
+    let app = tauri::test::mock_app();
+    let (_dir, store) = make_store();
+    let central_root = tempfile::tempdir().unwrap();
+    set_central_path(&store, central_root.path());
+    crate::core::cache_cleanup::set_git_cache_ttl_secs(&store, 0).unwrap();
+
+    let repo_dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(repo_dir.path().join("skills/a")).unwrap();
+    fs::write(
+        repo_dir.path().join("skills/a/SKILL.md"),
+        "---\nname: A\n---\n",
+    )
+    .unwrap();
+    fs::write(repo_dir.path().join("README.md"), "v1").unwrap();
+    let repo = init_git_repo(repo_dir.path());
+
+    let res = super::install_git_skill_from_selection(
+        app.handle(),
+        &store,
+        repo_dir.path().to_string_lossy().as_ref(),
+        "skills/a",
+        None,
+    )
+    .unwrap();
+    let record = store.get_skill_by_id(&res.skill_id).unwrap().unwrap();
+    assert_eq!(record.source_subpath.as_deref(), Some("skills/a"));
+
+    let status = |store: &SkillStore| {
+        let checks = super::check_updates(app.handle(), store).unwrap();
+        assert_eq!(checks.len(), 1);
+        checks[0].status
+    };
+    assert_eq!(status(&store), super::UpdateStatus::UpToDate);
+
+    // A commit outside the skill folder moves HEAD but leaves the skill untouched.
+    fs::write(repo_dir.path().join("README.md"), "v2").unwrap();
+    commit_all(&repo, "touch readme");
+    assert_eq!(status(&store), super::UpdateStatus::UpToDate);
+
+    fs::write(
+        repo_dir.path().join("skills/a/SKILL.md"),
+        "---\nname: A\n---\nchanged\n",
+    )
+    .unwrap();
+    commit_all(&repo, "change skill");
+    let checks = super::check_updates(app.handle(), &store).unwrap();
+    assert_eq!(checks[0].status, super::UpdateStatus::UpdateAvailable);
+    assert_ne!(checks[0].latest_revision, checks[0].current_revision);
+
+    // Checking never rewrites central content.
+    let central = fs::read_to_string(res.central_path.join("SKILL.md")).unwrap();
+    assert!(!central.contains("changed"));
+
+    fs::remove_dir_all(repo_dir.path().join("skills/a")).unwrap();
+    let mut index = repo.index().unwrap();
+    index.remove_dir(Path::new("skills/a"), 0).unwrap();
+    index.write().unwrap();
+    commit_all(&repo, "drop skill");
+    assert_eq!(status(&store), super::UpdateStatus::SourceMissing);
+}
+
+#[test]
+fn check_reports_source_missing_only_when_the_remote_is_gone() {
+    let app = tauri::test::mock_app();
+    let (_dir, store) = make_store();
+    let central_root = tempfile::tempdir().unwrap();
+    set_central_path(&store, central_root.path());
+    crate::core::cache_cleanup::set_git_cache_ttl_secs(&store, 0).unwrap();
+
+    let parent = tempfile::tempdir().unwrap();
+    let repo_dir = parent.path().join("origin");
+    fs::create_dir_all(&repo_dir).unwrap();
+    fs::write(repo_dir.join("SKILL.md"), "---\nname: gone\n---\n").unwrap();
+    init_git_repo(&repo_dir);
+    let res =
+        super::install_git_skill(app.handle(), &store, &repo_dir.to_string_lossy(), None).unwrap();
+    let check = || {
+        let record = store.get_skill_by_id(&res.skill_id).unwrap().unwrap();
+        super::check_skill_update(app.handle(), &store, &record)
+    };
+
+    // A pin that does not resolve is an error, not a vanished source.
+    let record = store.get_skill_by_id(&res.skill_id).unwrap().unwrap();
+    store
+        .upsert_skill(&SkillRecord {
+            source_pin: Some("no-such-tag".to_string()),
+            ..record.clone()
+        })
+        .unwrap();
+    assert_eq!(check().status, super::UpdateStatus::Error);
+    store.upsert_skill(&record).unwrap();
+
+    fs::remove_dir_all(&repo_dir).unwrap();
+    let gone = check();
+    assert_eq!(
+        gone.status,
+        super::UpdateStatus::SourceMissing,
+        "{:?}",
+        gone.error
+    );
+}
+
+#[test]
+fn root_level_skill_stays_up_to_date_when_content_is_unchanged() {
+    let app = tauri::test::mock_app();
+    let (_dir, store) = make_store();
+    let central_root = tempfile::tempdir().unwrap();
+    set_central_path(&store, central_root.path());
+    crate::core::cache_cleanup::set_git_cache_ttl_secs(&store, 0).unwrap();
+
+    let repo_dir = tempfile::tempdir().unwrap();
+    fs::write(repo_dir.path().join("SKILL.md"), "---\nname: root\n---\n").unwrap();
+    let repo = init_git_repo(repo_dir.path());
+
+    let res = super::install_git_skill(
+        app.handle(),
+        &store,
+        repo_dir.path().to_string_lossy().as_ref(),
+        None,
+    )
+    .unwrap();
+    // Cache bookkeeping is not skill content.
+    assert!(!res
+        .central_path
+        .join(crate::core::cache_cleanup::CACHE_META_FILE)
+        .exists());
+
+    // A new commit with the same tree moves HEAD; the refetch rewrites the cache meta.
+    commit_all(&repo, "empty");
+    let checks = super::check_updates(app.handle(), &store).unwrap();
+    assert_ne!(checks[0].latest_revision, checks[0].current_revision);
+    assert_eq!(checks[0].status, super::UpdateStatus::UpToDate);
+}
+
+#[test]
+fn pinned_git_skill_only_moves_when_pin_changes() {
+    let app = tauri::test::mock_app();
+    let (_dir, store) = make_store();
+    let central_root = tempfile::tempdir().unwrap();
+    set_central_path(&store, central_root.path());
+    crate::core::cache_cleanup::set_git_cache_ttl_secs(&store, 0).unwrap();
+
+    let repo_dir = tempfile::tempdir().unwrap();
+    fs::write(repo_dir.path().join("SKILL.md"), "---\nname: P\n---\nv1\n").unwrap();
+    let repo = init_git_repo(repo_dir.path());
+    let c1 = repo.head().unwrap().target().unwrap();
+    fs::write(repo_dir.path().join("SKILL.md"), "---\nname: P\n---\nv2\n").unwrap();
+    let c2 = commit_all(&repo, "v2");
+    repo.tag_lightweight("v2.0.0", &repo.find_object(c2, None).unwrap(), false)
+        .unwrap();
+
+    let url = format!("{}@{}", repo_dir.path().to_string_lossy(), c1);
+    let res =
+        super::install_git_skill(app.handle(), &store, &url, Some("pinned".to_string())).unwrap();
+    let skill_md = res.central_path.join("SKILL.md");
+    assert!(fs::read_to_string(&skill_md).unwrap().contains("v1"));
+    let record = store.get_skill_by_id(&res.skill_id).unwrap().unwrap();
+    assert_eq!(record.source_pin, Some(c1.to_string()));
+    assert_eq!(record.source_revision, Some(c1.to_string()));
+
+    // Upstream moves on, but a pinned skill stays put.
+    fs::write(repo_dir.path().join("SKILL.md"), "---\nname: P\n---\nv3\n").unwrap();
+    commit_all(&repo, "v3");
+    let up = super::update_managed_skill_from_source(
+        app.handle(),
+        &store,
+        &res.skill_id,
+        super::LocalChangesPolicy::Refuse,
+    )
+    .unwrap();
+    assert_eq!(up.source_revision, Some(c1.to_string()));
+    assert!(fs::read_to_string(&skill_md).unwrap().contains("v1"));
+    let checks = super::check_updates(app.handle(), &store).unwrap();
+    assert_eq!(checks[0].status, super::UpdateStatus::UpToDate);
+
+    let up = super::set_skill_pin(
+        app.handle(),
+        &store,
+        &res.skill_id,
+        Some("v2.0.0"),
+        super::LocalChangesPolicy::Refuse,
+    )
+    .unwrap();
+    assert_eq!(up.source_revision, Some(c2.to_string()));
+    assert!(fs::read_to_string(&skill_md).unwrap().contains("v2"));
+
+    let up = super::set_skill_pin(
+        app.handle(),
+        &store,
+        &res.skill_id,
+        None,
+        super::LocalChangesPolicy::Refuse,
+    )
+    .unwrap();
+    assert_ne!(up.source_revision, Some(c2.to_string()));
+    assert!(fs::read_to_string(&skill_md).unwrap().contains("v3"));
+    let record = store.get_skill_by_id(&res.skill_id).unwrap().unwrap();
+    assert!(record.source_pin.is_none());
+
+    // A pin that does not resolve leaves the skill and its stored pin untouched.
+    assert!(super::set_skill_pin(
+        app.handle(),
+        &store,
+        &res.skill_id,
+        Some("no-such-tag"),
+        super::LocalChangesPolicy::Refuse,
+    )
+    .is_err());
+    let record = store.get_skill_by_id(&res.skill_id).unwrap().unwrap();
+    assert!(record.source_pin.is_none());
+    assert!(fs::read_to_string(&skill_md).unwrap().contains("v3"));
+}
+
 #[test]
 fn install_git_skill_errors_on_multi_skills_repo_root() {
     let app = tauri::test::mock_app();
@@ -265,7 +867,7 @@ fn lists_local_skills_with_invalid_entries() {
     fs::write(base.join("skills/c/SKILL.md"), "name: C\n").unwrap();
     fs::write(base.join("skills/d/SKILL.md"), "---\ndescription: D\n---\n").unwrap();
 
-    let list = super::list_local_skills(base).unwrap();
+    let list = super::list_local_skills(base, None).unwrap();
 
     let find = |subpath: &str| list.iter().find(|c| c.subpath == subpath).cloned();
 
@@ -327,3 +929,136 @@ fn install_local_selection_validates_skill_md() {
     };
     assert!(format!("{:#}", err).contains("SKILL_INVALID|missing_skill_md"));
 }
+
+#[test]
+fn installs_archive_skill_and_updates_when_archive_changes() {
+    use std::io::Write;
+
+    let app = tauri::test::mock_app();
+    let (_dir, store) = make_store();
+    let central_root = tempfile::tempdir().unwrap();
+    set_central_path(&store, central_root.path());
+
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("skills.zip");
+    let write_archive = |version: &[u8]| {
+        let mut zip = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+        let opts = zip::write::SimpleFileOptions::default();
+        for (name, data) in [
+            ("pack/skills/a/SKILL.md", &b"---\nname: a\n---\n"[..]),
+            ("pack/skills/a/notes.txt", version),
+            ("pack/skills/b/SKILL.md", &b"---\nname: b\n---\n"[..]),
+        ] {
+            zip.start_file(name, opts).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+    };
+    write_archive(b"v1");
+    let source = archive.to_string_lossy().to_string();
+
+    let candidates = super::list_archive_skills(app.handle(), &store, &source).unwrap();
+    let subpaths: Vec<_> = candidates.iter().map(|c| c.subpath.as_str()).collect();
+    assert_eq!(subpaths, vec!["skills/a", "skills/b"]);
+
+    let err = match super::install_archive_skill(app.handle(), &store, &source, None, None) {
+        Ok(_) => panic!("expected error"),
+        Err(e) => e,
+    };
+    assert!(format!("{:#}", err).starts_with("MULTI_SKILLS|"));
+
+    let res = super::install_archive_skill(app.handle(), &store, &source, Some("skills/a"), None)
+        .unwrap();
+    assert_eq!(res.name, "a");
+    assert_eq!(fs::read(res.central_path.join("notes.txt")).unwrap(), b"v1");
+    let skill = store.get_skill_by_id(&res.skill_id).unwrap().unwrap();
+    assert_eq!(skill.source_type, "archive");
+    assert_eq!(skill.source_subpath.as_deref(), Some("skills/a"));
+    let first_checksum = skill.source_revision.clone().unwrap();
+    assert!(first_checksum.starts_with("sha256:"));
+
+    write_archive(b"v2");
+    super::update_managed_skill_from_source(
+        app.handle(),
+        &store,
+        &res.skill_id,
+        super::LocalChangesPolicy::Refuse,
+    )
+    .unwrap();
+    assert_eq!(fs::read(res.central_path.join("notes.txt")).unwrap(), b"v2");
+    let skill = store.get_skill_by_id(&res.skill_id).unwrap().unwrap();
+    assert_ne!(skill.source_revision.unwrap(), first_checksum);
+}
+
+#[test]
+fn archive_skill_name_cannot_escape_central_repo() {
+    use std::io::Write;
+
+    let app = tauri::test::mock_app();
+    let (_dir, store) = make_store();
+    let root = tempfile::tempdir().unwrap();
+    let central = root.path().join("central");
+    set_central_path(&store, &central);
+
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("evil.zip");
+    let outside = root.path().join("escaped");
+    for name in [
+        "../escaped".to_string(),
+        outside.to_string_lossy().to_string(),
+        "..".to_string(),
+        "a\\b".to_string(),
+        "bad\tname".to_string(),
+    ] {
+        let mut zip = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+        zip.start_file("SKILL.md", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        write!(zip, "---\nname: {:?}\n---\n", name).unwrap();
+        zip.finish().unwrap();
+
+        let source = archive.to_string_lossy().to_string();
+        let err = match super::install_archive_skill(app.handle(), &store, &source, None, None) {
+            Ok(_) => panic!("expected error for {:?}", name),
+            Err(e) => e,
+        };
+        assert!(
+            format!("{:#}", err).contains("invalid skill name"),
+            "{:?}: {:#}",
+            name,
+            err
+        );
+    }
+    assert!(!outside.exists());
+    assert!(store.list_skills().unwrap().is_empty());
+}
+
+#[test]
+fn cleanup_removes_leftover_update_staging_dirs() {
+    let (_dir, store) = make_store();
+    let central = tempfile::tempdir().unwrap();
+    let stale_update = central.path().join(".skills-hub-update-1");
+    let stale_merge = central.path().join(".skills-hub-merge-2");
+    let journaled = central.path().join(".skills-hub-update-3");
+    for dir in [&stale_update, &stale_merge, &journaled] {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("SKILL.md"), b"x").unwrap();
+    }
+    fs::create_dir_all(central.path().join("kept-skill")).unwrap();
+    let swap = crate::core::op_journal::Swap::adopt(
+        &store,
+        "central_update",
+        &journaled,
+        &central.path().join("kept-skill"),
+    )
+    .unwrap();
+
+    assert_eq!(
+        super::cleanup_update_staging_dirs(&store, central.path()).unwrap(),
+        2
+    );
+    assert!(!stale_update.exists());
+    assert!(!stale_merge.exists());
+    assert!(journaled.exists());
+    assert!(central.path().join("kept-skill").exists());
+    swap.abort();
+}
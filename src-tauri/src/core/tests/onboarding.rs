@@ -20,7 +20,7 @@ fn groups_by_name_and_detects_conflicts_by_fingerprint() {
     fs::create_dir_all(home.path().join(".codex/skills/.system")).unwrap();
     fs::write(home.path().join(".codex/skills/.system/SKILL.md"), b"x").unwrap();
 
-    let plan = build_onboarding_plan_in_home(home.path(), None, None).unwrap();
+    let plan = build_onboarding_plan_in_home(home.path(), None, None, &[]).unwrap();
     assert_eq!(plan.total_tools_scanned, 2);
     assert_eq!(plan.total_skills_found, 2);
     assert_eq!(plan.groups.len(), 1);
@@ -46,7 +46,7 @@ fn excludes_central_repo_path() {
     let link_path = home.path().join(".cursor/skills/skill-a");
     symlink(central.join("skill-a"), &link_path).unwrap();
 
-    let plan = build_onboarding_plan_in_home(home.path(), Some(&central), None).unwrap();
+    let plan = build_onboarding_plan_in_home(home.path(), Some(&central), None, &[]).unwrap();
     assert_eq!(plan.total_skills_found, 0);
 }
 
@@ -65,6 +65,254 @@ fn excludes_managed_skill_targets() {
         &home.path().join(".cursor/skills/foo"),
     ));
 
-    let plan = build_onboarding_plan_in_home(home.path(), None, Some(&exclude)).unwrap();
+    let plan = build_onboarding_plan_in_home(home.path(), None, Some(&exclude), &[]).unwrap();
     assert_eq!(plan.total_skills_found, 0);
 }
+
+fn write_variant(dir: &std::path::Path, body: &str) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join("SKILL.md"), body).unwrap();
+}
+
+#[test]
+fn applies_pick_keep_both_and_skip_decisions() {
+    use super::{apply_in_home, NamedVariant, OnboardingDecision};
+    use crate::core::test_fixtures::make_store;
+
+    let app = tauri::test::mock_app();
+    let (_db, store) = make_store();
+    let central = tempfile::tempdir().unwrap();
+    store
+        .set_setting(
+            "central_repo_path",
+            central.path().to_string_lossy().as_ref(),
+        )
+        .unwrap();
+
+    let home = tempfile::tempdir().unwrap();
+    let cursor = home.path().join(".cursor/skills");
+    let codex = home.path().join(".codex/skills");
+    write_variant(&cursor.join("foo"), "cursor foo");
+    write_variant(&codex.join("foo"), "codex foo");
+    write_variant(&cursor.join("bar"), "cursor bar");
+    write_variant(&codex.join("bar"), "codex bar");
+    write_variant(&codex.join("baz"), "baz");
+
+    let decisions = vec![
+        OnboardingDecision::Pick {
+            group: "foo".to_string(),
+            variant_path: cursor.join("foo"),
+            name: None,
+        },
+        OnboardingDecision::KeepBoth {
+            group: "bar".to_string(),
+            variants: vec![
+                NamedVariant {
+                    path: cursor.join("bar"),
+                    name: "bar-cursor".to_string(),
+                },
+                NamedVariant {
+                    path: codex.join("bar"),
+                    name: "bar-codex".to_string(),
+                },
+            ],
+        },
+        OnboardingDecision::Skip {
+            group: "baz".to_string(),
+        },
+        OnboardingDecision::Pick {
+            group: "gone".to_string(),
+            variant_path: codex.join("gone"),
+            name: None,
+        },
+    ];
+    let report = apply_in_home(app.handle(), &store, &decisions, home.path()).unwrap();
+    assert_eq!(report.groups.len(), 3, "skipped groups are not reported");
+    assert!(report.groups[0].error.is_none());
+    assert!(report.groups[1].error.is_none());
+    assert!(report.groups[2].error.is_some());
+
+    // Every variant of `foo` now carries the picked content.
+    let read = |p: std::path::PathBuf| fs::read_to_string(p.join("SKILL.md")).unwrap();
+    assert_eq!(read(central.path().join("foo")), "cursor foo");
+    assert_eq!(read(cursor.join("foo")), "cursor foo");
+    assert_eq!(read(codex.join("foo")), "cursor foo");
+    let foo = &report.groups[0];
+    assert_eq!(foo.imported.len(), 1);
+    assert_eq!(foo.linked.len(), 2);
+    for tool in ["cursor", "codex"] {
+        let target = store
+            .get_skill_target(&foo.imported[0].skill_id, tool, "global")
+            .unwrap()
+            .unwrap();
+        assert_eq!(target.status, "ok");
+    }
+
+    // The replaced `codex` copy differed from the pick and was kept in history.
+    let foo_id = &foo.imported[0].skill_id;
+    let snapshots = store.list_skill_snapshots(foo_id).unwrap();
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(
+        read(std::path::PathBuf::from(&snapshots[0].snapshot_path)),
+        "codex foo"
+    );
+    let snapshot_of = |tool: &str| {
+        foo.linked
+            .iter()
+            .find(|l| l.tool == tool)
+            .and_then(|l| l.snapshot_id.clone())
+    };
+    assert_eq!(snapshot_of("codex"), Some(snapshots[0].id.clone()));
+    assert_eq!(snapshot_of("cursor"), None);
+
+    // Both `bar` variants were kept, each under its own name and still in place.
+    assert_eq!(read(central.path().join("bar-cursor")), "cursor bar");
+    assert_eq!(read(central.path().join("bar-codex")), "codex bar");
+    assert_eq!(read(codex.join("bar")), "codex bar");
+    assert_eq!(report.groups[1].imported.len(), 2);
+
+    // Skipped groups stay unmanaged.
+    assert!(!central.path().join("baz").exists());
+    assert_eq!(store.list_skills().unwrap().len(), 3);
+
+    // The scan remembers which folders became which skill.
+    let discovered = store.list_discovered_skills().unwrap();
+    let imported_as = |path: std::path::PathBuf| {
+        discovered
+            .iter()
+            .find(|row| row.found_path == path.to_string_lossy())
+            .and_then(|row| row.imported_skill_id.clone())
+    };
+    assert_eq!(
+        imported_as(codex.join("foo")),
+        Some(foo.imported[0].skill_id.clone())
+    );
+    assert_eq!(imported_as(codex.join("baz")), None);
+}
+
+#[test]
+fn keeps_a_snapshot_of_every_replaced_variant() {
+    use super::{apply_in_home, OnboardingDecision};
+    use crate::core::skill_history::MAX_SNAPSHOTS_PER_SKILL;
+    use crate::core::test_fixtures::make_store;
+
+    let app = tauri::test::mock_app();
+    let (_db, store) = make_store();
+    let central = tempfile::tempdir().unwrap();
+    store
+        .set_setting(
+            "central_repo_path",
+            central.path().to_string_lossy().as_ref(),
+        )
+        .unwrap();
+
+    let home = tempfile::tempdir().unwrap();
+    let tools = [
+        ".cursor", ".codex", ".cline", ".junie", ".kiro", ".qwen", ".roo",
+    ];
+    for tool in tools {
+        write_variant(&home.path().join(tool).join("skills/foo"), tool);
+    }
+
+    let decisions = vec![OnboardingDecision::Pick {
+        group: "foo".to_string(),
+        variant_path: home.path().join(".cursor/skills/foo"),
+        name: None,
+    }];
+    let report = apply_in_home(app.handle(), &store, &decisions, home.path()).unwrap();
+    let foo = &report.groups[0];
+    assert!(foo.error.is_none(), "{:?}", foo.error);
+
+    let replaced = tools.len() - 1;
+    assert!(replaced > MAX_SNAPSHOTS_PER_SKILL);
+    let snapshots = store
+        .list_skill_snapshots(&foo.imported[0].skill_id)
+        .unwrap();
+    assert_eq!(snapshots.len(), replaced);
+    let linked = foo.linked.iter().filter(|l| l.snapshot_id.is_some());
+    assert_eq!(linked.count(), replaced);
+}
+
+#[test]
+fn records_scans_and_reports_new_skills() {
+    use super::scan_and_record;
+    use crate::core::test_fixtures::make_store;
+
+    let (_db, store) = make_store();
+    let central = tempfile::tempdir().unwrap();
+    let home = tempfile::tempdir().unwrap();
+    let codex = home.path().join(".codex/skills");
+    write_variant(&codex.join("foo"), "foo");
+
+    let plan = scan_and_record(&store, home.path(), central.path()).unwrap();
+    assert_eq!(plan.new_since_last_scan, 1);
+    assert_eq!(store.list_discovered_skills().unwrap().len(), 1);
+
+    // Unchanged folders keep the fingerprint stored by the last scan.
+    let mut row = store.list_discovered_skills().unwrap().remove(0);
+    row.fingerprint = Some("cached".to_string());
+    store.upsert_discovered_skill(&row).unwrap();
+    write_variant(&codex.join("bar"), "bar");
+    let plan = scan_and_record(&store, home.path(), central.path()).unwrap();
+    assert_eq!(plan.new_since_last_scan, 1);
+    let variant = |name: &str| {
+        plan.groups
+            .iter()
+            .find(|g| g.name == name)
+            .map(|g| g.variants[0].clone())
+            .unwrap()
+    };
+    assert_eq!(variant("foo").fingerprint.as_deref(), Some("cached"));
+    assert!(!variant("foo").is_new);
+    assert!(variant("bar").is_new);
+
+    // Folders that vanished are forgotten.
+    fs::remove_dir_all(codex.join("bar")).unwrap();
+    let plan = scan_and_record(&store, home.path(), central.path()).unwrap();
+    assert_eq!(plan.new_since_last_scan, 0);
+    assert_eq!(store.list_discovered_skills().unwrap().len(), 1);
+}
+
+#[test]
+fn compares_variants_of_a_conflict_group() {
+    use super::compare_variants;
+    use crate::core::skill_diff::FileChangeKind;
+
+    let home = tempfile::tempdir().unwrap();
+    let cursor = home.path().join(".cursor/skills/foo");
+    let codex = home.path().join(".codex/skills/foo");
+    write_variant(&cursor, "# foo\nstep one\n");
+    write_variant(&codex, "# foo\nstep two\n");
+    fs::write(codex.join("notes.txt"), "codex only\n").unwrap();
+
+    let plan = build_onboarding_plan_in_home(home.path(), None, None, &[]).unwrap();
+    let comparison = compare_variants(&plan.groups[0], Some(&cursor)).unwrap();
+    assert_eq!(comparison.variants.len(), 2);
+    assert_eq!(comparison.variants[0].path, cursor);
+    assert!(comparison.variants[0].diff.files.is_empty());
+
+    let files: Vec<(&str, Vec<usize>, usize)> = comparison
+        .files
+        .iter()
+        .map(|f| {
+            (
+                f.path.as_str(),
+                f.present_in.clone(),
+                f.content_groups.len(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        files,
+        vec![("SKILL.md", vec![0, 1], 2), ("notes.txt", vec![1], 1)]
+    );
+
+    let diff = &comparison.variants[1].diff.files;
+    assert_eq!(diff[0].path, "SKILL.md");
+    assert_eq!(diff[0].kind, FileChangeKind::Modified);
+    let text = diff[0].unified_diff.as_deref().unwrap();
+    assert!(text.contains("-step one") && text.contains("+step two"));
+    assert_eq!(diff[1].kind, FileChangeKind::Added);
+
+    assert!(compare_variants(&plan.groups[0], Some(&home.path().join("elsewhere"))).is_err());
+}
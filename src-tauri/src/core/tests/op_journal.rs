@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::Path;
+
+use super::{recover_operations, Swap};
+use crate::core::skill_store::OperationRecord;
+use crate::core::test_fixtures::make_store;
+
+fn write_skill(dir: &Path, body: &str) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join("SKILL.md"), body).unwrap();
+}
+
+fn entries(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+fn journal_entry(phase: &str, target: &Path, staged: &Path, backup: &Path) -> OperationRecord {
+    OperationRecord {
+        id: "op-1".to_string(),
+        kind: "central_update".to_string(),
+        target_path: target.to_string_lossy().to_string(),
+        staged_path: staged.to_string_lossy().to_string(),
+        backup_path: backup.to_string_lossy().to_string(),
+        phase: phase.to_string(),
+        created_at: 1,
+    }
+}
+
+#[test]
+fn commit_swaps_content_and_clears_journal() {
+    let (dir, store) = make_store();
+    let root = dir.path().join("skills");
+    write_skill(&root.join("demo"), "old");
+
+    let swap = Swap::begin(&store, "copy_target", &root.join("demo")).unwrap();
+    assert_eq!(store.list_operations().unwrap().len(), 1);
+    write_skill(swap.staged(), "new");
+    swap.commit().unwrap();
+
+    assert_eq!(
+        fs::read_to_string(root.join("demo/SKILL.md")).unwrap(),
+        "new"
+    );
+    assert_eq!(entries(&root), vec!["demo".to_string()]);
+    assert!(store.list_operations().unwrap().is_empty());
+
+    // Aborting leaves the target alone.
+    let swap = Swap::begin(&store, "copy_target", &root.join("demo")).unwrap();
+    write_skill(swap.staged(), "discarded");
+    swap.abort();
+    assert_eq!(
+        fs::read_to_string(root.join("demo/SKILL.md")).unwrap(),
+        "new"
+    );
+    assert_eq!(entries(&root), vec!["demo".to_string()]);
+    assert!(store.list_operations().unwrap().is_empty());
+}
+
+#[test]
+fn failed_move_aside_is_not_replayed_on_recovery() {
+    let (dir, store) = make_store();
+    let root = dir.path().join("skills");
+    write_skill(&root.join("demo"), "old");
+
+    let swap = Swap::begin(&store, "copy_target", &root.join("demo")).unwrap();
+    write_skill(swap.staged(), "new");
+    // Something already sits at the backup path, so the old version cannot be moved
+    // aside (a read-only parent would do, but not when the tests run as root).
+    let blocker = swap.backup.clone();
+    write_skill(&blocker, "in the way");
+    assert!(swap.commit().is_err());
+
+    assert_eq!(
+        fs::read_to_string(root.join("demo/SKILL.md")).unwrap(),
+        "old"
+    );
+    assert!(store.list_operations().unwrap().is_empty());
+    fs::remove_dir_all(&blocker).unwrap();
+    assert_eq!(entries(&root), vec!["demo".to_string()]);
+
+    // The failed update stays failed after a restart.
+    assert_eq!(recover_operations(&store).unwrap(), 0);
+    assert_eq!(
+        fs::read_to_string(root.join("demo/SKILL.md")).unwrap(),
+        "old"
+    );
+}
+
+#[test]
+fn recovery_replays_a_swap_interrupted_after_moving_the_old_version() {
+    let (dir, store) = make_store();
+    let root = dir.path().join("skills");
+    let target = root.join("demo");
+    let staged = root.join(".demo.staged");
+    let backup = root.join(".demo.old");
+    // Crash between "old moved aside" and "new moved in": no `demo` at all.
+    write_skill(&staged, "new");
+    write_skill(&backup, "old");
+    store
+        .insert_operation(&journal_entry("ready", &target, &staged, &backup))
+        .unwrap();
+
+    assert_eq!(recover_operations(&store).unwrap(), 1);
+    assert_eq!(fs::read_to_string(target.join("SKILL.md")).unwrap(), "new");
+    assert_eq!(entries(&root), vec!["demo".to_string()]);
+    assert!(store.list_operations().unwrap().is_empty());
+}
+
+#[test]
+fn recovery_rolls_back_a_swap_that_was_still_staging() {
+    let (dir, store) = make_store();
+    let root = dir.path().join("skills");
+    let target = root.join("demo");
+    let staged = root.join(".demo.staged");
+    let backup = root.join(".demo.old");
+    write_skill(&target, "old");
+    // Half-copied content that must not replace the skill.
+    fs::create_dir_all(&staged).unwrap();
+    store
+        .insert_operation(&journal_entry("staging", &target, &staged, &backup))
+        .unwrap();
+
+    assert_eq!(recover_operations(&store).unwrap(), 1);
+    assert_eq!(fs::read_to_string(target.join("SKILL.md")).unwrap(), "old");
+    assert_eq!(entries(&root), vec!["demo".to_string()]);
+    assert!(store.list_operations().unwrap().is_empty());
+}
+
+#[test]
+fn recovery_restores_the_old_version_when_the_staged_one_is_gone() {
+    let (dir, store) = make_store();
+    let root = dir.path().join("skills");
+    let target = root.join("demo");
+    let backup = root.join(".demo.old");
+    write_skill(&backup, "old");
+    store
+        .insert_operation(&journal_entry(
+            "ready",
+            &target,
+            &root.join(".demo.staged"),
+            &backup,
+        ))
+        .unwrap();
+
+    recover_operations(&store).unwrap();
+    assert_eq!(fs::read_to_string(target.join("SKILL.md")).unwrap(), "old");
+    assert_eq!(entries(&root), vec!["demo".to_string()]);
+}
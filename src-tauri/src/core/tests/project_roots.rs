@@ -0,0 +1,50 @@
+use crate::core::test_fixtures::make_store;
+use crate::core::tool_adapters::TargetScope;
+
+use super::{add_project_root, get_project_roots, remove_project_root, resolve_target_scope};
+
+#[test]
+fn add_and_remove_project_roots() {
+    let (dir, store) = make_store();
+    let repo = dir.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    let key = repo.to_string_lossy().to_string();
+
+    assert!(get_project_roots(&store).is_empty());
+    assert_eq!(add_project_root(&store, &key).unwrap(), vec![key.clone()]);
+    // Trailing separators name the same root.
+    assert_eq!(
+        add_project_root(&store, &format!("{}/", key)).unwrap(),
+        vec![key.clone()]
+    );
+
+    assert!(add_project_root(&store, "relative/repo").is_err());
+    assert!(add_project_root(&store, &dir.path().join("missing").to_string_lossy()).is_err());
+
+    assert!(remove_project_root(&store, &key).unwrap().is_empty());
+    assert!(get_project_roots(&store).is_empty());
+}
+
+#[test]
+fn scope_must_be_global_or_a_registered_root() {
+    let (dir, store) = make_store();
+    let repo = dir.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    let key = repo.to_string_lossy().to_string();
+
+    assert_eq!(
+        resolve_target_scope(&store, None).unwrap(),
+        TargetScope::Global
+    );
+    assert_eq!(
+        resolve_target_scope(&store, Some("global")).unwrap(),
+        TargetScope::Global
+    );
+    assert!(resolve_target_scope(&store, Some(&key)).is_err());
+
+    add_project_root(&store, &key).unwrap();
+    assert_eq!(
+        resolve_target_scope(&store, Some(&key)).unwrap(),
+        TargetScope::Project(repo)
+    );
+}
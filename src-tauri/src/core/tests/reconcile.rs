@@ -0,0 +1,337 @@
+use std::fs;
+use std::path::Path;
+
+use super::{apply_in_home, plan_in_home, ReconcileAction, ReconcileStep};
+use crate::core::cache_cleanup::set_git_cache_ttl_secs;
+use crate::core::content_hash::hash_dir;
+use crate::core::installer::LocalChangesPolicy;
+use crate::core::skill_store::SkillStore;
+use crate::core::team_manifest::{load_lockfile, write_lockfile};
+use crate::core::test_fixtures::make_store;
+
+fn write_skill(dir: &Path, name: &str, body: &str) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(
+        dir.join("SKILL.md"),
+        format!("---\nname: {}\n---\n{}", name, body),
+    )
+    .unwrap();
+}
+
+fn commit_all(dir: &Path, msg: &str) -> String {
+    let repo = git2::Repository::open(dir)
+        .or_else(|_| git2::Repository::init(dir))
+        .unwrap();
+    let sig = git2::Signature::now("t", "t@example.com").unwrap();
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    let parents: Vec<_> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, msg, &tree, &parents)
+        .unwrap()
+        .to_string()
+}
+
+fn central_store() -> (tempfile::TempDir, tempfile::TempDir, SkillStore) {
+    let (dir, store) = make_store();
+    let central = tempfile::tempdir().unwrap();
+    store
+        .set_setting(
+            "central_repo_path",
+            central.path().to_string_lossy().as_ref(),
+        )
+        .unwrap();
+    (dir, central, store)
+}
+
+fn installed_body(store: &SkillStore) -> String {
+    let skill = store.list_skills().unwrap().remove(0);
+    fs::read_to_string(Path::new(&skill.central_path).join("SKILL.md")).unwrap()
+}
+
+fn actions(steps: &[ReconcileStep]) -> Vec<(ReconcileAction, Option<&str>, Option<&str>)> {
+    steps
+        .iter()
+        .map(|s| (s.action, s.tool.as_deref(), s.reason.as_deref()))
+        .collect()
+}
+
+#[test]
+fn plans_and_applies_manifest_then_tracks_drift() {
+    let app = tauri::test::mock_app();
+    let (_dir, store) = make_store();
+    let central = tempfile::tempdir().unwrap();
+    store
+        .set_setting(
+            "central_repo_path",
+            central.path().to_string_lossy().as_ref(),
+        )
+        .unwrap();
+    let home = tempfile::tempdir().unwrap();
+    fs::create_dir_all(home.path().join(".cursor")).unwrap();
+
+    let team = tempfile::tempdir().unwrap();
+    write_skill(&team.path().join("skills/style"), "style", "v1");
+    let manifest = team.path().join("skills.toml");
+    fs::write(
+        &manifest,
+        "tools = [\"cursor\", \"codex\"]\n\n[[skill]]\nname = \"style\"\nsource = \"./skills/style\"\n",
+    )
+    .unwrap();
+
+    let plan = plan_in_home(&store, &manifest, home.path()).unwrap();
+    assert_eq!(
+        actions(&plan.steps),
+        vec![
+            (ReconcileAction::Install, None, None),
+            (
+                ReconcileAction::Sync,
+                Some("cursor"),
+                Some("target_missing")
+            ),
+            (
+                ReconcileAction::Skip,
+                Some("codex"),
+                Some("tool_not_installed")
+            ),
+        ]
+    );
+    assert!(
+        store.list_skills().unwrap().is_empty(),
+        "plan must not apply"
+    );
+
+    let report = apply_in_home(
+        app.handle(),
+        &store,
+        &manifest,
+        LocalChangesPolicy::Refuse,
+        home.path(),
+        false,
+    )
+    .unwrap();
+    assert!(
+        report.steps.iter().all(|s| s.error.is_none()),
+        "{:?}",
+        report
+    );
+    let target = home.path().join(".cursor/skills/style/SKILL.md");
+    assert!(fs::read_to_string(&target).unwrap().ends_with("v1"));
+
+    let lock = load_lockfile(&team.path().join("skills.lock")).unwrap();
+    assert_eq!(lock.skills.len(), 1);
+    assert_eq!(lock.skills[0].source, "./skills/style");
+    assert!(lock.skills[0].content_hash.is_some());
+
+    let plan = plan_in_home(&store, &manifest, home.path()).unwrap();
+    assert_eq!(
+        actions(&plan.steps),
+        vec![
+            (ReconcileAction::Unchanged, None, None),
+            (
+                ReconcileAction::Skip,
+                Some("codex"),
+                Some("tool_not_installed")
+            ),
+        ]
+    );
+
+    // Local sources are not locked to their content: apply keeps the lock as it is.
+    write_skill(&team.path().join("skills/style"), "style", "v2");
+    apply_in_home(
+        app.handle(),
+        &store,
+        &manifest,
+        LocalChangesPolicy::Refuse,
+        home.path(),
+        false,
+    )
+    .unwrap();
+    assert_eq!(
+        load_lockfile(&team.path().join("skills.lock")).unwrap(),
+        lock
+    );
+
+    // A teammate committed a lockfile for newer content.
+    let mut newer = lock.clone();
+    newer.skills[0].content_hash = Some(hash_dir(&team.path().join("skills/style")).unwrap());
+    write_lockfile(&team.path().join("skills.lock"), &newer).unwrap();
+    let plan = plan_in_home(&store, &manifest, home.path()).unwrap();
+    assert_eq!(plan.steps[0].action, ReconcileAction::Update);
+    assert_eq!(plan.steps[0].reason.as_deref(), Some("lockfile_drift"));
+    apply_in_home(
+        app.handle(),
+        &store,
+        &manifest,
+        LocalChangesPolicy::Refuse,
+        home.path(),
+        false,
+    )
+    .unwrap();
+    let skill = store.list_skills().unwrap().remove(0);
+    assert!(
+        fs::read_to_string(Path::new(&skill.central_path).join("SKILL.md"))
+            .unwrap()
+            .ends_with("v2")
+    );
+    let lock = load_lockfile(&team.path().join("skills.lock")).unwrap();
+    assert_eq!(lock.skills[0].content_hash, skill.content_hash);
+
+    // Pointing the entry at another source moves the installed skill over.
+    write_skill(&team.path().join("vendor/style"), "style", "vendored");
+    fs::write(
+        &manifest,
+        "tools = [\"cursor\"]\n\n[[skill]]\nname = \"style\"\nsource = \"./vendor/style\"\n",
+    )
+    .unwrap();
+    let plan = plan_in_home(&store, &manifest, home.path()).unwrap();
+    assert_eq!(
+        actions(&plan.steps),
+        vec![(ReconcileAction::Update, None, Some("source_changed"))]
+    );
+    apply_in_home(
+        app.handle(),
+        &store,
+        &manifest,
+        LocalChangesPolicy::Refuse,
+        home.path(),
+        false,
+    )
+    .unwrap();
+    let skill = store.list_skills().unwrap().remove(0);
+    assert!(skill.source_ref.unwrap().ends_with("vendor/style"));
+    assert!(
+        fs::read_to_string(Path::new(&skill.central_path).join("SKILL.md"))
+            .unwrap()
+            .ends_with("vendored")
+    );
+}
+
+#[test]
+fn failed_install_keeps_going_and_skips_its_syncs() {
+    let app = tauri::test::mock_app();
+    let (_dir, store) = make_store();
+    let central = tempfile::tempdir().unwrap();
+    store
+        .set_setting(
+            "central_repo_path",
+            central.path().to_string_lossy().as_ref(),
+        )
+        .unwrap();
+    let home = tempfile::tempdir().unwrap();
+    fs::create_dir_all(home.path().join(".cursor")).unwrap();
+
+    let team = tempfile::tempdir().unwrap();
+    write_skill(&team.path().join("ok"), "ok", "");
+    let manifest = team.path().join("skills.toml");
+    fs::write(
+        &manifest,
+        "tools = [\"cursor\"]\n\n[[skill]]\nname = \"missing\"\nsource = \"./missing\"\n\n[[skill]]\nname = \"ok\"\nsource = \"./ok\"\n",
+    )
+    .unwrap();
+
+    let report = apply_in_home(
+        app.handle(),
+        &store,
+        team.path(),
+        LocalChangesPolicy::Refuse,
+        home.path(),
+        false,
+    )
+    .unwrap();
+    let errors: Vec<_> = report
+        .steps
+        .iter()
+        .map(|s| (s.step.skill.as_str(), s.error.is_some()))
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            ("missing", true),
+            ("missing", true),
+            ("ok", false),
+            ("ok", false)
+        ]
+    );
+    let lock = load_lockfile(&team.path().join("skills.lock")).unwrap();
+    let names: Vec<_> = lock.skills.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["ok"]);
+}
+
+#[test]
+fn apply_stays_on_locked_revision_until_lock_is_updated() {
+    let app = tauri::test::mock_app();
+    let (_dir, _central, store) = central_store();
+    set_git_cache_ttl_secs(&store, 0).unwrap();
+    let home = tempfile::tempdir().unwrap();
+
+    let upstream = tempfile::tempdir().unwrap();
+    write_skill(upstream.path(), "style", "v1");
+    let c1 = commit_all(upstream.path(), "v1");
+    let team = tempfile::tempdir().unwrap();
+    let manifest = team.path().join("skills.toml");
+    fs::write(
+        &manifest,
+        format!(
+            "tools = []\n\n[[skill]]\nname = \"style\"\nsource = \"{}\"\ntype = \"git\"\n",
+            upstream.path().to_string_lossy()
+        ),
+    )
+    .unwrap();
+    let apply = |store: &SkillStore, update_lock: bool| {
+        let report = apply_in_home(
+            app.handle(),
+            store,
+            &manifest,
+            LocalChangesPolicy::Refuse,
+            home.path(),
+            update_lock,
+        )
+        .unwrap();
+        assert!(
+            report.steps.iter().all(|s| s.error.is_none()),
+            "{:?}",
+            report
+        );
+    };
+    let lock_path = team.path().join("skills.lock");
+
+    apply(&store, false);
+    let lock = load_lockfile(&lock_path).unwrap();
+    assert_eq!(lock.skills[0].source_revision.as_deref(), Some(c1.as_str()));
+
+    // Upstream moves on after the lock was committed.
+    write_skill(upstream.path(), "style", "v2");
+    let c2 = commit_all(upstream.path(), "v2");
+
+    // A teammate applying the manifest gets the locked revision, not the new head.
+    let (_dir2, _central2, teammate) = central_store();
+    set_git_cache_ttl_secs(&teammate, 0).unwrap();
+    apply(&teammate, false);
+    assert!(installed_body(&teammate).ends_with("v1"));
+    assert_eq!(
+        teammate.list_skills().unwrap()[0]
+            .source_revision
+            .as_deref(),
+        Some(c1.as_str())
+    );
+
+    // Re-applying does not move either the skill or the lock.
+    apply(&store, false);
+    assert!(installed_body(&store).ends_with("v1"));
+    assert_eq!(load_lockfile(&lock_path).unwrap(), lock);
+
+    // Only updating the lock advances it, and the skill with it.
+    apply(&store, true);
+    assert!(installed_body(&store).ends_with("v2"));
+    let lock = load_lockfile(&lock_path).unwrap();
+    assert_eq!(lock.skills[0].source_revision.as_deref(), Some(c2.as_str()));
+    let plan = plan_in_home(&teammate, &manifest, home.path()).unwrap();
+    assert_eq!(
+        actions(&plan.steps),
+        vec![(ReconcileAction::Update, None, Some("lockfile_drift"))]
+    );
+}
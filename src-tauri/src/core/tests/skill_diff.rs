@@ -0,0 +1,90 @@
+use std::fs;
+
+use super::{compare_dirs, diff_dirs, FileChangeKind};
+
+#[test]
+fn reports_added_removed_and_modified_files() {
+    let old = tempfile::tempdir().unwrap();
+    let new = tempfile::tempdir().unwrap();
+
+    fs::write(old.path().join("SKILL.md"), "# Demo\nstep one\n").unwrap();
+    fs::write(new.path().join("SKILL.md"), "# Demo\nstep two\n").unwrap();
+    fs::write(old.path().join("gone.txt"), "bye\n").unwrap();
+    fs::create_dir_all(new.path().join("scripts")).unwrap();
+    fs::write(new.path().join("scripts/run.sh"), "echo hi\n").unwrap();
+    fs::write(old.path().join("same.txt"), "same\n").unwrap();
+    fs::write(new.path().join("same.txt"), "same\n").unwrap();
+
+    let diff = diff_dirs(old.path(), new.path()).unwrap();
+    let summary: Vec<(&str, FileChangeKind)> = diff
+        .files
+        .iter()
+        .map(|f| (f.path.as_str(), f.kind))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("SKILL.md", FileChangeKind::Modified),
+            ("gone.txt", FileChangeKind::Removed),
+            ("scripts/run.sh", FileChangeKind::Added),
+        ]
+    );
+
+    let skill_md = diff.files[0].unified_diff.as_deref().unwrap();
+    assert!(skill_md.contains("--- a/SKILL.md"));
+    assert!(skill_md.contains("+++ b/SKILL.md"));
+    assert!(skill_md.contains("-step one"));
+    assert!(skill_md.contains("+step two"));
+
+    let added = diff.files[2].unified_diff.as_deref().unwrap();
+    assert!(added.contains("+echo hi"));
+}
+
+#[test]
+fn binary_files_have_no_text_diff_and_ignored_names_are_skipped() {
+    let old = tempfile::tempdir().unwrap();
+    let new = tempfile::tempdir().unwrap();
+
+    fs::write(old.path().join("icon.png"), [0u8, 1, 2]).unwrap();
+    fs::write(new.path().join("icon.png"), [0u8, 1, 3]).unwrap();
+    fs::write(new.path().join(".DS_Store"), "junk").unwrap();
+    fs::create_dir_all(new.path().join(".git")).unwrap();
+    fs::write(new.path().join(".git/HEAD"), "ref").unwrap();
+
+    let diff = diff_dirs(old.path(), new.path()).unwrap();
+    assert_eq!(diff.files.len(), 1);
+    assert_eq!(diff.files[0].path, "icon.png");
+    assert!(diff.files[0].binary);
+    assert!(diff.files[0].unified_diff.is_none());
+}
+
+#[test]
+fn identical_or_missing_dirs() {
+    let a = tempfile::tempdir().unwrap();
+    fs::write(a.path().join("SKILL.md"), "x").unwrap();
+    assert!(diff_dirs(a.path(), a.path()).unwrap().files.is_empty());
+
+    let missing = a.path().join("nope");
+    let diff = diff_dirs(&missing, a.path()).unwrap();
+    assert_eq!(diff.files.len(), 1);
+    assert_eq!(diff.files[0].kind, FileChangeKind::Added);
+}
+
+#[test]
+fn compares_files_across_several_dirs() {
+    let dirs: Vec<tempfile::TempDir> = (0..3).map(|_| tempfile::tempdir().unwrap()).collect();
+    for (dir, body) in dirs.iter().zip(["one\n", "two\n", "one\n"]) {
+        fs::write(dir.path().join("SKILL.md"), body).unwrap();
+    }
+    fs::write(dirs[1].path().join("extra.txt"), "only here\n").unwrap();
+
+    let paths: Vec<&std::path::Path> = dirs.iter().map(|d| d.path()).collect();
+    let compared = compare_dirs(&paths).unwrap();
+    assert_eq!(compared.len(), 2);
+    assert_eq!(compared[0].path, "SKILL.md");
+    assert_eq!(compared[0].present_in, vec![0, 1, 2]);
+    assert_eq!(compared[0].content_groups, vec![vec![0, 2], vec![1]]);
+    assert_eq!(compared[1].path, "extra.txt");
+    assert_eq!(compared[1].present_in, vec![1]);
+    assert_eq!(compared[1].content_groups, vec![vec![1]]);
+}
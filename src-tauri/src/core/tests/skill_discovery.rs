@@ -0,0 +1,94 @@
+use std::fs;
+use std::path::Path;
+
+use super::{discover_skill_dirs, FoundSkillDir, INDEX_FILE};
+
+fn write_skill(dir: &Path) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(dir.join("SKILL.md"), "---\nname: x\n---\n").unwrap();
+}
+
+fn summary(found: &[FoundSkillDir]) -> Vec<(String, String, usize, bool)> {
+    found
+        .iter()
+        .map(|f| (f.subpath.clone(), f.root.clone(), f.depth, f.has_skill_md))
+        .collect()
+}
+
+fn entry(
+    subpath: &str,
+    root: &str,
+    depth: usize,
+    has_skill_md: bool,
+) -> (String, String, usize, bool) {
+    (subpath.to_string(), root.to_string(), depth, has_skill_md)
+}
+
+#[test]
+fn finds_nested_skills_and_respects_gitignore() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path();
+    write_skill(base);
+    write_skill(&base.join("skills/a"));
+    write_skill(&base.join("skills/.curated/b"));
+    write_skill(&base.join("plugins/x/skills/y"));
+    // Skills do not nest, so this one is part of `y`.
+    write_skill(&base.join("plugins/x/skills/y/examples/z"));
+    fs::create_dir_all(base.join("skills/empty")).unwrap();
+    write_skill(&base.join("build/skills/generated"));
+    fs::write(base.join(".gitignore"), "build/\n").unwrap();
+    write_skill(&base.join(".git/skills/internal"));
+
+    let found = discover_skill_dirs(base, None).unwrap();
+    assert_eq!(
+        summary(&found),
+        vec![
+            entry(".", ".", 0, true),
+            entry("plugins/x/skills/y", ".", 4, true),
+            entry("skills/.curated/b", ".", 3, true),
+            entry("skills/a", ".", 2, true),
+            entry("skills/empty", ".", 2, false),
+        ]
+    );
+
+    // The depth limit cuts off deeper skills.
+    let shallow = discover_skill_dirs(base, Some(2)).unwrap();
+    assert!(shallow.iter().all(|f| f.subpath != "plugins/x/skills/y"));
+    assert!(shallow.iter().any(|f| f.subpath == "skills/a"));
+}
+
+#[test]
+fn index_file_sets_roots_or_lists_skills() {
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path();
+    write_skill(&base.join("skills/a"));
+    write_skill(&base.join("packages/p/skills/b"));
+
+    fs::write(
+        base.join(INDEX_FILE),
+        "roots = [\"packages\"]\nmax_depth = 3\n",
+    )
+    .unwrap();
+    let found = discover_skill_dirs(base, None).unwrap();
+    assert_eq!(
+        summary(&found),
+        vec![entry("packages/p/skills/b", "packages", 3, true)]
+    );
+
+    fs::write(
+        base.join(INDEX_FILE),
+        "skills = [\"skills/a\", \"skills/missing\", \"../outside\"]\n",
+    )
+    .unwrap();
+    let found = discover_skill_dirs(base, None).unwrap();
+    assert_eq!(
+        summary(&found),
+        vec![
+            entry("skills/a", INDEX_FILE, 2, true),
+            entry("skills/missing", INDEX_FILE, 2, false),
+        ]
+    );
+
+    fs::write(base.join(INDEX_FILE), "paths = []\n").unwrap();
+    assert!(discover_skill_dirs(base, None).is_err());
+}
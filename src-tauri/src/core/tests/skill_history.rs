@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::Path;
+
+use crate::core::skill_store::SkillRecord;
+use crate::core::test_fixtures::{make_skill, make_store};
+
+use super::{prune_skill_snapshots, remove_skill_history_dir, snapshot_skill_dir};
+
+fn git_skill(central_path: &Path) -> SkillRecord {
+    SkillRecord {
+        source_type: "git".to_string(),
+        source_ref: Some("https://example.com/o/r.git".to_string()),
+        source_revision: Some("rev1".to_string()),
+        ..make_skill("s1", "S1", &central_path.to_string_lossy(), 1)
+    }
+}
+
+#[test]
+fn snapshot_copies_dir_and_records_revision() {
+    let (_dir, store) = make_store();
+    let central = tempfile::tempdir().unwrap();
+    let history = tempfile::tempdir().unwrap();
+    fs::write(central.path().join("SKILL.md"), b"v1").unwrap();
+
+    let skill = git_skill(central.path());
+    store.upsert_skill(&skill).unwrap();
+
+    let snap = snapshot_skill_dir(&store, history.path(), &skill).unwrap();
+    assert_eq!(snap.source_revision.as_deref(), Some("rev1"));
+    assert!(snap.content_hash.is_some());
+    assert_eq!(
+        fs::read(Path::new(&snap.snapshot_path).join("SKILL.md")).unwrap(),
+        b"v1"
+    );
+    assert!(Path::new(&snap.snapshot_path).starts_with(history.path().join("s1")));
+
+    let listed = store.list_skill_snapshots("s1").unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, snap.id);
+}
+
+#[test]
+fn snapshots_are_pruned_to_the_newest() {
+    let (_dir, store) = make_store();
+    let central = tempfile::tempdir().unwrap();
+    let history = tempfile::tempdir().unwrap();
+    let skill = git_skill(central.path());
+    store.upsert_skill(&skill).unwrap();
+
+    let mut created = Vec::new();
+    for i in 0..(super::MAX_SNAPSHOTS_PER_SKILL + 2) {
+        fs::write(central.path().join("SKILL.md"), format!("v{i}")).unwrap();
+        created.push(snapshot_skill_dir(&store, history.path(), &skill).unwrap());
+    }
+
+    let listed = store.list_skill_snapshots("s1").unwrap();
+    assert_eq!(listed.len(), super::MAX_SNAPSHOTS_PER_SKILL);
+    assert_eq!(listed[0].id, created.last().unwrap().id, "newest first");
+    assert!(!Path::new(&created[0].snapshot_path).exists());
+    assert!(!Path::new(&created[1].snapshot_path).exists());
+    assert!(Path::new(&created[2].snapshot_path).exists());
+
+    assert_eq!(prune_skill_snapshots(&store, "s1", 1).unwrap(), 4);
+    assert_eq!(store.list_skill_snapshots("s1").unwrap().len(), 1);
+}
+
+#[test]
+fn removing_history_dir_drops_all_snapshots_of_skill() {
+    let (_dir, store) = make_store();
+    let central = tempfile::tempdir().unwrap();
+    let history = tempfile::tempdir().unwrap();
+    let skill = git_skill(central.path());
+    store.upsert_skill(&skill).unwrap();
+    snapshot_skill_dir(&store, history.path(), &skill).unwrap();
+
+    remove_skill_history_dir(history.path(), "s1").unwrap();
+    assert!(!history.path().join("s1").exists());
+    // Missing dir is fine.
+    remove_skill_history_dir(history.path(), "s1").unwrap();
+
+    store.delete_skill("s1").unwrap();
+    assert!(store.list_skill_snapshots("s1").unwrap().is_empty());
+}
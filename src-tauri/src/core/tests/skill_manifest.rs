@@ -0,0 +1,118 @@
+use std::fs;
+
+use super::{backfill_skill_manifests, split_frontmatter, SkillManifest};
+use crate::core::test_fixtures::{make_skill, make_store};
+
+#[test]
+fn parses_full_yaml_frontmatter() {
+    let text = r#"---
+name: "pdf-tools"
+description: >
+  Fill and merge PDFs.
+  Use when: the user mentions forms.
+version: 1.10
+license: Apache-2.0
+allowed-tools: Bash(git add:*), Read Grep
+author:
+  name: Ada
+  email: ada@example.com
+metadata:
+  category: documents
+  tags: [pdf, forms]
+extra: ignored
+---
+
+# PDF
+"#;
+    let manifest = SkillManifest::parse(text).unwrap();
+    assert_eq!(manifest.name, "pdf-tools");
+    assert_eq!(
+        manifest.description.as_deref(),
+        Some("Fill and merge PDFs. Use when: the user mentions forms.")
+    );
+    assert_eq!(manifest.version.as_deref(), Some("1.1"));
+    assert_eq!(manifest.license.as_deref(), Some("Apache-2.0"));
+    assert_eq!(
+        manifest.allowed_tools,
+        vec!["Bash(git add:*)", "Read", "Grep"]
+    );
+    assert_eq!(manifest.author.as_deref(), Some("Ada"));
+    assert_eq!(manifest.metadata["category"], "documents");
+    assert_eq!(
+        manifest.metadata["tags"],
+        serde_json::json!(["pdf", "forms"])
+    );
+
+    let listed = SkillManifest::parse(
+        "---\nname: x\nallowed-tools:\n  - Read\n  - Write\nmetadata:\n  author: Bo\n---\n",
+    )
+    .unwrap();
+    assert_eq!(listed.allowed_tools, vec!["Read", "Write"]);
+    assert_eq!(listed.author.as_deref(), Some("Bo"));
+}
+
+#[test]
+fn reports_errors_with_positions() {
+    let err = SkillManifest::parse("---\nname: x\ndescription: [unclosed\n---\n").unwrap_err();
+    assert_eq!(err.reason, "invalid_frontmatter");
+    assert!(
+        err.line.unwrap() >= 3,
+        "position is in file lines: {:?}",
+        err
+    );
+    assert!(err.column.is_some());
+
+    let err = SkillManifest::parse("# no frontmatter\n").unwrap_err();
+    assert_eq!(err.reason, "invalid_frontmatter");
+    assert_eq!(err.line, None);
+
+    let err = SkillManifest::parse("---\ndescription: D\n---\n").unwrap_err();
+    assert_eq!(err.reason, "missing_name");
+    assert_eq!(
+        SkillManifest::parse_unchecked("---\ndescription: D\n---\n")
+            .unwrap()
+            .description
+            .as_deref(),
+        Some("D")
+    );
+
+    let err = SkillManifest::parse("---\nname: [a, b]\n---\n").unwrap_err();
+    assert_eq!(err.reason, "invalid_frontmatter");
+}
+
+#[test]
+fn splits_frontmatter_from_body() {
+    assert_eq!(
+        split_frontmatter("---\nname: x\n---\n\nbody\n"),
+        Some(("name: x\n", "\nbody\n"))
+    );
+    assert_eq!(
+        split_frontmatter("---\r\nname: x\r\n---\r\nbody"),
+        Some(("name: x\r\n", "body"))
+    );
+    assert_eq!(split_frontmatter("---\nname: x\n"), None);
+}
+
+#[test]
+fn backfills_manifests_for_stored_skills() {
+    let (dir, store) = make_store();
+    let central = dir.path().join("central/demo");
+    fs::create_dir_all(&central).unwrap();
+    fs::write(
+        central.join("SKILL.md"),
+        "---\nname: demo\nversion: 2.0.1\n---\n",
+    )
+    .unwrap();
+    store
+        .upsert_skill(&make_skill("s1", "demo", &central.to_string_lossy(), 1))
+        .unwrap();
+
+    assert_eq!(backfill_skill_manifests(&store).unwrap(), 1);
+    let manifest = store.list_skill_manifests().unwrap().remove("s1").unwrap();
+    assert_eq!(manifest.version.as_deref(), Some("2.0.1"));
+    // Already stored: nothing to do.
+    assert_eq!(backfill_skill_manifests(&store).unwrap(), 0);
+
+    store.delete_skill("s1").unwrap();
+    assert!(store.list_skill_manifests().unwrap().is_empty());
+}
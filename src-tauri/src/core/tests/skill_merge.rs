@@ -0,0 +1,99 @@
+use std::fs;
+use std::path::Path;
+
+use super::{has_local_changes, merge_into_upstream};
+use crate::core::content_hash::hash_dir;
+use crate::core::skill_store::SkillRecord;
+use crate::core::test_fixtures::make_skill;
+
+fn write(root: &Path, rel: &str, content: &str) {
+    let path = root.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn skill_at(central: &Path, content_hash: Option<String>) -> SkillRecord {
+    SkillRecord {
+        content_hash,
+        ..make_skill("s1", "s1", &central.to_string_lossy(), 1)
+    }
+}
+
+#[test]
+fn detects_drift_from_recorded_hash() {
+    let central = tempfile::tempdir().unwrap();
+    write(central.path(), "SKILL.md", "v1");
+    let hash = hash_dir(central.path()).unwrap();
+
+    assert!(!has_local_changes(&skill_at(central.path(), Some(hash.clone()))).unwrap());
+    write(central.path(), "SKILL.md", "v1 edited");
+    assert!(has_local_changes(&skill_at(central.path(), Some(hash))).unwrap());
+
+    // Without a recorded hash there is nothing to compare against.
+    assert!(!has_local_changes(&skill_at(central.path(), None)).unwrap());
+}
+
+#[test]
+fn merges_non_overlapping_edits_and_marks_conflicts() {
+    let base = tempfile::tempdir().unwrap();
+    let local = tempfile::tempdir().unwrap();
+    let upstream = tempfile::tempdir().unwrap();
+
+    let doc = "line 1\nline 2\nline 3\nline 4\nline 5\n";
+    for dir in [base.path(), local.path(), upstream.path()] {
+        write(dir, "SKILL.md", doc);
+        write(dir, "notes.txt", "same\n");
+        write(dir, "both.txt", "original\n");
+    }
+    write(
+        local.path(),
+        "SKILL.md",
+        "line 1 local\nline 2\nline 3\nline 4\nline 5\n",
+    );
+    write(
+        upstream.path(),
+        "SKILL.md",
+        "line 1\nline 2\nline 3\nline 4\nline 5 upstream\n",
+    );
+    write(local.path(), "both.txt", "local\n");
+    write(upstream.path(), "both.txt", "upstream\n");
+    write(local.path(), "extra/local-only.md", "mine\n");
+    fs::remove_file(local.path().join("notes.txt")).unwrap();
+
+    let report = merge_into_upstream(base.path(), local.path(), upstream.path()).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(upstream.path().join("SKILL.md")).unwrap(),
+        "line 1 local\nline 2\nline 3\nline 4\nline 5 upstream\n"
+    );
+    assert_eq!(
+        fs::read_to_string(upstream.path().join("extra/local-only.md")).unwrap(),
+        "mine\n"
+    );
+    assert!(!upstream.path().join("notes.txt").exists());
+    let both = fs::read_to_string(upstream.path().join("both.txt")).unwrap();
+    assert!(both.contains("<<<<<<<") && both.contains("local") && both.contains("upstream"));
+
+    assert_eq!(
+        report.merged,
+        vec!["SKILL.md", "extra/local-only.md", "notes.txt"]
+    );
+    assert_eq!(report.conflicts, vec!["both.txt"]);
+}
+
+#[test]
+fn binary_conflict_keeps_local_version() {
+    let base = tempfile::tempdir().unwrap();
+    let local = tempfile::tempdir().unwrap();
+    let upstream = tempfile::tempdir().unwrap();
+    fs::write(base.path().join("icon.png"), [0u8, 1]).unwrap();
+    fs::write(local.path().join("icon.png"), [0u8, 2]).unwrap();
+    fs::write(upstream.path().join("icon.png"), [0u8, 3]).unwrap();
+
+    let report = merge_into_upstream(base.path(), local.path(), upstream.path()).unwrap();
+    assert_eq!(report.conflicts, vec!["icon.png"]);
+    assert_eq!(
+        fs::read(upstream.path().join("icon.png")).unwrap(),
+        [0u8, 2]
+    );
+}
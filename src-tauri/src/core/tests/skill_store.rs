@@ -1,31 +1,10 @@
 use std::path::PathBuf;
 
-use crate::core::skill_store::{SkillRecord, SkillStore, SkillTargetRecord};
-
-fn make_store() -> (tempfile::TempDir, SkillStore) {
-    let dir = tempfile::tempdir().expect("tempdir");
-    let db = dir.path().join("test.db");
-    let store = SkillStore::new(db);
-    store.ensure_schema().expect("ensure_schema");
-    (dir, store)
-}
-
-fn make_skill(id: &str, name: &str, central_path: &str, updated_at: i64) -> SkillRecord {
-    SkillRecord {
-        id: id.to_string(),
-        name: name.to_string(),
-        source_type: "local".to_string(),
-        source_ref: Some("/tmp/source".to_string()),
-        source_revision: None,
-        central_path: central_path.to_string(),
-        content_hash: None,
-        created_at: 1,
-        updated_at,
-        last_sync_at: None,
-        last_seen_at: 1,
-        status: "ok".to_string(),
-    }
-}
+use crate::core::skill_manifest::SkillManifest;
+use crate::core::skill_store::{
+    DiscoveredSkillRecord, OperationRecord, SkillStore, SkillTargetRecord,
+};
+use crate::core::test_fixtures::{make_skill, make_store};
 
 #[test]
 fn schema_is_idempotent() {
@@ -99,16 +78,18 @@ fn skill_targets_upsert_unique_constraint_and_list_order() {
         id: "t1".to_string(),
         skill_id: "s1".to_string(),
         tool: "cursor".to_string(),
+        scope: "global".to_string(),
         target_path: "/target/1".to_string(),
         mode: "copy".to_string(),
         status: "ok".to_string(),
         last_error: None,
         synced_at: None,
+        rendered_hash: None,
     };
     store.upsert_skill_target(&t1).unwrap();
     assert_eq!(
         store
-            .get_skill_target("s1", "cursor")
+            .get_skill_target("s1", "cursor", "global")
             .unwrap()
             .unwrap()
             .target_path,
@@ -120,13 +101,17 @@ fn skill_targets_upsert_unique_constraint_and_list_order() {
     t1b.target_path = "/target/2".to_string();
     store.upsert_skill_target(&t1b).unwrap();
     assert_eq!(
-        store.get_skill_target("s1", "cursor").unwrap().unwrap().id,
+        store
+            .get_skill_target("s1", "cursor", "global")
+            .unwrap()
+            .unwrap()
+            .id,
         "t1",
-        "unique(skill_id, tool) 冲突时应更新现有行而不是替换 id"
+        "unique(skill_id, tool, scope) 冲突时应更新现有行而不是替换 id"
     );
     assert_eq!(
         store
-            .get_skill_target("s1", "cursor")
+            .get_skill_target("s1", "cursor", "global")
             .unwrap()
             .unwrap()
             .target_path,
@@ -137,11 +122,13 @@ fn skill_targets_upsert_unique_constraint_and_list_order() {
         id: "t3".to_string(),
         skill_id: "s1".to_string(),
         tool: "claude_code".to_string(),
+        scope: "global".to_string(),
         target_path: "/target/cc".to_string(),
         mode: "copy".to_string(),
         status: "ok".to_string(),
         last_error: None,
         synced_at: None,
+        rendered_hash: None,
     };
     store.upsert_skill_target(&t2).unwrap();
 
@@ -150,8 +137,11 @@ fn skill_targets_upsert_unique_constraint_and_list_order() {
     assert_eq!(targets[0].tool, "claude_code");
     assert_eq!(targets[1].tool, "cursor");
 
-    store.delete_skill_target("s1", "cursor").unwrap();
-    assert!(store.get_skill_target("s1", "cursor").unwrap().is_none());
+    store.delete_skill_target("s1", "cursor", "global").unwrap();
+    assert!(store
+        .get_skill_target("s1", "cursor", "global")
+        .unwrap()
+        .is_none());
 }
 
 #[test]
@@ -164,11 +154,13 @@ fn deleting_skill_cascades_targets() {
         id: "t1".to_string(),
         skill_id: "s1".to_string(),
         tool: "cursor".to_string(),
+        scope: "global".to_string(),
         target_path: "/target/1".to_string(),
         mode: "copy".to_string(),
         status: "ok".to_string(),
         last_error: None,
         synced_at: None,
+        rendered_hash: None,
     };
     store.upsert_skill_target(&t).unwrap();
     assert_eq!(store.list_skill_targets("s1").unwrap().len(), 1);
@@ -177,6 +169,56 @@ fn deleting_skill_cascades_targets() {
     assert_eq!(store.list_skill_targets("s1").unwrap().len(), 0);
 }
 
+#[test]
+fn list_skills_with_targets_joins_in_one_pass() {
+    let (_dir, store) = make_store();
+    store
+        .upsert_skill(&make_skill("a", "A", "/central/a", 10))
+        .unwrap();
+    store
+        .upsert_skill(&make_skill("b", "B", "/central/b", 20))
+        .unwrap();
+
+    for (id, tool) in [("t1", "cursor"), ("t2", "claude_code")] {
+        store
+            .upsert_skill_target(&SkillTargetRecord {
+                id: id.to_string(),
+                skill_id: "b".to_string(),
+                tool: tool.to_string(),
+                scope: "global".to_string(),
+                target_path: format!("/target/{tool}"),
+                mode: "copy".to_string(),
+                status: "ok".to_string(),
+                last_error: None,
+                synced_at: None,
+                rendered_hash: None,
+            })
+            .unwrap();
+    }
+
+    let listed = store.list_skills_with_targets().unwrap();
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[0].skill.id, "b");
+    let tools: Vec<&str> = listed[0].targets.iter().map(|t| t.tool.as_str()).collect();
+    assert_eq!(tools, vec!["claude_code", "cursor"]);
+    assert_eq!(listed[1].skill.id, "a");
+    assert!(listed[1].targets.is_empty());
+}
+
+#[test]
+fn clones_share_one_wal_connection() {
+    let (_dir, store) = make_store();
+    let clone = store.clone();
+    clone.set_setting("k", "v").unwrap();
+    assert_eq!(store.get_setting("k").unwrap().as_deref(), Some("v"));
+    assert!(std::sync::Arc::ptr_eq(&store.conn, &clone.conn));
+
+    let mode: String = store
+        .with_conn(|conn| Ok(conn.query_row("PRAGMA journal_mode;", [], |row| row.get(0))?))
+        .unwrap();
+    assert_eq!(mode.to_ascii_lowercase(), "wal");
+}
+
 #[test]
 fn error_context_includes_db_path() {
     let store = SkillStore::new(PathBuf::from("/this/path/should/not/exist/test.db"));
@@ -184,3 +226,408 @@ fn error_context_includes_db_path() {
     let msg = format!("{:#}", err);
     assert!(msg.contains("failed to open db at"), "{msg}");
 }
+
+fn user_version(conn: &rusqlite::Connection) -> i32 {
+    conn.query_row("PRAGMA user_version;", [], |row| row.get(0))
+        .unwrap()
+}
+
+fn list_backups(dir: &std::path::Path) -> Vec<String> {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| name.contains(".bak-v"))
+        .collect()
+}
+
+fn add_note_column(conn: &rusqlite::Connection) -> anyhow::Result<()> {
+    conn.execute_batch("ALTER TABLE skills ADD COLUMN note TEXT NULL;")?;
+    Ok(())
+}
+
+fn add_extra_table(conn: &rusqlite::Connection) -> anyhow::Result<()> {
+    conn.execute_batch("CREATE TABLE extra (id TEXT PRIMARY KEY);")?;
+    Ok(())
+}
+
+fn broken_step(conn: &rusqlite::Connection) -> anyhow::Result<()> {
+    conn.execute_batch("CREATE TABLE half_done (id TEXT);")?;
+    anyhow::bail!("boom")
+}
+
+#[test]
+fn fresh_db_migrates_to_latest_without_backup() {
+    let (dir, store) = make_store();
+    let conn = rusqlite::Connection::open(store.db_path()).unwrap();
+    assert_eq!(user_version(&conn), super::SCHEMA_VERSION);
+    assert!(list_backups(dir.path()).is_empty());
+}
+
+#[test]
+fn migrations_are_ordered_and_cover_schema_version() {
+    let versions: Vec<i32> = super::MIGRATIONS.iter().map(|m| m.version).collect();
+    assert_eq!(versions.first(), Some(&1));
+    assert_eq!(versions.last(), Some(&super::SCHEMA_VERSION));
+    assert!(versions.windows(2).all(|w| w[1] == w[0] + 1));
+}
+
+/// A database as an app that only knew schema v1 would have left it.
+fn make_v1_db(dir: &std::path::Path) -> std::path::PathBuf {
+    let db = dir.join("test.db");
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    super::migrate_v1(&conn).unwrap();
+    conn.pragma_update(None, "user_version", 1).unwrap();
+    conn.execute(
+        "INSERT INTO skills (id, name, source_type, central_path, created_at, updated_at, last_seen_at, status)
+         VALUES ('a', 'A', 'local', '/central/a', 1, 1, 1, 'ok')",
+        [],
+    )
+    .unwrap();
+    db
+}
+
+/// A v1 database migrated up to `version` only, as an app release at that schema would
+/// have left it.
+fn make_db_at(dir: &std::path::Path, version: i32) -> std::path::PathBuf {
+    let db = make_v1_db(dir);
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    super::run_migrations(&conn, &db, super::MIGRATIONS, version).unwrap();
+    assert_eq!(user_version(&conn), version);
+    db
+}
+
+#[test]
+fn runner_applies_pending_steps_and_backs_up_existing_db() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = make_v1_db(dir.path());
+
+    let steps = [
+        super::Migration {
+            version: 1,
+            apply: super::migrate_v1,
+        },
+        super::Migration {
+            version: 2,
+            apply: add_note_column,
+        },
+        super::Migration {
+            version: 3,
+            apply: add_extra_table,
+        },
+    ];
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    super::run_migrations(&conn, &db, &steps, 3).unwrap();
+    assert_eq!(user_version(&conn), 3);
+
+    let note: Option<String> = conn
+        .query_row("SELECT note FROM skills WHERE id = 'a'", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert!(note.is_none(), "existing rows survive the new column");
+
+    let backups = list_backups(dir.path());
+    assert_eq!(backups.len(), 1, "{backups:?}");
+    assert!(backups[0].contains(".bak-v1-"));
+
+    // Backup holds the pre-migration schema and data.
+    let backup = rusqlite::Connection::open(dir.path().join(&backups[0])).unwrap();
+    assert_eq!(user_version(&backup), 1);
+    let count: i64 = backup
+        .query_row("SELECT COUNT(*) FROM skills", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 1);
+
+    // Re-running at the target version is a no-op and takes no new backup.
+    super::run_migrations(&conn, &db, &steps, 3).unwrap();
+    assert_eq!(list_backups(dir.path()).len(), 1);
+}
+
+#[test]
+fn failed_step_rolls_back_to_previous_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = make_v1_db(dir.path());
+    let steps = [
+        super::Migration {
+            version: 1,
+            apply: super::migrate_v1,
+        },
+        super::Migration {
+            version: 2,
+            apply: add_note_column,
+        },
+        super::Migration {
+            version: 3,
+            apply: broken_step,
+        },
+    ];
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    let err = super::run_migrations(&conn, &db, &steps, 3).unwrap_err();
+    assert!(format!("{:#}", err).contains("to v3"), "{err:#}");
+
+    assert_eq!(user_version(&conn), 2, "v2 committed, v3 rolled back");
+    let half_done: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE name = 'half_done'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(half_done, 0);
+}
+
+#[test]
+fn newer_schema_version_is_rejected() {
+    let (_dir, store) = make_store();
+    let conn = rusqlite::Connection::open(store.db_path()).unwrap();
+    conn.pragma_update(None, "user_version", super::SCHEMA_VERSION + 1)
+        .unwrap();
+    let err = store.ensure_schema().unwrap_err();
+    assert!(format!("{:#}", err).contains("is newer than app supports"));
+}
+
+#[test]
+fn v2_adds_skill_history_to_existing_v1_db() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = make_v1_db(dir.path());
+
+    let store = SkillStore::new(db);
+    store.ensure_schema().unwrap();
+    assert!(store.get_skill_by_id("a").unwrap().is_some());
+
+    store
+        .insert_skill_snapshot(&super::SkillSnapshotRecord {
+            id: "h1".to_string(),
+            skill_id: "a".to_string(),
+            content_hash: Some("hash".to_string()),
+            source_revision: None,
+            snapshot_path: "/history/a/h1".to_string(),
+            created_at: 5,
+        })
+        .unwrap();
+    assert_eq!(
+        store
+            .get_skill_snapshot("h1")
+            .unwrap()
+            .unwrap()
+            .snapshot_path,
+        "/history/a/h1"
+    );
+    assert_eq!(list_backups(dir.path()).len(), 1);
+}
+
+#[test]
+fn v3_adds_nullable_source_subpath() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = SkillStore::new(make_v1_db(dir.path()));
+    store.ensure_schema().unwrap();
+
+    let mut skill = store.get_skill_by_id("a").unwrap().unwrap();
+    assert_eq!(skill.source_subpath, None);
+
+    skill.source_subpath = Some("skills/a".to_string());
+    store.upsert_skill(&skill).unwrap();
+    assert_eq!(
+        store
+            .get_skill_by_id("a")
+            .unwrap()
+            .unwrap()
+            .source_subpath
+            .as_deref(),
+        Some("skills/a")
+    );
+}
+
+#[test]
+fn v4_adds_nullable_source_pin() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = SkillStore::new(make_v1_db(dir.path()));
+    store.ensure_schema().unwrap();
+
+    let mut skill = store.get_skill_by_id("a").unwrap().unwrap();
+    assert_eq!(skill.source_pin, None);
+
+    skill.source_pin = Some("v1.2.0".to_string());
+    store.upsert_skill(&skill).unwrap();
+    let listed = store.list_skills_with_targets().unwrap();
+    assert_eq!(listed[0].skill.source_pin.as_deref(), Some("v1.2.0"));
+}
+
+#[test]
+fn v5_scopes_targets_and_keeps_existing_ones_global() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = make_v1_db(dir.path());
+    {
+        let conn = rusqlite::Connection::open(&db).unwrap();
+        conn.execute(
+            "INSERT INTO skill_targets (id, skill_id, tool, target_path, mode, status)
+             VALUES ('t1', 'a', 'cursor', '/home/.cursor/skills/A', 'copy', 'ok')",
+            [],
+        )
+        .unwrap();
+    }
+    let store = SkillStore::new(db);
+    store.ensure_schema().unwrap();
+
+    let global = store
+        .get_skill_target("a", "cursor", "global")
+        .unwrap()
+        .unwrap();
+    assert_eq!(global.scope, "global");
+    assert_eq!(global.target_path, "/home/.cursor/skills/A");
+
+    // The same tool can also be synced into a project without replacing the global target.
+    let project = SkillTargetRecord {
+        id: "t2".to_string(),
+        scope: "/work/repo".to_string(),
+        target_path: "/work/repo/.cursor/skills/A".to_string(),
+        ..global.clone()
+    };
+    store.upsert_skill_target(&project).unwrap();
+    let targets = store.list_skill_targets("a").unwrap();
+    assert_eq!(targets.len(), 2);
+    assert_eq!(targets[0].scope, "/work/repo");
+    assert_eq!(targets[1].scope, "global");
+
+    store
+        .delete_skill_target("a", "cursor", "/work/repo")
+        .unwrap();
+    assert!(store
+        .get_skill_target("a", "cursor", "global")
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn v6_adds_nullable_rendered_hash() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = make_db_at(dir.path(), 5);
+    {
+        let conn = rusqlite::Connection::open(&db).unwrap();
+        conn.execute(
+            "INSERT INTO skill_targets (id, skill_id, tool, scope, target_path, mode, status)
+             VALUES ('t1', 'a', 'augment', 'global', '/home/.augment/rules/A.md', 'copy', 'ok')",
+            [],
+        )
+        .unwrap();
+    }
+    let store = SkillStore::new(db);
+    store.ensure_schema().unwrap();
+
+    let mut target = store
+        .get_skill_target("a", "augment", "global")
+        .unwrap()
+        .unwrap();
+    assert_eq!(target.rendered_hash, None);
+
+    target.rendered_hash = Some("abc".to_string());
+    store.upsert_skill_target(&target).unwrap();
+    assert_eq!(
+        store
+            .get_skill_target("a", "augment", "global")
+            .unwrap()
+            .unwrap()
+            .rendered_hash
+            .as_deref(),
+        Some("abc")
+    );
+}
+
+#[test]
+fn v7_adds_operation_journal() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = SkillStore::new(make_v1_db(dir.path()));
+    store.ensure_schema().unwrap();
+    assert!(store.list_operations().unwrap().is_empty());
+
+    let op = OperationRecord {
+        id: "op".to_string(),
+        kind: "copy_target".to_string(),
+        target_path: "/t".to_string(),
+        staged_path: "/.t.swap".to_string(),
+        backup_path: "/.t.old".to_string(),
+        phase: "staging".to_string(),
+        created_at: 1,
+    };
+    store.insert_operation(&op).unwrap();
+    store.set_operation_phase("op", "ready").unwrap();
+    let listed = store.list_operations().unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].phase, "ready");
+
+    store.delete_operation("op").unwrap();
+    assert!(store.list_operations().unwrap().is_empty());
+}
+
+#[test]
+fn v8_persists_discovered_skills_per_tool_and_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = SkillStore::new(make_v1_db(dir.path()));
+    store.ensure_schema().unwrap();
+    store
+        .upsert_skill(&make_skill("s1", "demo", "/central/demo", 1))
+        .unwrap();
+
+    let found = |id: &str, fingerprint: &str, found_at: i64| DiscoveredSkillRecord {
+        id: id.to_string(),
+        tool: "claude_code".to_string(),
+        found_path: "/home/.claude/skills/demo".to_string(),
+        name_guess: Some("demo".to_string()),
+        fingerprint: Some(fingerprint.to_string()),
+        dir_stamp: Some("stamp".to_string()),
+        found_at,
+        imported_skill_id: None,
+    };
+    store
+        .upsert_discovered_skill(&found("d1", "aaa", 1))
+        .unwrap();
+    store
+        .mark_discovered_imported("/home/.claude/skills/demo", "s1")
+        .unwrap();
+    // A rescan refreshes the fingerprint but keeps the first sighting and the import.
+    store
+        .upsert_discovered_skill(&found("d2", "bbb", 2))
+        .unwrap();
+
+    let listed = store.list_discovered_skills().unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, "d1");
+    assert_eq!(listed[0].found_at, 1);
+    assert_eq!(listed[0].fingerprint.as_deref(), Some("bbb"));
+    assert_eq!(listed[0].imported_skill_id.as_deref(), Some("s1"));
+
+    store.delete_skill("s1").unwrap();
+    assert_eq!(
+        store.list_discovered_skills().unwrap()[0].imported_skill_id,
+        None
+    );
+
+    store.delete_discovered_skill("d1").unwrap();
+    assert!(store.list_discovered_skills().unwrap().is_empty());
+}
+
+#[test]
+fn v9_adds_skill_manifests() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = SkillStore::new(make_db_at(dir.path(), 8));
+    store.ensure_schema().unwrap();
+    assert!(store.list_skill_manifests().unwrap().is_empty());
+
+    let manifest = SkillManifest {
+        name: "A".to_string(),
+        description: Some("does a".to_string()),
+        allowed_tools: vec!["Read".to_string(), "Bash".to_string()],
+        metadata: [("team".to_string(), serde_json::json!("core"))].into(),
+        ..Default::default()
+    };
+    store.upsert_skill_manifest("a", &manifest).unwrap();
+    assert_eq!(
+        store.list_skill_manifests().unwrap().get("a"),
+        Some(&manifest)
+    );
+
+    // Manifests go away with their skill.
+    store.delete_skill("a").unwrap();
+    assert!(store.list_skill_manifests().unwrap().is_empty());
+}
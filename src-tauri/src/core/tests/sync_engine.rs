@@ -1,9 +1,10 @@
 use std::fs;
 
 use crate::core::sync_engine::{
-    copy_dir_recursive, sync_dir_for_tool_with_overwrite, sync_dir_hybrid,
-    sync_dir_hybrid_with_overwrite, SyncMode,
+    copy_dir_recursive, sync_dir_for_tool_with_mode, sync_dir_hybrid,
+    sync_dir_hybrid_with_overwrite, sync_dir_tree_with_overwrite, SyncMode,
 };
+use crate::core::test_fixtures::make_store;
 
 #[test]
 fn copy_dir_recursive_skips_git_dir() {
@@ -61,6 +62,7 @@ fn hybrid_sync_with_overwrite_replaces_existing() {
 
 #[test]
 fn cursor_sync_forces_copy() {
+    let (_db, store) = make_store();
     let src_dir = tempfile::tempdir().unwrap();
     fs::create_dir_all(src_dir.path().join("s")).unwrap();
     fs::write(src_dir.path().join("s/a.txt"), b"ok").unwrap();
@@ -68,7 +70,8 @@ fn cursor_sync_forces_copy() {
     let dst_dir = tempfile::tempdir().unwrap();
     let target = dst_dir.path().join("t");
 
-    let out = sync_dir_for_tool_with_overwrite("cursor", src_dir.path(), &target, false).unwrap();
+    let out = sync_dir_for_tool_with_mode(&store, "cursor", src_dir.path(), &target, false, None)
+        .unwrap();
     assert!(matches!(out.mode_used, SyncMode::Copy));
     assert!(target.join("s/a.txt").exists());
     assert_eq!(fs::read(target.join("s/a.txt")).unwrap(), b"ok");
@@ -77,6 +80,7 @@ fn cursor_sync_forces_copy() {
 #[cfg(unix)]
 #[test]
 fn copy_overwrite_replaces_broken_symlink_target() {
+    let (_db, store) = make_store();
     use std::os::unix::fs::symlink;
 
     let src_dir = tempfile::tempdir().unwrap();
@@ -89,10 +93,114 @@ fn copy_overwrite_replaces_broken_symlink_target() {
     // Create a broken symlink at the target path.
     symlink(dst_dir.path().join("missing"), &target).unwrap();
 
-    let out = crate::core::sync_engine::sync_dir_copy_with_overwrite(src_dir.path(), &target, true)
-        .unwrap();
+    let out = crate::core::sync_engine::sync_dir_copy_with_overwrite(
+        &store,
+        src_dir.path(),
+        &target,
+        true,
+    )
+    .unwrap();
 
     assert!(matches!(out.mode_used, SyncMode::Copy));
     assert!(target.join("s/a.txt").exists());
     assert_eq!(fs::read(target.join("s/a.txt")).unwrap(), b"ok");
 }
+
+#[test]
+fn render_sync_is_idempotent_and_respects_overwrite() {
+    let (_db, store) = make_store();
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("pdf");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(
+        source.join("SKILL.md"),
+        "---\nname: pdf\ndescription: PDFs\n---\nv1\n",
+    )
+    .unwrap();
+    let rules = dir.path().join("rules");
+
+    let out =
+        sync_dir_for_tool_with_mode(&store, "augment", &source, &rules.join("pdf"), false, None)
+            .unwrap();
+    assert!(matches!(out.mode_used, SyncMode::Render));
+    assert_eq!(out.target_path, rules.join("pdf.md"));
+    assert!(fs::read_to_string(&out.target_path)
+        .unwrap()
+        .ends_with("v1\n"));
+    let first_hash = out.rendered_hash.clone().unwrap();
+    assert!(first_hash.starts_with("sha256:"));
+
+    // Same rendering: nothing to overwrite.
+    let again =
+        sync_dir_for_tool_with_mode(&store, "augment", &source, &rules.join("pdf"), false, None)
+            .unwrap();
+    assert!(!again.replaced);
+    assert_eq!(again.rendered_hash.as_deref(), Some(first_hash.as_str()));
+
+    fs::write(
+        source.join("SKILL.md"),
+        "---\nname: pdf\ndescription: PDFs\n---\nv2\n",
+    )
+    .unwrap();
+    let err =
+        sync_dir_for_tool_with_mode(&store, "augment", &source, &rules.join("pdf"), false, None)
+            .expect_err("changed rendering needs overwrite");
+    assert!(err.to_string().contains("target already exists"));
+
+    let updated =
+        sync_dir_for_tool_with_mode(&store, "augment", &source, &rules.join("pdf"), true, None)
+            .unwrap();
+    assert!(updated.replaced);
+    assert_ne!(updated.rendered_hash.unwrap(), first_hash);
+    assert!(fs::read_to_string(rules.join("pdf.md"))
+        .unwrap()
+        .ends_with("v2\n"));
+    // Replaced by renaming a finished file over it: no temp file is left behind.
+    assert_eq!(fs::read_dir(&rules).unwrap().count(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn hardlink_mode_links_every_file_and_reflink_falls_back_to_copy() {
+    let (_db, store) = make_store();
+    use std::os::unix::fs::MetadataExt;
+
+    let src_dir = tempfile::tempdir().unwrap();
+    fs::create_dir_all(src_dir.path().join("s/refs")).unwrap();
+    fs::write(src_dir.path().join("s/SKILL.md"), b"ok").unwrap();
+    fs::write(src_dir.path().join("s/refs/a.md"), b"a").unwrap();
+    let source = src_dir.path().join("s");
+
+    let dst_dir = tempfile::tempdir().unwrap();
+    let target = dst_dir.path().join("t");
+    let out = sync_dir_for_tool_with_mode(
+        &store,
+        "claude_code",
+        &source,
+        &target,
+        false,
+        Some(SyncMode::Hardlink),
+    )
+    .unwrap();
+    assert!(matches!(out.mode_used, SyncMode::Hardlink | SyncMode::Copy));
+    if matches!(out.mode_used, SyncMode::Hardlink) {
+        for file in ["SKILL.md", "refs/a.md"] {
+            assert_eq!(
+                fs::metadata(source.join(file)).unwrap().ino(),
+                fs::metadata(target.join(file)).unwrap().ino()
+            );
+        }
+    }
+
+    // Reflinks need btrfs/xfs/APFS; elsewhere the target is a plain copy.
+    let out =
+        sync_dir_tree_with_overwrite(&store, &source, &target, true, SyncMode::Reflink).unwrap();
+    assert!(out.replaced);
+    assert!(matches!(out.mode_used, SyncMode::Reflink | SyncMode::Copy));
+    assert_eq!(fs::read(target.join("refs/a.md")).unwrap(), b"a");
+    assert_ne!(
+        fs::metadata(source.join("SKILL.md")).unwrap().ino(),
+        fs::metadata(target.join("SKILL.md")).unwrap().ino()
+    );
+    assert_eq!(fs::read_dir(dst_dir.path()).unwrap().count(), 1);
+}
@@ -0,0 +1,196 @@
+use std::fs;
+use std::path::Path;
+
+use super::{repair_targets, verify_targets, TargetHealth};
+use crate::core::skill_store::{SkillStore, SkillTargetRecord};
+use crate::core::sync_engine::{sync_dir_copy_with_overwrite, sync_dir_for_tool_with_mode};
+use crate::core::test_fixtures::{make_skill, make_store};
+
+fn add_skill(store: &SkillStore, id: &str, central: &Path) {
+    fs::create_dir_all(central).unwrap();
+    fs::write(
+        central.join("SKILL.md"),
+        format!("---\nname: {}\ndescription: d\n---\nbody\n", id),
+    )
+    .unwrap();
+    store
+        .upsert_skill(&make_skill(id, id, &central.to_string_lossy(), 1))
+        .unwrap();
+}
+
+fn add_target(store: &SkillStore, skill_id: &str, tool: &str, central: &Path, target: &Path) {
+    let result = if tool == "copy_tool" {
+        sync_dir_copy_with_overwrite(store, central, target, false).unwrap()
+    } else {
+        sync_dir_for_tool_with_mode(store, tool, central, target, false, None).unwrap()
+    };
+    store
+        .upsert_skill_target(&SkillTargetRecord {
+            id: format!("{}-{}", skill_id, tool),
+            skill_id: skill_id.to_string(),
+            tool: tool.to_string(),
+            scope: "global".to_string(),
+            target_path: result.target_path.to_string_lossy().to_string(),
+            mode: result.mode_used.as_key().to_string(),
+            status: "ok".to_string(),
+            last_error: None,
+            synced_at: Some(1),
+            rendered_hash: result.rendered_hash,
+        })
+        .unwrap();
+}
+
+fn health_of(checks: &[super::TargetCheck], target_id: &str) -> TargetHealth {
+    checks
+        .iter()
+        .find(|c| c.target_id == target_id)
+        .unwrap()
+        .health
+}
+
+#[test]
+fn classifies_and_repairs_drifted_targets() {
+    let (dir, store) = make_store();
+    let central = dir.path().join("central");
+    let tools = dir.path().join("tools");
+
+    add_skill(&store, "healthy", &central.join("healthy"));
+    add_target(
+        &store,
+        "healthy",
+        "claude_code",
+        &central.join("healthy"),
+        &tools.join("claude/healthy"),
+    );
+
+    add_skill(&store, "gone", &central.join("gone"));
+    add_target(
+        &store,
+        "gone",
+        "claude_code",
+        &central.join("gone"),
+        &tools.join("claude/gone"),
+    );
+    fs::remove_file(tools.join("claude/gone")).ok();
+    fs::remove_dir_all(tools.join("claude/gone")).ok();
+
+    add_skill(&store, "edited", &central.join("edited"));
+    add_target(
+        &store,
+        "edited",
+        "cursor",
+        &central.join("edited"),
+        &tools.join("cursor/edited"),
+    );
+    fs::write(tools.join("cursor/edited/SKILL.md"), "local edit").unwrap();
+
+    add_skill(&store, "rule", &central.join("rule"));
+    add_target(
+        &store,
+        "rule",
+        "augment",
+        &central.join("rule"),
+        &tools.join("augment/rule"),
+    );
+    fs::write(tools.join("augment/rule.md"), "edited rule").unwrap();
+
+    let checks = verify_targets(&store).unwrap();
+    assert_eq!(
+        health_of(&checks, "healthy-claude_code"),
+        TargetHealth::Healthy
+    );
+    assert_eq!(
+        health_of(&checks, "gone-claude_code"),
+        TargetHealth::Missing
+    );
+    assert_eq!(health_of(&checks, "edited-cursor"), TargetHealth::Drifted);
+    assert_eq!(health_of(&checks, "rule-augment"), TargetHealth::Drifted);
+
+    // The verdict is persisted for the UI.
+    let edited = store
+        .get_skill_target("edited", "cursor", "global")
+        .unwrap()
+        .unwrap();
+    assert_eq!(edited.status, "drifted");
+    assert!(edited.last_error.is_some());
+
+    // Repair only the rule first, then everything else.
+    let report = repair_targets(&store, Some(&["rule-augment".to_string()])).unwrap();
+    assert_eq!(report.repaired.len(), 1);
+    assert!(fs::read_to_string(tools.join("augment/rule.md"))
+        .unwrap()
+        .ends_with("body\n"));
+
+    let report = repair_targets(&store, None).unwrap();
+    assert!(report.failed.is_empty());
+    assert_eq!(report.repaired.len(), 2);
+    assert!(report
+        .repaired
+        .iter()
+        .all(|c| c.health == TargetHealth::Healthy));
+    assert_eq!(
+        fs::read_to_string(tools.join("cursor/edited/SKILL.md")).unwrap(),
+        fs::read_to_string(central.join("edited/SKILL.md")).unwrap()
+    );
+
+    let checks = verify_targets(&store).unwrap();
+    assert!(checks.iter().all(|c| c.health == TargetHealth::Healthy));
+    let edited = store
+        .get_skill_target("edited", "cursor", "global")
+        .unwrap()
+        .unwrap();
+    assert_eq!(edited.status, "ok");
+    assert_eq!(edited.last_error, None);
+}
+
+#[cfg(unix)]
+#[test]
+fn detects_broken_and_misdirected_links() {
+    let (dir, store) = make_store();
+    let central = dir.path().join("central");
+    let tools = dir.path().join("tools");
+    fs::create_dir_all(&tools).unwrap();
+
+    // Linked from an old central repo that was moved away.
+    add_skill(&store, "moved", &central.join("moved"));
+    let old = dir.path().join("old-central/moved");
+    fs::create_dir_all(&old).unwrap();
+    std::os::unix::fs::symlink(&old, tools.join("moved")).unwrap();
+    // Linked to a folder that no longer exists.
+    add_skill(&store, "dangling", &central.join("dangling"));
+    std::os::unix::fs::symlink(dir.path().join("nowhere"), tools.join("dangling")).unwrap();
+
+    for id in ["moved", "dangling"] {
+        store
+            .upsert_skill_target(&SkillTargetRecord {
+                id: id.to_string(),
+                skill_id: id.to_string(),
+                tool: "claude_code".to_string(),
+                scope: "global".to_string(),
+                target_path: tools.join(id).to_string_lossy().to_string(),
+                mode: "symlink".to_string(),
+                status: "ok".to_string(),
+                last_error: None,
+                synced_at: Some(1),
+                rendered_hash: None,
+            })
+            .unwrap();
+    }
+
+    let checks = verify_targets(&store).unwrap();
+    assert_eq!(health_of(&checks, "moved"), TargetHealth::WrongLinkTarget);
+    assert_eq!(health_of(&checks, "dangling"), TargetHealth::BrokenLink);
+
+    let report = repair_targets(&store, None).unwrap();
+    assert!(report.failed.is_empty(), "{:?}", report.failed);
+    assert_eq!(
+        fs::canonicalize(tools.join("moved")).unwrap(),
+        fs::canonicalize(central.join("moved")).unwrap()
+    );
+    assert_eq!(
+        fs::canonicalize(tools.join("dangling")).unwrap(),
+        fs::canonicalize(central.join("dangling")).unwrap()
+    );
+    // The old central folder is left alone.
+    assert!(old.exists());
+}
@@ -0,0 +1,115 @@
+use std::path::Path;
+
+use super::{load_lockfile, lockfile_path, parse_manifest, write_lockfile, LockedSkill, Lockfile};
+
+#[test]
+fn parses_manifest_and_infers_source_types() {
+    let manifest = parse_manifest(
+        r#"
+tools = ["claude_code"]
+
+[[skill]]
+name = "pdf"
+source = "https://github.com/owner/skills/tree/main/skills/pdf"
+ref = "v1.2.0"
+
+[[skill]]
+name = "review"
+source = "owner/repo"
+subpath = "skills/review"
+tools = ["cursor", "codex"]
+
+[[skill]]
+name = "house-style"
+source = "./skills/house-style"
+
+[[skill]]
+name = "bundle"
+source = "https://example.com/releases/bundle.tar.gz"
+
+[[skill]]
+name = "blob"
+source = "https://github.com/owner/repo/blob/main/skills/x/SKILL.md"
+"#,
+    )
+    .unwrap();
+
+    let kinds: Vec<_> = manifest.skills.iter().map(|s| s.kind()).collect();
+    assert_eq!(kinds, vec!["git", "git", "local", "archive", "git"]);
+    assert_eq!(manifest.skills[0].tools(&manifest), ["claude_code"]);
+    assert_eq!(manifest.skills[1].tools(&manifest), ["cursor", "codex"]);
+
+    let base = Path::new("/team/repo");
+    assert_eq!(
+        manifest.skills[0].resolved_source(base),
+        "https://github.com/owner/skills/tree/main/skills/pdf@v1.2.0"
+    );
+    assert_eq!(
+        manifest.skills[0].declared_source(),
+        manifest.skills[0].resolved_source(base)
+    );
+    assert_eq!(
+        Path::new(&manifest.skills[2].resolved_source(base)),
+        Path::new("/team/repo/skills/house-style")
+    );
+    assert_eq!(manifest.skills[2].declared_source(), "./skills/house-style");
+}
+
+#[test]
+fn rejects_invalid_manifests() {
+    for (text, expected) in [
+        (
+            "[[skill]]\nname = \"a\"\nsource = \"o/r\"\n[[skill]]\nname = \"a\"\nsource = \"o/s\"\n",
+            "duplicate",
+        ),
+        ("[[skill]]\nname = \"../a\"\nsource = \"o/r\"\n", "invalid skill name"),
+        ("tools = [\"nope\"]\n", "unknown tool"),
+        (
+            "[[skill]]\nname = \"a\"\nsource = \"./a\"\nref = \"v1\"\n",
+            "only valid for git",
+        ),
+        (
+            "[[skill]]\nname = \"a\"\nsource = \"o/r\"\nsubpath = \"../x\"\n",
+            "invalid subpath",
+        ),
+        (
+            "[[skill]]\nname = \"a\"\nsource = \"o/r\"\ntype = \"svn\"\n",
+            "unknown type",
+        ),
+        ("[[skill]]\nname = \"a\"\nsource = \"o/r\"\ncolour = 1\n", "colour"),
+    ] {
+        let err = parse_manifest(text).expect_err(text);
+        assert!(
+            format!("{:#}", err).contains(expected),
+            "{} -> {:#}",
+            text,
+            err
+        );
+    }
+}
+
+#[test]
+fn lockfile_round_trips_and_defaults_when_missing() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = lockfile_path(&dir.path().join("skills.toml"));
+    assert_eq!(path, dir.path().join("skills.lock"));
+    assert_eq!(load_lockfile(&path).unwrap(), Lockfile::default());
+
+    let lock = Lockfile {
+        skills: vec![LockedSkill {
+            name: "pdf".to_string(),
+            source: "owner/repo@v1".to_string(),
+            source_revision: Some("abc".to_string()),
+            content_hash: None,
+        }],
+        ..Lockfile::default()
+    };
+    write_lockfile(&path, &lock).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.starts_with("# Generated by Skills Hub"));
+    assert!(!text.contains("content_hash"));
+    assert_eq!(load_lockfile(&path).unwrap(), lock);
+
+    std::fs::write(&path, "version = 9\n").unwrap();
+    assert!(load_lockfile(&path).is_err());
+}
@@ -1,7 +1,10 @@
 use std::fs;
+use std::path::{Path, PathBuf};
 
+use super::resolve_home_path;
 use crate::core::tool_adapters::{
-    adapter_by_key, adapters_sharing_skills_dir, scan_tool_dir, ToolAdapter, ToolId,
+    adapter_by_key, adapters_sharing_skills_dir, adapters_sharing_skills_dir_in,
+    resolve_scope_path, scan_tool_dir, TargetScope, ToolAdapter, ToolId,
 };
 
 #[test]
@@ -23,12 +26,36 @@ fn adapter_by_key_finds_new_tools() {
 fn adapters_sharing_skills_dir_groups_amp_and_kimi() {
     let amp = adapter_by_key("amp").unwrap();
     let group = adapters_sharing_skills_dir(&amp);
-    let keys: std::collections::HashSet<&'static str> =
-        group.into_iter().map(|a| a.id.as_key()).collect();
+    let keys: std::collections::HashSet<String> = group
+        .into_iter()
+        .map(|a| a.id.as_key().to_string())
+        .collect();
     assert!(keys.contains("amp"));
     assert!(keys.contains("kimi_cli"));
 }
 
+#[test]
+fn project_scope_uses_project_dirs_and_groups() {
+    let scope = TargetScope::Project(PathBuf::from("/work/repo"));
+    let claude = adapter_by_key("claude_code").unwrap();
+    assert_eq!(
+        resolve_scope_path(&claude, &scope).unwrap(),
+        Path::new("/work/repo/.claude/skills")
+    );
+
+    // Trae and Trae CN have separate global dirs but share `.trae/skills` in a project.
+    let trae = adapter_by_key("trae").unwrap();
+    assert_eq!(adapters_sharing_skills_dir(&trae).len(), 1);
+    let keys: Vec<_> = adapters_sharing_skills_dir_in(&trae, &scope)
+        .into_iter()
+        .map(|a| a.id.as_key().to_string())
+        .collect();
+    assert_eq!(keys, vec!["trae", "trae_cn"]);
+
+    assert_eq!(TargetScope::from_key("global"), TargetScope::Global);
+    assert_eq!(TargetScope::from_key(&scope.as_key()), scope);
+}
+
 #[test]
 fn scan_tool_dir_skips_codex_system_and_includes_symlink_dir() {
     let dir = tempfile::tempdir().unwrap();
@@ -44,9 +71,10 @@ fn scan_tool_dir_skips_codex_system_and_includes_symlink_dir() {
 
     let tool = ToolAdapter {
         id: ToolId::Codex,
-        display_name: "Codex",
-        relative_skills_dir: "ignored",
-        relative_detect_dir: "ignored",
+        display_name: "Codex".into(),
+        relative_skills_dir: "ignored".into(),
+        relative_project_skills_dir: "ignored".into(),
+        relative_detect_dir: "ignored".into(),
     };
 
     let out = scan_tool_dir(&tool, dir.path()).unwrap();
@@ -73,11 +101,65 @@ fn scan_tool_dir_skips_app_support_path() {
 
     let tool = ToolAdapter {
         id: ToolId::Cursor,
-        display_name: "Cursor",
-        relative_skills_dir: "ignored",
-        relative_detect_dir: "ignored",
+        display_name: "Cursor".into(),
+        relative_skills_dir: "ignored".into(),
+        relative_project_skills_dir: "ignored".into(),
+        relative_detect_dir: "ignored".into(),
     };
 
     let out = scan_tool_dir(&tool, &root).unwrap();
     assert!(out.is_empty());
 }
+
+#[test]
+fn env_overrides_relocate_tool_dirs() {
+    let home = Path::new("/home/me");
+    let env = |vars: &'static [(&'static str, &'static str)]| {
+        move |key: &str| {
+            vars.iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.to_string())
+        }
+    };
+    let claude = adapter_by_key("claude_code").unwrap();
+    let goose = adapter_by_key("goose").unwrap();
+    let cursor = adapter_by_key("cursor").unwrap();
+
+    assert_eq!(
+        resolve_home_path(&claude, home, &claude.relative_skills_dir, env(&[])),
+        Path::new("/home/me/.claude/skills")
+    );
+    let vars = &[
+        ("CLAUDE_CONFIG_DIR", "/cfg/claude"),
+        ("XDG_CONFIG_HOME", "/xdg"),
+    ];
+    assert_eq!(
+        resolve_home_path(&claude, home, &claude.relative_skills_dir, env(vars)),
+        Path::new("/cfg/claude/skills")
+    );
+    assert_eq!(
+        resolve_home_path(&claude, home, &claude.relative_detect_dir, env(vars)),
+        Path::new("/cfg/claude")
+    );
+    assert_eq!(
+        resolve_home_path(&goose, home, &goose.relative_skills_dir, env(vars)),
+        Path::new("/xdg/goose/skills")
+    );
+    // Only `.config/...` dirs follow XDG.
+    assert_eq!(
+        resolve_home_path(&cursor, home, &cursor.relative_skills_dir, env(vars)),
+        Path::new("/home/me/.cursor/skills")
+    );
+
+    // Relative values are ignored.
+    let vars = &[("CODEX_HOME", "codex"), ("XDG_CONFIG_HOME", "")];
+    let codex = adapter_by_key("codex").unwrap();
+    assert_eq!(
+        resolve_home_path(&codex, home, &codex.relative_skills_dir, env(vars)),
+        Path::new("/home/me/.codex/skills")
+    );
+    assert_eq!(
+        resolve_home_path(&goose, home, &goose.relative_skills_dir, env(vars)),
+        Path::new("/home/me/.config/goose/skills")
+    );
+}
@@ -0,0 +1,36 @@
+use super::{get_tool_sync_modes, preferred_sync_mode, set_tool_sync_mode};
+use crate::core::sync_engine::SyncMode;
+use crate::core::test_fixtures::make_store;
+
+#[test]
+fn choose_and_reset_a_tool_sync_mode() {
+    let (_dir, store) = make_store();
+    assert!(get_tool_sync_modes(&store).is_empty());
+    assert!(preferred_sync_mode(&store, "cursor").is_none());
+
+    set_tool_sync_mode(&store, "cursor", Some("hardlink")).unwrap();
+    let modes = set_tool_sync_mode(&store, "claude_code", Some("reflink")).unwrap();
+    assert_eq!(modes.len(), 2);
+    assert!(matches!(
+        preferred_sync_mode(&store, "cursor"),
+        Some(SyncMode::Hardlink)
+    ));
+
+    let modes = set_tool_sync_mode(&store, "cursor", None).unwrap();
+    assert_eq!(modes.keys().collect::<Vec<_>>(), vec!["claude_code"]);
+    assert!(preferred_sync_mode(&store, "cursor").is_none());
+}
+
+#[test]
+fn rejects_unknown_tools_and_modes() {
+    let (_dir, store) = make_store();
+    for (tool, mode, expected) in [
+        ("nope", "copy", "unknown tool"),
+        ("cursor", "mirror", "unknown sync mode"),
+        ("cursor", "auto", "does not follow symlinks"),
+    ] {
+        let err = set_tool_sync_mode(&store, tool, Some(mode)).unwrap_err();
+        assert!(err.to_string().contains(expected), "{}", err);
+    }
+    assert!(get_tool_sync_modes(&store).is_empty());
+}
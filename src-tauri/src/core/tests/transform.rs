@@ -0,0 +1,51 @@
+use std::path::Path;
+
+use super::{transformer_for, with_suffix, Mdc, SkillDocument, SkillFormat, SkillTransformer};
+use crate::core::tool_adapters::adapter_by_key;
+
+const SKILL_MD: &str =
+    "---\nname: pdf\ndescription: \"Read: and fill PDFs\"\n---\n\n# PDF\n\nUse scripts/fill.py.\n";
+
+#[test]
+fn parses_frontmatter_and_body() {
+    let doc = SkillDocument::parse(SKILL_MD, "fallback");
+    assert_eq!(doc.name, "pdf");
+    assert_eq!(doc.description.as_deref(), Some("Read: and fill PDFs"));
+    assert_eq!(doc.body, "# PDF\n\nUse scripts/fill.py.\n");
+
+    let bare = SkillDocument::parse("# Just markdown\n", "notes");
+    assert_eq!(bare.name, "notes");
+    assert_eq!(bare.description, None);
+    assert_eq!(bare.body, "# Just markdown\n");
+}
+
+#[test]
+fn augment_renders_markdown_rules() {
+    let augment = adapter_by_key("augment").unwrap();
+    let transformer = transformer_for(&augment).expect("augment has a transformer");
+    assert_eq!(
+        transformer.output_path(Path::new("/home/.augment/rules/pdf")),
+        Path::new("/home/.augment/rules/pdf.md")
+    );
+    assert_eq!(
+        transformer.render(&SkillDocument::parse(SKILL_MD, "pdf")),
+        "---\ntype: agent_requested\ndescription: \"Read: and fill PDFs\"\n---\n\n# PDF\n\nUse scripts/fill.py.\n"
+    );
+
+    assert!(transformer_for(&adapter_by_key("claude_code").unwrap()).is_none());
+}
+
+#[test]
+fn mdc_renders_cursor_rule_frontmatter() {
+    let rendered = Mdc.render(&SkillDocument::parse(SKILL_MD, "pdf"));
+    assert!(rendered.starts_with(
+        "---\ndescription: \"Read: and fill PDFs\"\nglobs:\nalwaysApply: false\n---\n\n# PDF"
+    ));
+    // Dots in skill names are kept.
+    assert_eq!(
+        with_suffix(Path::new("/r/v1.2-notes"), "mdc"),
+        Path::new("/r/v1.2-notes.mdc")
+    );
+    assert_eq!(SkillFormat::from_key("mdc"), Some(SkillFormat::Mdc));
+    assert_eq!(SkillFormat::from_key("html"), None);
+}
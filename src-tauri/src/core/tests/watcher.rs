@@ -0,0 +1,141 @@
+use std::fs;
+use std::path::Path;
+
+use super::ChangeTracker;
+use crate::core::skill_store::{SkillStore, SkillTargetRecord};
+use crate::core::sync_engine::sync_dir_copy_with_overwrite;
+use crate::core::test_fixtures::{make_skill, make_store};
+
+fn add_skill(store: &SkillStore, id: &str, central: &Path) {
+    fs::create_dir_all(central).unwrap();
+    fs::write(
+        central.join("SKILL.md"),
+        format!("---\nname: {}\n---\nv1\n", id),
+    )
+    .unwrap();
+    store
+        .upsert_skill(&make_skill(id, id, &central.to_string_lossy(), 1))
+        .unwrap();
+}
+
+/// Copy target under a tool key with no built-in adapter, so the installed-tool
+/// check does not depend on the machine running the tests.
+fn add_copy_target(store: &SkillStore, skill_id: &str, central: &Path, target: &Path) {
+    sync_dir_copy_with_overwrite(store, central, target, false).unwrap();
+    store
+        .upsert_skill_target(&SkillTargetRecord {
+            id: format!("{}-copy", skill_id),
+            skill_id: skill_id.to_string(),
+            tool: "copy_tool".to_string(),
+            scope: "global".to_string(),
+            target_path: target.to_string_lossy().to_string(),
+            mode: "copy".to_string(),
+            status: "ok".to_string(),
+            last_error: None,
+            synced_at: Some(1),
+            rendered_hash: None,
+        })
+        .unwrap();
+}
+
+#[test]
+fn central_edits_refresh_copy_targets() {
+    let (dir, store) = make_store();
+    let central = dir.path().join("central");
+    let target = dir.path().join("tool/demo");
+    add_skill(&store, "demo", &central.join("demo"));
+    add_copy_target(&store, "demo", &central.join("demo"), &target);
+
+    let mut tracker = ChangeTracker::new(central.clone(), Vec::new());
+    fs::write(central.join("demo/SKILL.md"), "v2").unwrap();
+    let change = tracker
+        .apply(&store, &[central.join("demo/SKILL.md")])
+        .unwrap();
+
+    assert_eq!(change.updated_skills, vec!["demo".to_string()]);
+    assert_eq!(change.refreshed_targets, vec!["copy_tool".to_string()]);
+    assert_eq!(fs::read_to_string(target.join("SKILL.md")).unwrap(), "v2");
+
+    // Paths outside any managed skill are ignored.
+    let change = tracker
+        .apply(&store, &[dir.path().join("elsewhere/file")])
+        .unwrap();
+    assert!(change.is_empty());
+}
+
+#[test]
+fn reports_tool_dir_additions_and_removed_targets() {
+    let (dir, store) = make_store();
+    let central = dir.path().join("central");
+    let tool_dir = dir.path().join("tool");
+    add_skill(&store, "synced", &central.join("synced"));
+    add_copy_target(
+        &store,
+        "synced",
+        &central.join("synced"),
+        &tool_dir.join("synced"),
+    );
+
+    let mut tracker = ChangeTracker::new(
+        central.clone(),
+        vec![("copy_tool".to_string(), tool_dir.clone())],
+    );
+
+    fs::create_dir_all(tool_dir.join("handmade")).unwrap();
+    fs::remove_dir_all(tool_dir.join("synced")).unwrap();
+    let change = tracker
+        .apply(
+            &store,
+            &[tool_dir.join("handmade"), tool_dir.join("synced")],
+        )
+        .unwrap();
+
+    assert_eq!(change.added.len(), 1);
+    assert_eq!(change.added[0].name, "handmade");
+    assert!(!change.added[0].managed);
+    assert_eq!(change.removed.len(), 1);
+    assert_eq!(change.removed[0].name, "synced");
+    assert!(change.removed[0].managed);
+
+    let target = store
+        .get_skill_target("synced", "copy_tool", "global")
+        .unwrap()
+        .unwrap();
+    assert_eq!(target.status, "missing");
+
+    // Nothing changed since the last batch.
+    let change = tracker
+        .apply(&store, std::slice::from_ref(&tool_dir))
+        .unwrap();
+    assert!(change.is_empty());
+}
+
+#[test]
+fn central_refresh_waits_for_an_update_in_progress() {
+    let (dir, store) = make_store();
+    let central = dir.path().join("central");
+    let target = dir.path().join("tool/locked");
+    add_skill(&store, "locked", &central.join("locked"));
+    add_copy_target(&store, "locked", &central.join("locked"), &target);
+
+    let skill_lock = crate::core::installer::SkillLock::for_skill("locked");
+    let guard = skill_lock.lock();
+    let worker = {
+        let store = store.clone();
+        let central = central.clone();
+        std::thread::spawn(move || {
+            let mut tracker = ChangeTracker::new(central.clone(), Vec::new());
+            tracker
+                .apply(&store, &[central.join("locked/SKILL.md")])
+                .unwrap()
+        })
+    };
+    // The installer's swap finishes while the watcher waits.
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    fs::write(central.join("locked/SKILL.md"), "v2").unwrap();
+    drop(guard);
+
+    let change = worker.join().unwrap();
+    assert_eq!(change.updated_skills, vec!["locked".to_string()]);
+    assert_eq!(fs::read_to_string(target.join("SKILL.md")).unwrap(), "v2");
+}
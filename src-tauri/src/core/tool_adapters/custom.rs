@@ -0,0 +1,245 @@
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::transform::SkillFormat;
+use super::{builtin_tool_adapters, ToolAdapter, ToolId};
+use crate::core::sync_engine::SyncMode;
+
+/// User adapters live next to the default central repo, so they survive reinstalls.
+const CUSTOM_TOOLS_FILE: &str = ".skillshub/tools.toml";
+
+/// User-defined tools (`~/.skillshub/tools.toml`), for agents that have no built-in
+/// adapter yet. Paths are relative to the home directory (or the project root).
+///
+/// ```toml
+/// [[tool]]
+/// key = "my_agent"
+/// name = "My Agent"
+/// skills_dir = ".my-agent/skills"
+/// detect_dir = ".my-agent"
+/// sync_mode = "copy"
+/// format = "skill"
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomToolsFile {
+    #[serde(default, rename = "tool")]
+    pub tools: Vec<CustomTool>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomTool {
+    pub key: String,
+    pub name: String,
+    pub skills_dir: String,
+    /// Defaults to `skills_dir`.
+    #[serde(default)]
+    pub project_skills_dir: Option<String>,
+    pub detect_dir: String,
+    /// `auto` (symlink, then junction, then copy), `copy`, `hardlink` or `reflink`;
+    /// `symlink` and `junction` are accepted as aliases of `auto`.
+    #[serde(default)]
+    pub sync_mode: Option<String>,
+    /// `skill` (SKILL.md folder), `rule` (markdown rule file) or `mdc`.
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+#[derive(Clone)]
+struct LoadedTool {
+    adapter: ToolAdapter,
+    sync_mode: SyncMode,
+    format: SkillFormat,
+}
+
+/// What the last read of `tools.toml` produced. An invalid file leaves no custom tools
+/// and keeps the error for the UI, so the built-ins keep working.
+#[derive(Default)]
+struct Registry {
+    tools: Vec<LoadedTool>,
+    error: Option<String>,
+}
+
+static CUSTOM_TOOLS: RwLock<Option<Registry>> = RwLock::new(None);
+
+/// Custom adapters from the config file, read on first use and again on each
+/// `reload_custom_tools`. A missing file means none.
+pub fn custom_tool_adapters() -> Vec<ToolAdapter> {
+    with_registry(|r| r.tools.iter().map(|t| t.adapter.clone()).collect())
+}
+
+/// Sync mode a custom tool asked for; `None` for built-in or unknown keys.
+pub fn custom_sync_mode(key: &str) -> Option<SyncMode> {
+    with_registry(|r| {
+        r.tools
+            .iter()
+            .find(|t| t.adapter.id.as_key() == key)
+            .map(|t| t.sync_mode.clone())
+    })
+}
+
+/// Native format a custom tool declared; `None` for built-in or unknown keys.
+pub fn custom_format(key: &str) -> Option<SkillFormat> {
+    with_registry(|r| {
+        r.tools
+            .iter()
+            .find(|t| t.adapter.id.as_key() == key)
+            .map(|t| t.format)
+    })
+}
+
+pub fn custom_tools_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(CUSTOM_TOOLS_FILE))
+}
+
+/// Re-read `tools.toml` so edits apply without a restart. Returns why the file was
+/// rejected, if it was.
+pub fn reload_custom_tools() -> Option<String> {
+    let registry = read_registry();
+    let error = registry.error.clone();
+    *CUSTOM_TOOLS.write().unwrap_or_else(|e| e.into_inner()) = Some(registry);
+    error
+}
+
+fn with_registry<T>(f: impl FnOnce(&Registry) -> T) -> T {
+    {
+        let current = CUSTOM_TOOLS.read().unwrap_or_else(|e| e.into_inner());
+        if let Some(registry) = current.as_ref() {
+            return f(registry);
+        }
+    }
+    let mut current = CUSTOM_TOOLS.write().unwrap_or_else(|e| e.into_inner());
+    f(current.get_or_insert_with(read_registry))
+}
+
+fn read_registry() -> Registry {
+    custom_tools_path()
+        .map(|path| registry_from(&path))
+        .unwrap_or_default()
+}
+
+fn registry_from(path: &Path) -> Registry {
+    match load_custom_tools(path) {
+        Ok(tools) => Registry {
+            tools: tools.into_iter().map(into_loaded).collect(),
+            error: None,
+        },
+        Err(err) => {
+            log::warn!("ignoring custom tools: {:#}", err);
+            Registry {
+                tools: Vec::new(),
+                error: Some(format!("{:#}", err)),
+            }
+        }
+    }
+}
+
+/// Read and validate `path`; a missing file is an empty list.
+pub fn load_custom_tools(path: &Path) -> Result<Vec<CustomTool>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err).with_context(|| format!("read {:?}", path)),
+    };
+    parse_custom_tools(&text).with_context(|| format!("{:?}", path))
+}
+
+pub fn parse_custom_tools(text: &str) -> Result<Vec<CustomTool>> {
+    let file: CustomToolsFile = toml::from_str(text).context("invalid tools.toml")?;
+    let builtin: HashSet<String> = builtin_tool_adapters()
+        .iter()
+        .map(|a| a.id.as_key().to_string())
+        .collect();
+    let mut keys = HashSet::new();
+    for tool in &file.tools {
+        let key = tool.key.as_str();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            anyhow::bail!(
+                "invalid tool key {:?} (use lowercase letters, digits and _)",
+                key
+            );
+        }
+        if builtin.contains(key) {
+            anyhow::bail!("tool {:?} is built in", key);
+        }
+        if !keys.insert(key) {
+            anyhow::bail!("duplicate tool key {:?}", key);
+        }
+        if tool.name.trim().is_empty() {
+            anyhow::bail!("tool {:?} has no name", key);
+        }
+        validate_dir(key, "skills_dir", &tool.skills_dir)?;
+        validate_dir(key, "detect_dir", &tool.detect_dir)?;
+        if let Some(dir) = &tool.project_skills_dir {
+            validate_dir(key, "project_skills_dir", dir)?;
+        }
+        parse_sync_mode(tool.sync_mode.as_deref()).with_context(|| format!("tool {:?}", key))?;
+        parse_format(tool.format.as_deref()).with_context(|| format!("tool {:?}", key))?;
+    }
+    Ok(file.tools)
+}
+
+/// Directories must stay inside home (or the project root).
+fn validate_dir(key: &str, field: &str, dir: &str) -> Result<()> {
+    let path = Path::new(dir);
+    if dir.trim().is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        anyhow::bail!(
+            "tool {:?}: {} must be a relative path like \".my-agent/skills\", got {:?}",
+            key,
+            field,
+            dir
+        );
+    }
+    Ok(())
+}
+
+fn parse_sync_mode(mode: Option<&str>) -> Result<SyncMode> {
+    match mode.unwrap_or("auto") {
+        "auto" | "symlink" | "junction" => Ok(SyncMode::Auto),
+        "copy" => Ok(SyncMode::Copy),
+        "hardlink" => Ok(SyncMode::Hardlink),
+        "reflink" => Ok(SyncMode::Reflink),
+        other => anyhow::bail!(
+            "unknown sync_mode {:?} (expected auto, copy, hardlink or reflink)",
+            other
+        ),
+    }
+}
+
+fn parse_format(format: Option<&str>) -> Result<SkillFormat> {
+    let format = format.unwrap_or("skill");
+    SkillFormat::from_key(format)
+        .ok_or_else(|| anyhow::anyhow!("unknown format {:?} (expected skill, rule or mdc)", format))
+}
+
+fn into_loaded(tool: CustomTool) -> LoadedTool {
+    let sync_mode = parse_sync_mode(tool.sync_mode.as_deref()).unwrap_or(SyncMode::Auto);
+    let format = parse_format(tool.format.as_deref()).unwrap_or(SkillFormat::SkillDir);
+    LoadedTool {
+        adapter: ToolAdapter {
+            id: ToolId::Custom(Arc::from(tool.key)),
+            display_name: tool.name.into(),
+            relative_project_skills_dir: tool
+                .project_skills_dir
+                .unwrap_or_else(|| tool.skills_dir.clone())
+                .into(),
+            relative_skills_dir: tool.skills_dir.into(),
+            relative_detect_dir: tool.detect_dir.into(),
+        },
+        sync_mode,
+        format,
+    }
+}
+
+#[cfg(test)]
+#[path = "../tests/custom_tools.rs"]
+mod tests;
@@ -1,7 +1,15 @@
+use std::borrow::Cow;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::{Context, Result};
 
+mod custom;
+mod transform;
+
+pub use custom::{custom_format, custom_sync_mode, custom_tools_path, reload_custom_tools};
+pub use transform::{skill_format, transformer_for, SkillDocument, SkillTransformer};
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ToolId {
     Cursor,
@@ -45,10 +53,12 @@ pub enum ToolId {
     Droid,
     Windsurf,
     Moltbot,
+    /// Adapter defined in the user's `tools.toml`, by key.
+    Custom(Arc<str>),
 }
 
 impl ToolId {
-    pub fn as_key(&self) -> &'static str {
+    pub fn as_key(&self) -> &str {
         match self {
             ToolId::Cursor => "cursor",
             ToolId::ClaudeCode => "claude_code",
@@ -91,6 +101,7 @@ impl ToolId {
             ToolId::Droid => "droid",
             ToolId::Windsurf => "windsurf",
             ToolId::Moltbot => "moltbot",
+            ToolId::Custom(key) => key.as_ref(),
         }
     }
 }
@@ -98,11 +109,13 @@ impl ToolId {
 #[derive(Clone, Debug)]
 pub struct ToolAdapter {
     pub id: ToolId,
-    pub display_name: &'static str,
+    pub display_name: Cow<'static, str>,
     /// Global skill directory under user home (aligned with add-skill docs).
-    pub relative_skills_dir: &'static str,
+    pub relative_skills_dir: Cow<'static, str>,
+    /// Skill directory inside a project root (aligned with add-skill docs).
+    pub relative_project_skills_dir: Cow<'static, str>,
     /// Directory used to detect whether the tool is installed (aligned with add-skill docs).
-    pub relative_detect_dir: &'static str,
+    pub relative_detect_dir: Cow<'static, str>,
 }
 
 #[derive(Clone, Debug)]
@@ -114,302 +127,411 @@ pub struct DetectedSkill {
     pub link_target: Option<PathBuf>,
 }
 
+/// Built-in adapters followed by the user's custom ones.
 pub fn default_tool_adapters() -> Vec<ToolAdapter> {
+    let mut adapters = builtin_tool_adapters();
+    adapters.extend(custom::custom_tool_adapters());
+    adapters
+}
+
+pub fn builtin_tool_adapters() -> Vec<ToolAdapter> {
     vec![
         ToolAdapter {
             id: ToolId::Cursor,
-            display_name: "Cursor",
-            relative_skills_dir: ".cursor/skills",
-            relative_detect_dir: ".cursor",
+            display_name: "Cursor".into(),
+            relative_skills_dir: ".cursor/skills".into(),
+            relative_project_skills_dir: ".cursor/skills".into(),
+            relative_detect_dir: ".cursor".into(),
         },
         ToolAdapter {
             id: ToolId::ClaudeCode,
-            display_name: "Claude Code",
-            relative_skills_dir: ".claude/skills",
-            relative_detect_dir: ".claude",
+            display_name: "Claude Code".into(),
+            relative_skills_dir: ".claude/skills".into(),
+            relative_project_skills_dir: ".claude/skills".into(),
+            relative_detect_dir: ".claude".into(),
         },
         ToolAdapter {
             id: ToolId::Codex,
-            display_name: "Codex",
-            relative_skills_dir: ".codex/skills",
-            relative_detect_dir: ".codex",
+            display_name: "Codex".into(),
+            relative_skills_dir: ".codex/skills".into(),
+            relative_project_skills_dir: ".codex/skills".into(),
+            relative_detect_dir: ".codex".into(),
         },
         ToolAdapter {
             id: ToolId::OpenCode,
-            display_name: "OpenCode",
+            display_name: "OpenCode".into(),
             // add-skill global path: ~/.config/opencode/skills/
-            relative_skills_dir: ".config/opencode/skills",
-            relative_detect_dir: ".config/opencode",
+            relative_skills_dir: ".config/opencode/skills".into(),
+            relative_project_skills_dir: ".opencode/skills".into(),
+            relative_detect_dir: ".config/opencode".into(),
         },
         ToolAdapter {
             id: ToolId::Antigravity,
-            display_name: "Antigravity",
+            display_name: "Antigravity".into(),
             // add-skill global path: ~/.gemini/antigravity/global_skills/
-            relative_skills_dir: ".gemini/antigravity/global_skills",
-            relative_detect_dir: ".gemini/antigravity",
+            relative_skills_dir: ".gemini/antigravity/global_skills".into(),
+            relative_project_skills_dir: ".agent/skills".into(),
+            relative_detect_dir: ".gemini/antigravity".into(),
         },
         ToolAdapter {
             id: ToolId::Amp,
-            display_name: "Amp",
+            display_name: "Amp".into(),
             // add-skill global path: ~/.config/agents/skills/
-            relative_skills_dir: ".config/agents/skills",
-            relative_detect_dir: ".config/agents",
+            relative_skills_dir: ".config/agents/skills".into(),
+            relative_project_skills_dir: ".agents/skills".into(),
+            relative_detect_dir: ".config/agents".into(),
         },
         ToolAdapter {
             id: ToolId::KimiCli,
-            display_name: "Kimi Code CLI",
+            display_name: "Kimi Code CLI".into(),
             // add-skill global path: ~/.config/agents/skills/
             // NOTE: Shares the same skills directory with Amp.
-            relative_skills_dir: ".config/agents/skills",
-            relative_detect_dir: ".config/agents",
+            relative_skills_dir: ".config/agents/skills".into(),
+            relative_project_skills_dir: ".agents/skills".into(),
+            relative_detect_dir: ".config/agents".into(),
         },
         ToolAdapter {
             id: ToolId::Augment,
-            display_name: "Augment",
+            display_name: "Augment".into(),
             // add-skill global path: ~/.augment/rules/
-            relative_skills_dir: ".augment/rules",
-            relative_detect_dir: ".augment",
+            relative_skills_dir: ".augment/rules".into(),
+            relative_project_skills_dir: ".augment/rules".into(),
+            relative_detect_dir: ".augment".into(),
         },
         ToolAdapter {
             id: ToolId::OpenClaw,
-            display_name: "OpenClaw",
+            display_name: "OpenClaw".into(),
             // add-skill global path: ~/.openclaw/skills/
-            relative_skills_dir: ".openclaw/skills",
-            relative_detect_dir: ".openclaw",
+            relative_skills_dir: ".openclaw/skills".into(),
+            relative_project_skills_dir: "skills".into(),
+            relative_detect_dir: ".openclaw".into(),
         },
         ToolAdapter {
             id: ToolId::Cline,
-            display_name: "Cline",
+            display_name: "Cline".into(),
             // add-skill global path: ~/.cline/skills/
-            relative_skills_dir: ".cline/skills",
-            relative_detect_dir: ".cline",
+            relative_skills_dir: ".cline/skills".into(),
+            relative_project_skills_dir: ".cline/skills".into(),
+            relative_detect_dir: ".cline".into(),
         },
         ToolAdapter {
             id: ToolId::CodeBuddy,
-            display_name: "CodeBuddy",
+            display_name: "CodeBuddy".into(),
             // add-skill global path: ~/.codebuddy/skills/
-            relative_skills_dir: ".codebuddy/skills",
-            relative_detect_dir: ".codebuddy",
+            relative_skills_dir: ".codebuddy/skills".into(),
+            relative_project_skills_dir: ".codebuddy/skills".into(),
+            relative_detect_dir: ".codebuddy".into(),
         },
         ToolAdapter {
             id: ToolId::CommandCode,
-            display_name: "Command Code",
+            display_name: "Command Code".into(),
             // add-skill global path: ~/.commandcode/skills/
-            relative_skills_dir: ".commandcode/skills",
-            relative_detect_dir: ".commandcode",
+            relative_skills_dir: ".commandcode/skills".into(),
+            relative_project_skills_dir: ".commandcode/skills".into(),
+            relative_detect_dir: ".commandcode".into(),
         },
         ToolAdapter {
             id: ToolId::Continue,
-            display_name: "Continue",
+            display_name: "Continue".into(),
             // add-skill global path: ~/.continue/skills/
-            relative_skills_dir: ".continue/skills",
-            relative_detect_dir: ".continue",
+            relative_skills_dir: ".continue/skills".into(),
+            relative_project_skills_dir: ".continue/skills".into(),
+            relative_detect_dir: ".continue".into(),
         },
         ToolAdapter {
             id: ToolId::Crush,
-            display_name: "Crush",
+            display_name: "Crush".into(),
             // add-skill global path: ~/.config/crush/skills/
-            relative_skills_dir: ".config/crush/skills",
-            relative_detect_dir: ".config/crush",
+            relative_skills_dir: ".config/crush/skills".into(),
+            relative_project_skills_dir: ".crush/skills".into(),
+            relative_detect_dir: ".config/crush".into(),
         },
         ToolAdapter {
             id: ToolId::Junie,
-            display_name: "Junie",
+            display_name: "Junie".into(),
             // add-skill global path: ~/.junie/skills/
-            relative_skills_dir: ".junie/skills",
-            relative_detect_dir: ".junie",
+            relative_skills_dir: ".junie/skills".into(),
+            relative_project_skills_dir: ".junie/skills".into(),
+            relative_detect_dir: ".junie".into(),
         },
         ToolAdapter {
             id: ToolId::IflowCli,
-            display_name: "iFlow CLI",
+            display_name: "iFlow CLI".into(),
             // add-skill global path: ~/.iflow/skills/
-            relative_skills_dir: ".iflow/skills",
-            relative_detect_dir: ".iflow",
+            relative_skills_dir: ".iflow/skills".into(),
+            relative_project_skills_dir: ".iflow/skills".into(),
+            relative_detect_dir: ".iflow".into(),
         },
         ToolAdapter {
             id: ToolId::KiroCli,
-            display_name: "Kiro CLI",
+            display_name: "Kiro CLI".into(),
             // add-skill global path: ~/.kiro/skills/
-            relative_skills_dir: ".kiro/skills",
-            relative_detect_dir: ".kiro",
+            relative_skills_dir: ".kiro/skills".into(),
+            relative_project_skills_dir: ".kiro/skills".into(),
+            relative_detect_dir: ".kiro".into(),
         },
         ToolAdapter {
             id: ToolId::Kode,
-            display_name: "Kode",
+            display_name: "Kode".into(),
             // add-skill global path: ~/.kode/skills/
-            relative_skills_dir: ".kode/skills",
-            relative_detect_dir: ".kode",
+            relative_skills_dir: ".kode/skills".into(),
+            relative_project_skills_dir: ".kode/skills".into(),
+            relative_detect_dir: ".kode".into(),
         },
         ToolAdapter {
             id: ToolId::McpJam,
-            display_name: "MCPJam",
+            display_name: "MCPJam".into(),
             // add-skill global path: ~/.mcpjam/skills/
-            relative_skills_dir: ".mcpjam/skills",
-            relative_detect_dir: ".mcpjam",
+            relative_skills_dir: ".mcpjam/skills".into(),
+            relative_project_skills_dir: ".mcpjam/skills".into(),
+            relative_detect_dir: ".mcpjam".into(),
         },
         ToolAdapter {
             id: ToolId::MistralVibe,
-            display_name: "Mistral Vibe",
+            display_name: "Mistral Vibe".into(),
             // add-skill global path: ~/.vibe/skills/
-            relative_skills_dir: ".vibe/skills",
-            relative_detect_dir: ".vibe",
+            relative_skills_dir: ".vibe/skills".into(),
+            relative_project_skills_dir: ".vibe/skills".into(),
+            relative_detect_dir: ".vibe".into(),
         },
         ToolAdapter {
             id: ToolId::Mux,
-            display_name: "Mux",
+            display_name: "Mux".into(),
             // add-skill global path: ~/.mux/skills/
-            relative_skills_dir: ".mux/skills",
-            relative_detect_dir: ".mux",
+            relative_skills_dir: ".mux/skills".into(),
+            relative_project_skills_dir: ".mux/skills".into(),
+            relative_detect_dir: ".mux".into(),
         },
         ToolAdapter {
             id: ToolId::OpenClaude,
-            display_name: "OpenClaude IDE",
+            display_name: "OpenClaude IDE".into(),
             // add-skill global path: ~/.openclaude/skills/
-            relative_skills_dir: ".openclaude/skills",
-            relative_detect_dir: ".openclaude",
+            relative_skills_dir: ".openclaude/skills".into(),
+            relative_project_skills_dir: ".openclaude/skills".into(),
+            relative_detect_dir: ".openclaude".into(),
         },
         ToolAdapter {
             id: ToolId::OpenHands,
-            display_name: "OpenHands",
+            display_name: "OpenHands".into(),
             // add-skill global path: ~/.openhands/skills/
-            relative_skills_dir: ".openhands/skills",
-            relative_detect_dir: ".openhands",
+            relative_skills_dir: ".openhands/skills".into(),
+            relative_project_skills_dir: ".openhands/skills".into(),
+            relative_detect_dir: ".openhands".into(),
         },
         ToolAdapter {
             id: ToolId::Pi,
-            display_name: "Pi",
+            display_name: "Pi".into(),
             // add-skill global path: ~/.pi/agent/skills/
-            relative_skills_dir: ".pi/agent/skills",
-            relative_detect_dir: ".pi",
+            relative_skills_dir: ".pi/agent/skills".into(),
+            relative_project_skills_dir: ".pi/skills".into(),
+            relative_detect_dir: ".pi".into(),
         },
         ToolAdapter {
             id: ToolId::Qoder,
-            display_name: "Qoder",
+            display_name: "Qoder".into(),
             // add-skill global path: ~/.qoder/skills/
-            relative_skills_dir: ".qoder/skills",
-            relative_detect_dir: ".qoder",
+            relative_skills_dir: ".qoder/skills".into(),
+            relative_project_skills_dir: ".qoder/skills".into(),
+            relative_detect_dir: ".qoder".into(),
         },
         ToolAdapter {
             id: ToolId::QwenCode,
-            display_name: "Qwen Code",
+            display_name: "Qwen Code".into(),
             // add-skill global path: ~/.qwen/skills/
-            relative_skills_dir: ".qwen/skills",
-            relative_detect_dir: ".qwen",
+            relative_skills_dir: ".qwen/skills".into(),
+            relative_project_skills_dir: ".qwen/skills".into(),
+            relative_detect_dir: ".qwen".into(),
         },
         ToolAdapter {
             id: ToolId::Trae,
-            display_name: "Trae",
+            display_name: "Trae".into(),
             // add-skill global path: ~/.trae/skills/
-            relative_skills_dir: ".trae/skills",
-            relative_detect_dir: ".trae",
+            relative_skills_dir: ".trae/skills".into(),
+            relative_project_skills_dir: ".trae/skills".into(),
+            relative_detect_dir: ".trae".into(),
         },
         ToolAdapter {
             id: ToolId::TraeCn,
-            display_name: "Trae CN",
+            display_name: "Trae CN".into(),
             // add-skill global path: ~/.trae-cn/skills/
-            relative_skills_dir: ".trae-cn/skills",
-            relative_detect_dir: ".trae-cn",
+            relative_skills_dir: ".trae-cn/skills".into(),
+            relative_project_skills_dir: ".trae/skills".into(),
+            relative_detect_dir: ".trae-cn".into(),
         },
         ToolAdapter {
             id: ToolId::Zencoder,
-            display_name: "Zencoder",
+            display_name: "Zencoder".into(),
             // add-skill global path: ~/.zencoder/skills/
-            relative_skills_dir: ".zencoder/skills",
-            relative_detect_dir: ".zencoder",
+            relative_skills_dir: ".zencoder/skills".into(),
+            relative_project_skills_dir: ".zencoder/skills".into(),
+            relative_detect_dir: ".zencoder".into(),
         },
         ToolAdapter {
             id: ToolId::Neovate,
-            display_name: "Neovate",
+            display_name: "Neovate".into(),
             // add-skill global path: ~/.neovate/skills/
-            relative_skills_dir: ".neovate/skills",
-            relative_detect_dir: ".neovate",
+            relative_skills_dir: ".neovate/skills".into(),
+            relative_project_skills_dir: ".neovate/skills".into(),
+            relative_detect_dir: ".neovate".into(),
         },
         ToolAdapter {
             id: ToolId::Pochi,
-            display_name: "Pochi",
+            display_name: "Pochi".into(),
             // add-skill global path: ~/.pochi/skills/
-            relative_skills_dir: ".pochi/skills",
-            relative_detect_dir: ".pochi",
+            relative_skills_dir: ".pochi/skills".into(),
+            relative_project_skills_dir: ".pochi/skills".into(),
+            relative_detect_dir: ".pochi".into(),
         },
         ToolAdapter {
             id: ToolId::AdaL,
-            display_name: "AdaL",
+            display_name: "AdaL".into(),
             // add-skill global path: ~/.adal/skills/
-            relative_skills_dir: ".adal/skills",
-            relative_detect_dir: ".adal",
+            relative_skills_dir: ".adal/skills".into(),
+            relative_project_skills_dir: ".adal/skills".into(),
+            relative_detect_dir: ".adal".into(),
         },
         ToolAdapter {
             id: ToolId::KiloCode,
-            display_name: "Kilo Code",
+            display_name: "Kilo Code".into(),
             // add-skill global path: ~/.kilocode/skills/
-            relative_skills_dir: ".kilocode/skills",
-            relative_detect_dir: ".kilocode",
+            relative_skills_dir: ".kilocode/skills".into(),
+            relative_project_skills_dir: ".kilocode/skills".into(),
+            relative_detect_dir: ".kilocode".into(),
         },
         ToolAdapter {
             id: ToolId::RooCode,
-            display_name: "Roo Code",
+            display_name: "Roo Code".into(),
             // add-skill global path: ~/.roo/skills/
-            relative_skills_dir: ".roo/skills",
-            relative_detect_dir: ".roo",
+            relative_skills_dir: ".roo/skills".into(),
+            relative_project_skills_dir: ".roo/skills".into(),
+            relative_detect_dir: ".roo".into(),
         },
         ToolAdapter {
             id: ToolId::Goose,
-            display_name: "Goose",
+            display_name: "Goose".into(),
             // add-skill global path: ~/.config/goose/skills/
-            relative_skills_dir: ".config/goose/skills",
-            relative_detect_dir: ".config/goose",
+            relative_skills_dir: ".config/goose/skills".into(),
+            relative_project_skills_dir: ".goose/skills".into(),
+            relative_detect_dir: ".config/goose".into(),
         },
         ToolAdapter {
             id: ToolId::GeminiCli,
-            display_name: "Gemini CLI",
+            display_name: "Gemini CLI".into(),
             // add-skill global path: ~/.gemini/skills/
-            relative_skills_dir: ".gemini/skills",
-            relative_detect_dir: ".gemini",
+            relative_skills_dir: ".gemini/skills".into(),
+            relative_project_skills_dir: ".gemini/skills".into(),
+            relative_detect_dir: ".gemini".into(),
         },
         ToolAdapter {
             id: ToolId::GithubCopilot,
-            display_name: "GitHub Copilot",
+            display_name: "GitHub Copilot".into(),
             // add-skill global path: ~/.copilot/skills/
-            relative_skills_dir: ".copilot/skills",
-            relative_detect_dir: ".copilot",
+            relative_skills_dir: ".copilot/skills".into(),
+            relative_project_skills_dir: ".github/skills".into(),
+            relative_detect_dir: ".copilot".into(),
         },
         ToolAdapter {
             id: ToolId::Clawdbot,
-            display_name: "Clawdbot",
+            display_name: "Clawdbot".into(),
             // add-skill global path: ~/.clawdbot/skills/
-            relative_skills_dir: ".clawdbot/skills",
-            relative_detect_dir: ".clawdbot",
+            relative_skills_dir: ".clawdbot/skills".into(),
+            relative_project_skills_dir: "skills".into(),
+            relative_detect_dir: ".clawdbot".into(),
         },
         ToolAdapter {
             id: ToolId::Droid,
-            display_name: "Droid",
+            display_name: "Droid".into(),
             // add-skill global path: ~/.factory/skills/
-            relative_skills_dir: ".factory/skills",
-            relative_detect_dir: ".factory",
+            relative_skills_dir: ".factory/skills".into(),
+            relative_project_skills_dir: ".factory/skills".into(),
+            relative_detect_dir: ".factory".into(),
         },
         ToolAdapter {
             id: ToolId::Windsurf,
-            display_name: "Windsurf",
+            display_name: "Windsurf".into(),
             // add-skill global path: ~/.codeium/windsurf/skills/
-            relative_skills_dir: ".codeium/windsurf/skills",
-            relative_detect_dir: ".codeium/windsurf",
+            relative_skills_dir: ".codeium/windsurf/skills".into(),
+            relative_project_skills_dir: ".windsurf/skills".into(),
+            relative_detect_dir: ".codeium/windsurf".into(),
         },
         ToolAdapter {
             id: ToolId::Moltbot,
-            display_name: "MoltBot",
+            display_name: "MoltBot".into(),
             // add-skill global path: ~/.moltbot/skills/
-            relative_skills_dir: ".moltbot/skills",
-            relative_detect_dir: ".moltbot",
+            relative_skills_dir: ".moltbot/skills".into(),
+            relative_project_skills_dir: "skills".into(),
+            relative_detect_dir: ".moltbot".into(),
         },
     ]
 }
 
+/// Where a skill target lives: a tool's global directory under home, or its
+/// directory inside one project root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TargetScope {
+    Global,
+    Project(PathBuf),
+}
+
+impl TargetScope {
+    pub const GLOBAL_KEY: &'static str = "global";
+
+    /// Scope stored in `skill_targets.scope`: `global` or the project root path.
+    pub fn from_key(key: &str) -> Self {
+        if key.is_empty() || key == Self::GLOBAL_KEY {
+            TargetScope::Global
+        } else {
+            TargetScope::Project(PathBuf::from(key))
+        }
+    }
+
+    pub fn as_key(&self) -> String {
+        match self {
+            TargetScope::Global => Self::GLOBAL_KEY.to_string(),
+            TargetScope::Project(root) => root.to_string_lossy().to_string(),
+        }
+    }
+}
+
+impl ToolAdapter {
+    /// Global skills directory for `home`, honoring the tool's env overrides.
+    pub fn skills_dir_in(&self, home: &Path) -> PathBuf {
+        resolve_home_path(self, home, &self.relative_skills_dir, |k| {
+            std::env::var(k).ok()
+        })
+    }
+
+    /// Install-detection directory for `home`, honoring the tool's env overrides.
+    pub fn detect_dir_in(&self, home: &Path) -> PathBuf {
+        resolve_home_path(self, home, &self.relative_detect_dir, |k| {
+            std::env::var(k).ok()
+        })
+    }
+
+    /// Skills directory relative to the scope's root (home or the project).
+    pub fn relative_dir_for(&self, scope: &TargetScope) -> &str {
+        match scope {
+            TargetScope::Global => &self.relative_skills_dir,
+            TargetScope::Project(_) => &self.relative_project_skills_dir,
+        }
+    }
+}
+
 /// Tools can share the same global skills directory (e.g. Amp and Kimi Code CLI).
 /// Use this to coordinate UI warnings and avoid duplicate filesystem operations.
 pub fn adapters_sharing_skills_dir(adapter: &ToolAdapter) -> Vec<ToolAdapter> {
+    adapters_sharing_skills_dir_in(adapter, &TargetScope::Global)
+}
+
+/// Like `adapters_sharing_skills_dir`, for the directories used in `scope`; project
+/// dirs are shared more often (e.g. Trae and Trae CN both read `.trae/skills`).
+pub fn adapters_sharing_skills_dir_in(
+    adapter: &ToolAdapter,
+    scope: &TargetScope,
+) -> Vec<ToolAdapter> {
     default_tool_adapters()
         .into_iter()
-        .filter(|a| a.relative_skills_dir == adapter.relative_skills_dir)
+        .filter(|a| a.relative_dir_for(scope) == adapter.relative_dir_for(scope))
         .collect()
 }
 
@@ -419,14 +541,59 @@ pub fn adapter_by_key(key: &str) -> Option<ToolAdapter> {
         .find(|adapter| adapter.id.as_key() == key)
 }
 
+/// Env var a tool reads to relocate its config dir, and the home-relative dir it
+/// replaces.
+fn config_dir_env(id: &ToolId) -> Option<(&'static str, &'static str)> {
+    match id {
+        ToolId::ClaudeCode => Some(("CLAUDE_CONFIG_DIR", ".claude")),
+        ToolId::Codex => Some(("CODEX_HOME", ".codex")),
+        _ => None,
+    }
+}
+
+/// `home.join(relative)`, unless the tool would look elsewhere: its own config-dir
+/// variable wins, then `$XDG_CONFIG_HOME` for dirs under `.config/`. Empty or relative
+/// values are ignored, as the XDG spec asks.
+fn resolve_home_path(
+    adapter: &ToolAdapter,
+    home: &Path,
+    relative: &str,
+    env: impl Fn(&str) -> Option<String>,
+) -> PathBuf {
+    let absolute = |key: &str| env(key).map(PathBuf::from).filter(|p| p.is_absolute());
+    let relocated = |base: &str, dir: PathBuf| {
+        Path::new(relative)
+            .strip_prefix(base)
+            .ok()
+            .map(|rest| dir.join(rest))
+    };
+    if let Some((key, base)) = config_dir_env(&adapter.id) {
+        if let Some(path) = absolute(key).and_then(|dir| relocated(base, dir)) {
+            return path;
+        }
+    }
+    if let Some(path) = absolute("XDG_CONFIG_HOME").and_then(|dir| relocated(".config", dir)) {
+        return path;
+    }
+    home.join(relative)
+}
+
 pub fn resolve_default_path(adapter: &ToolAdapter) -> Result<PathBuf> {
     let home = dirs::home_dir().context("failed to resolve home directory")?;
-    Ok(home.join(adapter.relative_skills_dir))
+    Ok(adapter.skills_dir_in(&home))
+}
+
+/// Skills directory of `adapter` in `scope`.
+pub fn resolve_scope_path(adapter: &ToolAdapter, scope: &TargetScope) -> Result<PathBuf> {
+    match scope {
+        TargetScope::Global => resolve_default_path(adapter),
+        TargetScope::Project(root) => Ok(root.join(adapter.relative_project_skills_dir.as_ref())),
+    }
 }
 
 pub fn resolve_detect_path(adapter: &ToolAdapter) -> Result<PathBuf> {
     let home = dirs::home_dir().context("failed to resolve home directory")?;
-    Ok(home.join(adapter.relative_detect_dir))
+    Ok(adapter.detect_dir_in(&home))
 }
 
 pub fn is_tool_installed(adapter: &ToolAdapter) -> Result<bool> {
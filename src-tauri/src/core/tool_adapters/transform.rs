@@ -0,0 +1,150 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use super::{custom_format, ToolAdapter, ToolId};
+use crate::core::skill_manifest::{split_frontmatter, SkillManifest};
+
+/// What a tool reads from its skills directory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SkillFormat {
+    /// A folder holding `SKILL.md` (linked or copied as-is).
+    SkillDir,
+    /// A single `<name>.md` rule with `type`/`description` frontmatter (Augment).
+    MarkdownRule,
+    /// A single `<name>.mdc` rule with `description`/`globs`/`alwaysApply` (Cursor rules).
+    Mdc,
+}
+
+impl SkillFormat {
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "skill" => Some(SkillFormat::SkillDir),
+            "rule" => Some(SkillFormat::MarkdownRule),
+            "mdc" => Some(SkillFormat::Mdc),
+            _ => None,
+        }
+    }
+
+    pub fn as_key(&self) -> &'static str {
+        match self {
+            SkillFormat::SkillDir => "skill",
+            SkillFormat::MarkdownRule => "rule",
+            SkillFormat::Mdc => "mdc",
+        }
+    }
+}
+
+/// Renders a skill folder into a tool's native format when it is synced.
+pub trait SkillTransformer: Sync {
+    /// Where the rendered file goes, given the path the skill folder would have had.
+    fn output_path(&self, target: &Path) -> PathBuf;
+    fn render(&self, skill: &SkillDocument) -> String;
+}
+
+/// The parts of a `SKILL.md` a transformer needs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SkillDocument {
+    pub name: String,
+    pub description: Option<String>,
+    /// Markdown after the frontmatter.
+    pub body: String,
+}
+
+impl SkillDocument {
+    pub fn read(skill_dir: &Path) -> Result<Self> {
+        let path = skill_dir.join("SKILL.md");
+        let text = std::fs::read_to_string(&path).with_context(|| format!("read {:?}", path))?;
+        let fallback = skill_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(Self::parse(&text, &fallback))
+    }
+
+    /// Split `text` into frontmatter fields and body; `fallback_name` is used when the
+    /// frontmatter has no `name` (or cannot be parsed).
+    pub fn parse(text: &str, fallback_name: &str) -> Self {
+        let manifest = SkillManifest::parse_unchecked(text).unwrap_or_default();
+        let body = split_frontmatter(text).map_or(text, |(_, body)| body);
+        SkillDocument {
+            name: Some(manifest.name)
+                .filter(|n| !n.is_empty())
+                .unwrap_or_else(|| fallback_name.to_string()),
+            description: manifest.description,
+            body: body.trim_start_matches(['\r', '\n']).to_string(),
+        }
+    }
+}
+
+pub struct MarkdownRule;
+
+impl SkillTransformer for MarkdownRule {
+    fn output_path(&self, target: &Path) -> PathBuf {
+        with_suffix(target, "md")
+    }
+
+    fn render(&self, skill: &SkillDocument) -> String {
+        let mut out = String::from("---\ntype: agent_requested\n");
+        if let Some(description) = &skill.description {
+            out.push_str(&format!("description: {}\n", yaml_string(description)));
+        }
+        out.push_str("---\n\n");
+        out.push_str(&skill.body);
+        out
+    }
+}
+
+pub struct Mdc;
+
+impl SkillTransformer for Mdc {
+    fn output_path(&self, target: &Path) -> PathBuf {
+        with_suffix(target, "mdc")
+    }
+
+    fn render(&self, skill: &SkillDocument) -> String {
+        let description = skill.description.as_deref().unwrap_or(&skill.name);
+        format!(
+            "---\ndescription: {}\nglobs:\nalwaysApply: false\n---\n\n{}",
+            yaml_string(description),
+            skill.body
+        )
+    }
+}
+
+pub fn skill_format(adapter: &ToolAdapter) -> SkillFormat {
+    match &adapter.id {
+        // `.augment/rules` holds markdown rules, not skill folders.
+        ToolId::Augment => SkillFormat::MarkdownRule,
+        ToolId::Custom(key) => custom_format(key).unwrap_or(SkillFormat::SkillDir),
+        _ => SkillFormat::SkillDir,
+    }
+}
+
+/// Transformer for `adapter`, or `None` when it takes skill folders as they are.
+pub fn transformer_for(adapter: &ToolAdapter) -> Option<&'static dyn SkillTransformer> {
+    match skill_format(adapter) {
+        SkillFormat::SkillDir => None,
+        SkillFormat::MarkdownRule => Some(&MarkdownRule),
+        SkillFormat::Mdc => Some(&Mdc),
+    }
+}
+
+/// `target` with `.ext` appended; skill names may contain dots, so the extension is
+/// never replaced.
+fn with_suffix(target: &Path, ext: &str) -> PathBuf {
+    let mut name = target.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(ext);
+    target.with_file_name(name)
+}
+
+/// JSON strings are valid YAML scalars, so quoting through serde_json is safe for any
+/// description.
+fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("{:?}", value))
+}
+
+#[cfg(test)]
+#[path = "../tests/transform.rs"]
+mod tests;
@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+
+use super::skill_store::SkillStore;
+use super::sync_engine::SyncMode;
+use super::tool_adapters::adapter_by_key;
+
+pub const TOOL_SYNC_MODES_KEY: &str = "tool_sync_modes";
+
+/// Modes a tool can be pinned to; `auto` is symlink, then junction, then copy.
+const CHOOSABLE_MODES: &[&str] = &["auto", "copy", "hardlink", "reflink"];
+
+/// Sync mode chosen per tool key. Tools without an entry use their default.
+pub fn get_tool_sync_modes(store: &SkillStore) -> BTreeMap<String, String> {
+    store
+        .get_setting(TOOL_SYNC_MODES_KEY)
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Choose how `tool` is synced from now on; `None` restores its default. Existing
+/// targets keep their mode until they are synced again.
+pub fn set_tool_sync_mode(
+    store: &SkillStore,
+    tool: &str,
+    mode: Option<&str>,
+) -> Result<BTreeMap<String, String>> {
+    if adapter_by_key(tool).is_none() {
+        anyhow::bail!("unknown tool: {}", tool);
+    }
+    let mut modes = get_tool_sync_modes(store);
+    match mode {
+        Some(mode) => {
+            if !CHOOSABLE_MODES.contains(&mode) {
+                anyhow::bail!(
+                    "unknown sync mode {:?} (expected auto, copy, hardlink or reflink)",
+                    mode
+                );
+            }
+            if mode == "auto" && tool == "cursor" {
+                anyhow::bail!("cursor does not follow symlinks; use copy, hardlink or reflink");
+            }
+            modes.insert(tool.to_string(), mode.to_string());
+        }
+        None => {
+            modes.remove(tool);
+        }
+    }
+    store.set_setting(TOOL_SYNC_MODES_KEY, &serde_json::to_string(&modes)?)?;
+    Ok(modes)
+}
+
+/// The mode chosen for `tool`, if any, for `sync_dir_for_tool_with_mode`.
+pub fn preferred_sync_mode(store: &SkillStore, tool: &str) -> Option<SyncMode> {
+    get_tool_sync_modes(store)
+        .get(tool)
+        .and_then(|mode| SyncMode::from_key(mode))
+}
+
+#[cfg(test)]
+#[path = "tests/tool_sync_modes.rs"]
+mod tests;
@@ -0,0 +1,285 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use super::central_repo::resolve_central_repo_path;
+use super::installer::{resync_copy_targets, SkillLock};
+use super::project_roots::get_project_roots;
+use super::skill_manifest::refresh_skill_manifest;
+use super::skill_store::SkillStore;
+use super::target_health::verify_target_paths;
+use super::tool_adapters::{
+    default_tool_adapters, is_tool_installed, resolve_scope_path, TargetScope,
+};
+
+/// Emitted with a `SkillsChanged` payload whenever the watcher acted on or noticed
+/// something the UI shows.
+pub const SKILLS_CHANGED_EVENT: &str = "skills-changed";
+
+/// Editors save in bursts (temp file, rename, chmod); wait for them to settle.
+const DEBOUNCE: Duration = Duration::from_millis(750);
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SkillsChanged {
+    /// Managed skills whose central folder was edited.
+    pub updated_skills: Vec<String>,
+    /// Tools whose copy/render targets were refreshed from the central repo.
+    pub refreshed_targets: Vec<String>,
+    /// Skill folders that appeared in a tool dir outside the app.
+    pub added: Vec<ToolDirEntry>,
+    /// Skill folders that disappeared from a tool dir.
+    pub removed: Vec<ToolDirEntry>,
+}
+
+impl SkillsChanged {
+    pub fn is_empty(&self) -> bool {
+        self.updated_skills.is_empty()
+            && self.refreshed_targets.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ToolDirEntry {
+    pub tool: String,
+    pub name: String,
+    pub path: String,
+    /// Whether the path is a recorded sync target (a removed one is now `missing`).
+    pub managed: bool,
+}
+
+/// Running watcher, kept in Tauri state so it can be restarted when the watched
+/// directories change (e.g. a new central repo path).
+#[derive(Default)]
+pub struct WatcherState(Mutex<Option<Debouncer<RecommendedWatcher>>>);
+
+/// (Re)start watching the central repo and the installed tools' skills dirs, globally
+/// and in each registered project root. Dirs that do not exist yet are picked up on the
+/// next restart (central repo or project roots changing).
+pub fn restart_watcher<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    store: &SkillStore,
+) -> Result<()> {
+    let state = app.state::<WatcherState>();
+    let mut current = state.0.lock().unwrap_or_else(|e| e.into_inner());
+    // Stop the old watcher before snapshotting, so it cannot race the new one.
+    *current = None;
+
+    let central = resolve_central_repo_path(app, store)?;
+    let scopes: Vec<TargetScope> = std::iter::once(TargetScope::Global)
+        .chain(
+            get_project_roots(store)
+                .into_iter()
+                .map(|root| TargetScope::Project(PathBuf::from(root))),
+        )
+        .collect();
+    let mut tool_dirs: Vec<(String, PathBuf)> = Vec::new();
+    for adapter in default_tool_adapters() {
+        if !is_tool_installed(&adapter).unwrap_or(false) {
+            continue;
+        }
+        for scope in &scopes {
+            let dir = resolve_scope_path(&adapter, scope)?;
+            // Tools sharing a directory are watched (and reported) once.
+            if dir.is_dir() && !tool_dirs.iter().any(|(_, d)| *d == dir) {
+                tool_dirs.push((adapter.id.as_key().to_string(), dir));
+            }
+        }
+    }
+
+    let mut tracker = ChangeTracker::new(central.clone(), tool_dirs.clone());
+    let handle = app.clone();
+    let watch_store = store.clone();
+    let mut debouncer = new_debouncer(DEBOUNCE, move |res: DebounceEventResult| match res {
+        Ok(events) => {
+            let paths: Vec<PathBuf> = events.into_iter().map(|e| e.path).collect();
+            match tracker.apply(&watch_store, &paths) {
+                Ok(change) if !change.is_empty() => {
+                    if let Err(err) = handle.emit(SKILLS_CHANGED_EVENT, change) {
+                        log::warn!("emit {} failed: {}", SKILLS_CHANGED_EVENT, err);
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => log::warn!("watcher: {:#}", err),
+            }
+        }
+        Err(err) => log::warn!("watcher error: {}", err),
+    })
+    .context("start file watcher")?;
+
+    if central.is_dir() {
+        debouncer
+            .watcher()
+            .watch(&central, RecursiveMode::Recursive)
+            .with_context(|| format!("watch {:?}", central))?;
+    }
+    for (_, dir) in &tool_dirs {
+        // Only the skill folders themselves matter, not what is inside them.
+        if let Err(err) = debouncer.watcher().watch(dir, RecursiveMode::NonRecursive) {
+            log::warn!("watch {:?} failed: {}", dir, err);
+        }
+    }
+    *current = Some(debouncer);
+    Ok(())
+}
+
+/// Turns batches of changed paths into refreshed targets and a `SkillsChanged` report.
+/// Kept apart from the watcher thread so it can be driven directly.
+pub struct ChangeTracker {
+    central: PathBuf,
+    tool_dirs: Vec<(String, PathBuf)>,
+    /// Skill folder names last seen in each tool dir.
+    known: HashMap<PathBuf, BTreeSet<String>>,
+}
+
+impl ChangeTracker {
+    pub fn new(central: PathBuf, tool_dirs: Vec<(String, PathBuf)>) -> Self {
+        let known = tool_dirs
+            .iter()
+            .map(|(_, dir)| (dir.clone(), list_skill_dirs(dir)))
+            .collect();
+        Self {
+            central,
+            tool_dirs,
+            known,
+        }
+    }
+
+    pub fn apply(&mut self, store: &SkillStore, paths: &[PathBuf]) -> Result<SkillsChanged> {
+        let mut change = SkillsChanged::default();
+        self.refresh_central(store, paths, &mut change)?;
+        self.rescan_tool_dirs(store, paths, &mut change)?;
+        Ok(change)
+    }
+
+    fn refresh_central(
+        &self,
+        store: &SkillStore,
+        paths: &[PathBuf],
+        change: &mut SkillsChanged,
+    ) -> Result<()> {
+        // The first component under the central repo is the skill folder.
+        let folders: HashSet<PathBuf> = paths
+            .iter()
+            .filter_map(|p| p.strip_prefix(&self.central).ok())
+            .filter_map(|rel| rel.components().next())
+            .map(|c| self.central.join(c))
+            .collect();
+        if folders.is_empty() {
+            return Ok(());
+        }
+        for skill in store.list_skills()? {
+            let central_path = PathBuf::from(&skill.central_path);
+            if !folders.contains(&central_path) {
+                continue;
+            }
+            // Waits out an update or rollback of this skill; its own swap lands here too.
+            let skill_lock = SkillLock::for_skill(&skill.id);
+            let _guard = skill_lock.lock();
+            if !central_path.is_dir() {
+                continue;
+            }
+            if let Err(err) = refresh_skill_manifest(store, &skill.id, &central_path) {
+                log::warn!("refresh manifest of {}: {:#}", skill.name, err);
+            }
+            match resync_copy_targets(store, &skill.id, &central_path, now_ms()) {
+                Ok(tools) => {
+                    change.updated_skills.push(skill.id.clone());
+                    change.refreshed_targets.extend(tools);
+                }
+                Err(err) => log::warn!("refresh targets of {}: {:#}", skill.name, err),
+            }
+        }
+        Ok(())
+    }
+
+    fn rescan_tool_dirs(
+        &mut self,
+        store: &SkillStore,
+        paths: &[PathBuf],
+        change: &mut SkillsChanged,
+    ) -> Result<()> {
+        let touched: Vec<(String, PathBuf)> = self
+            .tool_dirs
+            .iter()
+            .filter(|(_, dir)| {
+                paths
+                    .iter()
+                    .any(|p| p == dir || p.parent() == Some(dir.as_path()))
+            })
+            .cloned()
+            .collect();
+        if touched.is_empty() {
+            return Ok(());
+        }
+
+        let managed: HashSet<PathBuf> = store
+            .list_all_skill_target_paths()?
+            .into_iter()
+            .map(|(_, path)| PathBuf::from(path))
+            .collect();
+        let mut gone_targets = Vec::new();
+        for (tool, dir) in touched {
+            let now = list_skill_dirs(&dir);
+            let before = self
+                .known
+                .insert(dir.clone(), now.clone())
+                .unwrap_or_default();
+            let entry = |name: &String| {
+                let path = dir.join(name);
+                ToolDirEntry {
+                    tool: tool.clone(),
+                    name: name.clone(),
+                    path: path.to_string_lossy().to_string(),
+                    managed: managed.contains(&path),
+                }
+            };
+            // Folders the app synced itself are not news.
+            change
+                .added
+                .extend(now.difference(&before).map(entry).filter(|e| !e.managed));
+            for removed in before.difference(&now).map(entry) {
+                if removed.managed {
+                    gone_targets.push(PathBuf::from(&removed.path));
+                }
+                change.removed.push(removed);
+            }
+        }
+        if !gone_targets.is_empty() {
+            verify_target_paths(store, &gone_targets)?;
+        }
+        Ok(())
+    }
+}
+
+/// Names of the skill folders (or links to them) directly inside `dir`.
+fn list_skill_dirs(dir: &Path) -> BTreeSet<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return BTreeSet::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.path().is_dir() || e.file_type().is_ok_and(|t| t.is_symlink()))
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| !name.starts_with('.'))
+        .collect()
+}
+
+fn now_ms() -> i64 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    now.as_millis() as i64
+}
+
+#[cfg(test)]
+#[path = "tests/watcher.rs"]
+mod tests;
@@ -1,7 +1,9 @@
 mod commands;
 mod core;
 
+use core::installer::PendingUpdates;
 use core::skill_store::{default_db_path, migrate_legacy_db_if_needed, SkillStore};
+use core::watcher::WatcherState;
 use tauri::Manager;
 use tauri_plugin_log::{Target, TargetKind};
 
@@ -27,7 +29,32 @@ pub fn run() {
             migrate_legacy_db_if_needed(&db_path).map_err(tauri::Error::from)?;
             let store = SkillStore::new(db_path);
             store.ensure_schema().map_err(tauri::Error::from)?;
+            // Finish or undo swaps a crash interrupted before anything touches the skills.
+            match core::op_journal::recover_operations(&store) {
+                Ok(0) => {}
+                Ok(n) => log::info!("recovered {} interrupted operations", n),
+                Err(err) => log::warn!("operation journal recovery failed: {:#}", err),
+            }
+            // Updates prepared but never confirmed or aborted leave their staging dirs.
+            match core::central_repo::resolve_central_repo_path(app.handle(), &store)
+                .and_then(|central| core::installer::cleanup_update_staging_dirs(&store, &central))
+            {
+                Ok(0) => {}
+                Ok(n) => log::info!("removed {} leftover update staging dirs", n),
+                Err(err) => log::warn!("update staging cleanup failed: {:#}", err),
+            }
+            // Skills installed before manifests were kept get theirs read once.
+            if let Err(err) = core::skill_manifest::backfill_skill_manifests(&store) {
+                log::warn!("reading skill manifests failed: {:#}", err);
+            }
             app.manage(store.clone());
+            app.manage(PendingUpdates::default());
+            app.manage(WatcherState::default());
+
+            // Keep copy-mode targets in step with edits made outside the app.
+            if let Err(err) = core::watcher::restart_watcher(app.handle(), &store) {
+                log::warn!("file watcher not started: {:#}", err);
+            }
 
             // Best-effort cleanup of our own old git temp directories.
             // Safety:
@@ -70,17 +97,45 @@ pub fn run() {
             commands::set_git_cache_cleanup_days,
             commands::set_git_cache_ttl_secs,
             commands::clear_git_cache_now,
+            commands::get_forge_hosts,
+            commands::set_forge_hosts,
+            commands::get_project_roots,
+            commands::add_project_root,
+            commands::remove_project_root,
+            commands::get_tool_sync_modes,
+            commands::set_tool_sync_mode,
+            commands::get_ssh_keys,
+            commands::set_ssh_key,
+            commands::list_credentials,
+            commands::set_credential,
+            commands::delete_credential,
             commands::get_onboarding_plan,
+            commands::apply_onboarding_plan,
+            commands::compare_onboarding_variants,
             commands::install_local,
             commands::list_local_skills_cmd,
             commands::install_local_selection,
             commands::install_git,
             commands::list_git_skills_cmd,
             commands::install_git_selection,
+            commands::list_archive_skills_cmd,
+            commands::install_archive,
+            commands::plan_team_manifest,
+            commands::apply_team_manifest,
+            commands::update_team_lockfile,
             commands::sync_skill_dir,
             commands::sync_skill_to_tool,
             commands::unsync_skill_from_tool,
+            commands::verify_targets,
+            commands::repair_targets,
             commands::update_managed_skill,
+            commands::prepare_skill_update,
+            commands::confirm_skill_update,
+            commands::abort_skill_update,
+            commands::list_skill_history,
+            commands::rollback_skill,
+            commands::check_skill_updates,
+            commands::set_skill_pin,
             commands::search_github,
             commands::import_existing_skill,
             commands::get_managed_skills,
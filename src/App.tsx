@@ -229,6 +229,25 @@ function App() {
     }
   }, [isTauri, loadManagedSkills])
 
+  // The backend watcher refreshes copy targets on disk; reload to show the new state.
+  useEffect(() => {
+    if (!isTauri) return
+    let unlisten: (() => void) | null = null
+    let cancelled = false
+    void import('@tauri-apps/api/event').then(({ listen }) =>
+      listen('skills-changed', () => {
+        void loadManagedSkills()
+      }).then((fn) => {
+        if (cancelled) fn()
+        else unlisten = fn
+      }),
+    )
+    return () => {
+      cancelled = true
+      unlisten?.()
+    }
+  }, [isTauri, loadManagedSkills])
+
   useEffect(() => {
     if (typeof window === 'undefined') return
     const stored = window.localStorage.getItem(themeStorageKey)
@@ -686,6 +705,12 @@ function App() {
         if (status.newly_installed.length > 0) {
           setShowNewToolsModal(true)
         }
+        if (status.custom_tools_error) {
+          toast.error(
+            t('errors.customToolsInvalid', { error: status.custom_tools_error }),
+            { duration: 6000 },
+          )
+        }
       } catch (err) {
         // Non-fatal; app can still work without detection.
         console.warn(err)
@@ -50,6 +50,9 @@ const SkillsList = ({
               <div className="banner-title">{t('discoveredTitle')}</div>
               <div className="banner-subtitle">
                 {t('discoveredCount', { count: plan.total_skills_found })}
+                {plan.new_since_last_scan > 0
+                  ? ` ${t('discoveredNewCount', { count: plan.new_since_last_scan })}`
+                  : null}
               </div>
             </div>
           </div>
@@ -5,6 +5,7 @@ export type OnboardingVariant = {
   fingerprint?: string | null
   is_link: boolean
   link_target?: string | null
+  is_new: boolean
 }
 
 export type OnboardingGroup = {
@@ -16,9 +17,50 @@ export type OnboardingGroup = {
 export type OnboardingPlan = {
   total_tools_scanned: number
   total_skills_found: number
+  new_since_last_scan: number
   groups: OnboardingGroup[]
 }
 
+export type OnboardingDecision =
+  | { action: 'pick'; group: string; variant_path: string; name?: string | null }
+  | { action: 'keep_both'; group: string; variants: { path: string; name: string }[] }
+  | { action: 'skip'; group: string }
+
+export type OnboardingGroupResult = {
+  group: string
+  imported: { skill_id: string; name: string; central_path: string }[]
+  linked: {
+    skill_id: string
+    tool: string
+    path: string
+    mode: string
+    snapshot_id: string | null
+  }[]
+  error?: string | null
+}
+
+export type OnboardingApplyReport = {
+  groups: OnboardingGroupResult[]
+}
+
+export type FileChange = {
+  path: string
+  kind: 'added' | 'removed' | 'modified'
+  binary: boolean
+  unified_diff?: string | null
+}
+
+export type VariantComparison = {
+  group: string
+  variants: {
+    path: string
+    tools: string[]
+    fingerprint?: string | null
+    diff: { files: FileChange[] }
+  }[]
+  files: { path: string; present_in: number[]; content_groups: number[][] }[]
+}
+
 export type ToolOption = {
   id: string
   label: string
@@ -36,17 +78,39 @@ export type ManagedSkill = {
   status: string
   targets: {
     tool: string
+    scope: string
     mode: string
     status: string
+    last_error?: string | null
     target_path: string
     synced_at?: number | null
   }[]
+  manifest?: SkillManifest | null
+}
+
+export type SkillManifest = {
+  name: string
+  description?: string | null
+  version?: string | null
+  license?: string | null
+  allowed_tools: string[]
+  metadata: Record<string, unknown>
+  author?: string | null
+}
+
+export type ManifestError = {
+  reason: string
+  message: string
+  line?: number | null
+  column?: number | null
 }
 
 export type GitSkillCandidate = {
   name: string
   description?: string | null
   subpath: string
+  root: string
+  depth: number
 }
 
 export type LocalSkillCandidate = {
@@ -55,6 +119,9 @@ export type LocalSkillCandidate = {
   subpath: string
   valid: boolean
   reason?: string | null
+  error?: ManifestError | null
+  root: string
+  depth: number
 }
 
 export type InstallResultDto = {
@@ -69,12 +136,15 @@ export type ToolInfoDto = {
   label: string
   installed: boolean
   skills_dir: string
+  format: 'skill' | 'rule' | 'mdc'
 }
 
 export type ToolStatusDto = {
   tools: ToolInfoDto[]
   installed: string[]
   newly_installed: string[]
+  custom_tools_path?: string | null
+  custom_tools_error?: string | null
 }
 
 export type UpdateResultDto = {
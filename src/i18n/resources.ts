@@ -31,6 +31,7 @@ export const resources = {
       discoveredTitle: 'Discovered skills',
       discoveredEmpty: 'Scan your tools to find existing skills to import.',
       discoveredCount: 'Found {{count}} skills ready for review.',
+      discoveredNewCount: '{{count}} new since the last scan.',
       reviewImport: 'Review & Import',
       scanNow: 'Scan now',
       skillsTitle: 'Managed skills',
@@ -165,6 +166,7 @@ export const resources = {
         importFailedTitle: 'Import failed: {{name}}',
         unsyncedTitle: 'Not synced: {{name}}',
         moreCount: ' (+{{count}} more)',
+        customToolsInvalid: 'Custom tools in tools.toml were not loaded:\n{{error}}',
       },
       actions: {
         importExisting: 'Import {{name}} ...',
@@ -294,6 +296,7 @@ export const resources = {
       discoveredTitle: '发现的 Skills',
       discoveredEmpty: '扫描工具以导入已存在的 Skills。',
       discoveredCount: '发现 {{count}} 个可导入 Skills。',
+      discoveredNewCount: '其中 {{count}} 个是上次扫描后新出现的。',
       reviewImport: '查看并导入',
       scanNow: '立即扫描',
       skillsTitle: '托管中的 Skills',
@@ -419,6 +422,7 @@ export const resources = {
         importFailedTitle: '导入失败：{{name}}',
         unsyncedTitle: '未同步：{{name}}',
         moreCount: '（另有{{count}}个）',
+        customToolsInvalid: 'tools.toml 中的自定义工具未加载：\n{{error}}',
       },
       actions: {
         importExisting: '导入 {{name}} ...',